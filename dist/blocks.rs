@@ -0,0 +1,95 @@
+//! Extraction of block maxima (e.g. annual maxima) from a series, the data to which the GEV
+//! distribution is fitted.
+
+/// Maxima of the consecutive blocks of `block_size` observations of the data. The last block is
+/// dropped if it is incomplete, as its maximum does not have the same distribution.
+pub fn block_maxima(data: &[f64], block_size: usize) -> Vec<f64> {
+    domain!(block_size > 0);
+    data.chunks_exact(block_size)
+        .map(|block| block.iter().cloned().fold(f64::NEG_INFINITY, f64::max))
+        .collect()
+}
+
+/// Online block maxima, for streams that are too long to be buffered: the values are pushed one
+/// at a time with `push` and the end of each block (e.g. of each year) is signalled by
+/// `block_boundary`, which emits the maximum of the block. The blocks thus do not need to have
+/// the same number of observations. NaN values are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BlockMaxAccumulator {
+    /// maximum of the current block, `None` if no value has been pushed since the last boundary
+    current: Option<f64>,
+    /// number of completed (non-empty) blocks
+    blocks: usize,
+}
+
+impl BlockMaxAccumulator {
+    /// Create an accumulator with an empty current block.
+    pub fn new() -> Self {
+        BlockMaxAccumulator::default()
+    }
+
+    /// Add a value to the current block.
+    pub fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            return;
+        }
+        self.current = Some(self.current.map_or(x, |max| max.max(x)));
+    }
+
+    /// Close the current block and return its maximum, or `None` if no value was pushed to it.
+    pub fn block_boundary(&mut self) -> Option<f64> {
+        let max: Option<f64> = self.current.take();
+        if max.is_some() {
+            self.blocks += 1;
+        }
+        max
+    }
+
+    /// Maximum of the current (not yet closed) block, if any.
+    pub fn current_max(&self) -> Option<f64> {
+        self.current
+    }
+
+    /// Number of blocks closed with at least one value.
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_maxima_test() {
+        let data: Vec<f64> = vec![1.0, 5.0, 2.0, 3.0, 0.5, 4.0, 7.0];
+        assert_eq!(block_maxima(&data, 3), vec![5.0, 4.0]);
+        assert_eq!(block_maxima(&data, 1), data);
+        assert!(block_maxima(&data, 8).is_empty());
+    }
+
+    #[test]
+    fn block_max_accumulator_test() {
+        let data: Vec<f64> = (0..100).map(|i| ((i * 37) % 101) as f64).collect();
+        let mut acc: BlockMaxAccumulator = BlockMaxAccumulator::new();
+        let mut maxima: Vec<f64> = Vec::new();
+        for (i, &x) in data.iter().enumerate() {
+            acc.push(x);
+            if (i + 1) % 10 == 0 {
+                maxima.extend(acc.block_boundary());
+            }
+        }
+        assert_eq!(maxima, block_maxima(&data, 10));
+        assert_eq!(acc.blocks(), 10);
+
+        // empty blocks emit nothing and NaNs are ignored
+        assert_eq!(acc.block_boundary(), None);
+        acc.push(f64::NAN);
+        assert_eq!(acc.current_max(), None);
+        acc.push(-2.0);
+        acc.push(f64::NAN);
+        assert_eq!(acc.block_boundary(), Some(-2.0));
+        assert_eq!(acc.blocks(), 11);
+    }
+}
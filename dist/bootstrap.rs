@@ -0,0 +1,130 @@
+//! Bootstrap tools, to assess the uncertainty of the statistics (e.g. fitted parameters or
+//! return levels) computed from a sample.
+use crate::dist::distutils::RandomSeed;
+
+use rand_chacha::ChaCha8Rng;
+use rand::Rng;
+
+/// Bias correction applied to an estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BiasCorrection {
+    /// No correction, the plug-in estimate is returned.
+    None,
+    /// Bootstrap bias correction with `reps` resamples drawn with the given `seed`.
+    Bootstrap { reps: usize, seed: u64 },
+}
+
+/// Ordinary (i.i.d.) nonparametric bootstrap: draw `reps` resamples of the data with replacement
+/// (each of the same size as the data) and compute `stat` on each of them.
+/// Replicates where `stat` returns NaN (e.g. a failed fit) are kept, so the caller can filter them.
+pub fn bootstrap<F: Fn(&[f64]) -> f64>(data: &[f64], reps: usize, seed: RandomSeed, stat: F) -> Vec<f64> {
+    domain!(!data.is_empty());
+    let mut rng: ChaCha8Rng = seed.rng();
+    let mut resample: Vec<f64> = vec![0.0; data.len()];
+    (0..reps)
+        .map(|_| {
+            for val in resample.iter_mut() {
+                *val = data[rng.gen_range(0..data.len())];
+            }
+            stat(&resample)
+        })
+        .collect()
+}
+
+/// Block bootstrap for dependent (e.g. autocorrelated) data: each resample, of the same size as the
+/// data, is made of contiguous blocks of `block_len` observations starting at random positions, which
+/// preserves the dependence within the blocks, and `stat` is computed on each of the `reps` resamples.
+/// This is the circular block bootstrap: the data is wrapped around, so a block starting near the end
+/// continues at the start and every observation is equally likely to be drawn (the non-circular
+/// moving block bootstrap underweights both ends of the series). The last block is truncated to fit.
+/// With `block_len = 1` this is the ordinary bootstrap (`bootstrap`), draw for draw.
+pub fn block_bootstrap<F: Fn(&[f64]) -> f64>(data: &[f64], block_len: usize, reps: usize, seed: RandomSeed, stat: F) -> Vec<f64> {
+    domain!(!data.is_empty() && block_len > 0);
+    let n: usize = data.len();
+    let mut rng: ChaCha8Rng = seed.rng();
+    let mut resample: Vec<f64> = vec![0.0; n];
+    (0..reps)
+        .map(|_| {
+            for block in resample.chunks_mut(block_len) {
+                let start: usize = rng.gen_range(0..n);
+                for (j, val) in block.iter_mut().enumerate() {
+                    *val = data[(start + j) % n];
+                }
+            }
+            stat(&resample)
+        })
+        .collect()
+}
+
+/// Leave-one-out jackknife: `stat` is recomputed on each of the $n$ subsamples $x_{(-i)}$ leaving out
+/// one observation, giving $\hat\theta_{(i)}$ with mean $\hat\theta_{(\cdot)}$. Returns the bias
+/// corrected jackknife estimate and the jackknife standard error,
+/// $$ n \hat\theta - (n - 1) \hat\theta_{(\cdot)}, \quad \sqrt{\frac{n - 1}{n} \sum_{i=1}^{n} \left( \hat\theta_{(i)} - \hat\theta_{(\cdot)} \right)^2}, $$
+/// where $\hat\theta$ is `stat` on the full data. Deterministic, unlike the bootstrap, and needs
+/// at least 2 observations.
+pub fn jackknife<F: Fn(&[f64]) -> f64>(data: &[f64], stat: F) -> (f64, f64) {
+    domain!(data.len() >= 2);
+    let n: usize = data.len();
+    let mut subsample: Vec<f64> = Vec::with_capacity(n - 1);
+    let leave_one_out: Vec<f64> = (0..n)
+        .map(|i| {
+            subsample.clear();
+            subsample.extend_from_slice(&data[..i]);
+            subsample.extend_from_slice(&data[(i + 1)..]);
+            stat(&subsample)
+        })
+        .collect();
+    let n_f: f64 = n as f64;
+    let loo_mean: f64 = leave_one_out.iter().sum::<f64>() / n_f;
+    let sum_sq: f64 = leave_one_out.iter().map(|t| (t - loo_mean) * (t - loo_mean)).sum::<f64>();
+    (n_f * stat(data) - (n_f - 1.0) * loo_mean, ((n_f - 1.0) / n_f * sum_sq).sqrt())
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bootstrap_test() {
+        let data: Vec<f64> = (0..50).map(|i| i as f64).collect();
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+        let reps: Vec<f64> = bootstrap(&data, 200, RandomSeed::Seed(1), mean);
+        assert_eq!(reps.len(), 200);
+        assert_eq!(reps, bootstrap(&data, 200, RandomSeed::Seed(1), mean));
+        // the bootstrap means are centered around the sample mean
+        assert!((mean(&reps) - 24.5).abs() < 1.0);
+    }
+
+    #[test]
+    fn block_bootstrap_test() {
+        let data: Vec<f64> = (0..50).map(|i| i as f64).collect();
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+        // blocks of length 1 are the ordinary bootstrap
+        assert_eq!(block_bootstrap(&data, 1, 100, RandomSeed::Seed(3), mean), bootstrap(&data, 100, RandomSeed::Seed(3), mean));
+
+        // the resamples are made of (wrapped around) runs of consecutive values
+        let first = |x: &[f64]| x[1] - x[0];
+        let steps: Vec<f64> = block_bootstrap(&data, 5, 100, RandomSeed::Seed(3), first);
+        assert!(steps.iter().all(|&s| s == 1.0 || s == -49.0));
+        let reps: Vec<f64> = block_bootstrap(&data, 7, 200, RandomSeed::Seed(3), mean);
+        assert_eq!(reps.len(), 200);
+        assert!((mean(&reps) - 24.5).abs() < 1.0);
+    }
+
+    #[test]
+    fn jackknife_test() {
+        let data: Vec<f64> = (0..40).map(|i| ((i * 7) % 13) as f64 + 0.1 * i as f64).collect();
+        let n: f64 = data.len() as f64;
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+        let m: f64 = mean(&data);
+        let s: f64 = (data.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / (n - 1.0)).sqrt();
+        let (estimate, se) = jackknife(&data, mean);
+        // the mean is unbiased and its jackknife standard error is s / sqrt(n)
+        assert!((estimate - m).abs() < 1e-12);
+        assert!((se - s / n.sqrt()).abs() < 1e-12);
+        // the jackknife removes the bias of the plug-in variance
+        let plug_in = |x: &[f64]| { let mu: f64 = mean(x); x.iter().map(|v| (v - mu) * (v - mu)).sum::<f64>() / x.len() as f64 };
+        assert!((jackknife(&data, plug_in).0 - s * s).abs() < 1e-10);
+    }
+}
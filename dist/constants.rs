@@ -0,0 +1,39 @@
+//! Mathematical constants shared by the moments, cumulants and entropies of the distributions,
+//! defined once so that the distributions do not redefine them.
+
+/// Euler-Mascheroni constant $\gamma = - \Gamma'(1)$, the mean of the standard Gumbel distribution
+/// (OEIS A001620).
+pub const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// Apéry's constant $\zeta(3) = \sum_{k \geq 1} k^{-3}$, in the skewness of the Gumbel distribution
+/// (OEIS A002117).
+pub const ZETA_3: f64 = 1.202_056_903_159_594_3;
+
+/// $\zeta(2) = \frac{\pi^2}{6}$, the variance of the standard Gumbel distribution (OEIS A013661).
+pub const PI_SQUARED_OVER_6: f64 = 1.644_934_066_848_226_4;
+
+/// $\frac{\pi^4}{15} = 6 \zeta(4)$, the fourth cumulant of the standard Gumbel distribution
+/// (from OEIS A013662).
+pub const PI_FOURTH_OVER_15: f64 = 6.493_939_402_266_829;
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn constants_test() {
+        // high-precision literals, rounded to the nearest f64 when parsed
+        let literal = |digits: &str| digits.parse::<f64>().unwrap();
+        assert_eq!(EULER_GAMMA, literal("0.57721566490153286060651209008240243"));
+        assert_eq!(ZETA_3, literal("1.2020569031595942853997381615114499"));
+        assert_eq!(PI_SQUARED_OVER_6, literal("1.6449340668482264364724151666460252"));
+        assert_eq!(PI_FOURTH_OVER_15, literal("6.4939394022668291490960221792470074"));
+        // consistent with the series and with pi
+        let zeta = |s: i32| (1..200_000).map(|k| (k as f64).powi(-s)).sum::<f64>();
+        assert!((ZETA_3 - zeta(3)).abs() < 1e-10);
+        assert!((PI_SQUARED_OVER_6 - PI * PI / 6.0).abs() <= f64::EPSILON);
+        assert!((PI_FOURTH_OVER_15 - PI.powi(4) / 15.0).abs() <= 8.0 * f64::EPSILON);
+    }
+}
@@ -1,16 +1,42 @@
 //! Functions used for the distributions.
+use std::fmt;
+
+use libm::{exp, expm1, log, log1p};
+
+use crate::dist::blocks::block_maxima;
+
+use rand_chacha::ChaCha8Rng;
+use rand::SeedableRng;
+use rand::Rng;
 
 /// macro used to ensure that the given domain is valid.
+/// By default the checks are `debug_assert!`s, so they are compiled out of release builds for speed.
+/// With the `strict-domain` feature they are always checked (`assert!`), whatever the build profile,
+/// at the cost of a comparison per call.
 #[macro_use]
 pub mod macros {
     macro_rules! domain( // should you consider an error enum instead? What is faster? More understandable? One variant for param and one for quantile domain
-        ($requirement:expr) => (debug_assert!($requirement));
-        ($requirement:expr, $code:expr) => (debug_assert!($code, stringify!($requirement)));
+        ($requirement:expr) => ({
+            #[cfg(feature = "strict-domain")]
+            assert!($requirement);
+            #[cfg(not(feature = "strict-domain"))]
+            debug_assert!($requirement);
+        });
+        ($requirement:expr, $code:expr) => ({
+            #[cfg(feature = "strict-domain")]
+            assert!($code, stringify!($requirement));
+            #[cfg(not(feature = "strict-domain"))]
+            debug_assert!($code, stringify!($requirement));
+        });
     );
 }
 
 /// Distributional Quantity trait (i.e. each distribution will provide each of the following
 /// quantities: the CDF, PDF, Quantile and random generation)
+///
+/// The trait is object safe, so heterogeneous collections such as `Vec<Box<dyn DistQuant>>` can be
+/// used: generic methods (taking a user provided random number generator, ...) are restricted
+/// to `Self: Sized` and are thus only available on the concrete distribution types.
 pub trait DistQuant {
     /// Cumulative Distribution Function (CDF)
     fn cdf(&self, x: f64) -> f64;
@@ -20,6 +46,633 @@ pub trait DistQuant {
     fn quantile(&self, x: f64) -> f64;
     /// Generate a random value from the distribution
     fn random(&self, seed: RandomSeed) -> f64;
+
+    /// Support of the distribution as `(lower, upper)` end points, possibly infinite.
+    /// The default is the whole real line, distributions with a bounded support override it.
+    fn support(&self) -> (f64, f64) {
+        (f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    /// Whether `x` lies (strictly) inside the support of the distribution, see `support`.
+    fn is_in_support(&self, x: f64) -> bool {
+        let (lower, upper) = self.support();
+        lower < x && x < upper
+    }
+
+    /// Quantile function tolerant to probabilities slightly outside of $[0, 1]$ (e.g. `1.0000001`
+    /// coming from floating point error in an optimizer): the probability is clamped into $[0, 1]$,
+    /// and the end points of the support are returned at the boundaries.
+    /// Use `quantile` (which checks its domain) unless the probability comes from such a noisy source.
+    fn quantile_clamped(&self, p: f64) -> f64 {
+        if p <= 0.0 {
+            self.support().0
+        } else if p >= 1.0 {
+            self.support().1
+        } else {
+            self.quantile(p)
+        }
+    }
+
+    /// Numerical quantile, solving $F(x) = p$ for distributions without a closed form quantile
+    /// function. The root is first bracketed with the end points of `support`, an infinite end
+    /// point being replaced by a bracket grown by doubling steps until it contains the root; it is
+    /// then refined by Newton's method with the `pdf` as derivative, falling back to bisection
+    /// whenever a Newton step leaves the bracket. The end points of the support are returned for
+    /// $p = 0$ and $p = 1$. Close to $p = 1$ the accuracy is limited by the resolution of the CDF
+    /// values near 1, so the closed form quantiles should be preferred when available.
+    fn quantile_numeric(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        let (lower, upper) = self.support();
+        if p == 0.0 {
+            return lower;
+        }
+        if p == 1.0 {
+            return upper;
+        }
+        // bracket [lo, hi] with F(lo) < p <= F(hi)
+        let (mut lo, mut hi) = match (lower.is_finite(), upper.is_finite()) {
+            (true, true) => (lower, upper),
+            (true, false) => (lower, lower + 1.0),
+            (false, true) => (upper - 1.0, upper),
+            (false, false) => (-1.0, 1.0),
+        };
+        let mut width: f64 = 1.0;
+        while !upper.is_finite() && self.cdf(hi) < p {
+            lo = hi;
+            width *= 2.0;
+            hi += width;
+        }
+        width = 1.0;
+        while !lower.is_finite() && self.cdf(lo) >= p {
+            hi = lo;
+            width *= 2.0;
+            lo -= width;
+        }
+
+        let mut x: f64 = 0.5 * (lo + hi);
+        for _ in 0..200 {
+            let diff: f64 = self.cdf(x) - p;
+            if diff == 0.0 {
+                return x;
+            }
+            if diff < 0.0 { lo = x; } else { hi = x; }
+            if hi - lo <= f64::EPSILON * x.abs().max(f64::MIN_POSITIVE) {
+                break;
+            }
+            let newton: f64 = x - diff / self.pdf(x);
+            x = if lo < newton && newton < hi { newton } else { 0.5 * (lo + hi) };
+        }
+        x
+    }
+
+    /// Density quantile function $\frac{dQ}{dp} = \frac{1}{f(Q(p))}$, the derivative of the quantile
+    /// function, e.g. for the asymptotic variance $\frac{p (1 - p)}{n} Q'(p)^2$ of a sample quantile.
+    /// Infinite where the density vanishes (e.g. at an end point of the support) rather than dividing by 0.
+    fn quantile_density(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        let pdf: f64 = self.pdf(self.quantile(p));
+        if pdf > 0.0 { 1.0 / pdf } else { f64::INFINITY }
+    }
+
+    /// Generate a random value from the distribution with the given random number generator
+    /// (inverse transform sampling through the quantile function).
+    /// Only available on concrete types, as it is generic.
+    fn random_with<R: Rng>(&self, mut rng: R) -> f64 where Self: Sized {
+        self.quantile(rng.gen::<f64>())
+    }
+
+    /// Generate a random value with the caller's random number generator, which is advanced
+    /// (by one draw for inverse transform sampling) rather than consumed, so that the caller
+    /// keeps control of its state across the steps of a simulation, unlike the seeded `random`
+    /// which always starts from a fresh generator. Uses `random_with`, so the draw is the one of
+    /// `random_with(&mut *rng)`. Only available on concrete types, as it is generic.
+    fn random_with_rng<R: Rng>(&self, rng: &mut R) -> f64 where Self: Sized {
+        self.random_with(rng)
+    }
+
+    /// Generate a deterministic random value from the ChaCha8 generator keyed by `seed` and
+    /// positioned on the independent stream `stream` (`ChaCha8Rng::seed_from_u64(seed)` followed
+    /// by `set_stream(stream)`, i.e. `stream` is the ChaCha nonce). Different streams of the same
+    /// seed give independent sequences, so that parallel workers can share one seed instead of
+    /// deriving child seeds.
+    fn random_stream(&self, seed: u64, stream: u64) -> f64 {
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_stream(stream);
+        self.quantile(rng.gen::<f64>())
+    }
+
+    /// Checked PDF, for parameters or inputs that cannot be trusted: returns
+    /// `EvalError::OutOfSupport` if `x` is not in the support (see `is_in_support`) and
+    /// `EvalError::NonFinite` if the density evaluates to NaN or an infinite value (e.g. an
+    /// overflow with extreme parameters), where `pdf` would silently return it.
+    fn try_pdf(&self, x: f64) -> Result<f64, EvalError> {
+        if !self.is_in_support(x) {
+            return Err(EvalError::OutOfSupport);
+        }
+        let val: f64 = self.pdf(x);
+        if val.is_finite() { Ok(val) } else { Err(EvalError::NonFinite) }
+    }
+
+    /// Checked CDF, with the same checks as `try_pdf`.
+    fn try_cdf(&self, x: f64) -> Result<f64, EvalError> {
+        if !self.is_in_support(x) {
+            return Err(EvalError::OutOfSupport);
+        }
+        let val: f64 = self.cdf(x);
+        if val.is_finite() { Ok(val) } else { Err(EvalError::NonFinite) }
+    }
+
+    /// PDF and CDF at the same point, `(pdf(x), cdf(x))`, e.g. for diagnostics showing both.
+    /// Distributions override it to share the computation of the reduced variate (and of the
+    /// costly `exp`/`pow` calls) between the two.
+    fn evaluate(&self, x: f64) -> (f64, f64) {
+        (self.pdf(x), self.cdf(x))
+    }
+
+    /// PDF evaluated at each of the given points (e.g. the bin centers of a histogram, see `viz::histogram`).
+    fn pdf_over(&self, xs: &[f64]) -> Vec<f64> {
+        xs.iter().map(|&x| self.pdf(x)).collect()
+    }
+
+    /// Survival function $S(x) = 1 - F(x)$, the exceedance probability of $x$.
+    /// Distributions override it with a form that stays accurate in the upper tail, where
+    /// $1 - F(x)$ loses all its digits to cancellation.
+    fn sf(&self, x: f64) -> f64 {
+        1.0 - self.cdf(x)
+    }
+
+    /// Inverse survival function, the value exceeded with probability `p`: $S^{-1}(p) = F^{-1}(1 - p)$.
+    /// Distributions override it with a form computed from $p$ directly, as forming $1 - p$ loses the
+    /// digits of a small exceedance probability.
+    fn isf(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        self.quantile(1.0 - p)
+    }
+
+    /// Return level for the given return period $T$ (in blocks), computed with `isf(1 / T)` rather than
+    /// `quantile(1 - 1 / T)`, so it stays accurate for very long return periods (e.g. $T = 10^5$ years).
+    /// The return period must be larger than 1.
+    fn return_level_precise(&self, return_period: f64) -> f64 {
+        domain!(return_period > 1.0);
+        self.isf(1.0 / return_period)
+    }
+
+    /// Logarithm of the CDF, $\log F(x)$. Distributions override it with a form that does not
+    /// underflow in the lower tail, where $F(x)$ rounds to 0.
+    fn log_cdf(&self, x: f64) -> f64 {
+        log(self.cdf(x))
+    }
+
+    /// Logarithm of the survival function, $\log S(x)$. Distributions override it with a form that
+    /// does not underflow in the upper tail, where $S(x)$ rounds to 0.
+    fn log_sf(&self, x: f64) -> f64 {
+        log(self.sf(x))
+    }
+
+    /// Ratio of the survival functions $\frac{S_{self}(x)}{S_{other}(x)}$, i.e. how much more
+    /// likely `self` is to exceed the level $x$ than `other`: a ratio growing with $x$ means that
+    /// `self` has the heavier tail. Computed as $\exp(\log S_{self}(x) - \log S_{other}(x))$ with
+    /// `log_sf`, so that it stays defined far in the tails where both survival functions round to 0
+    /// (it is then 0 or infinite rather than NaN).
+    /// Only available on concrete types, as it is generic.
+    fn tail_ratio<D: DistQuant + ?Sized>(&self, other: &D, x: f64) -> f64 where Self: Sized {
+        exp(self.log_sf(x) - other.log_sf(x))
+    }
+
+    /// Reduced variate of the Gumbel probability paper, $- \log(- \log F(x))$, computed from
+    /// `log_cdf` so that it stays finite deep in the lower tail, where $F(x)$ underflows.
+    /// It is linear in $x$ exactly for the Gumbel distribution (and the GEV with $shape = 0$),
+    /// $\frac{x - loc}{scale}$. For the GEV it is $\frac{\log(1 + shape \frac{x - loc}{scale})}{shape}$,
+    /// concave for $shape > 0$ (e.g. the Fréchet) and convex for $shape < 0$ (e.g. the Weibull),
+    /// which is how a plot of the data on this axis reveals the sign of the shape.
+    fn gumbel_reduced(&self, x: f64) -> f64 {
+        - log(- self.log_cdf(x))
+    }
+
+    /// Hazard (failure) rate $h(x) = \frac{f(x)}{S(x)}$, the density of an event at $x$
+    /// given that none happened below $x$.
+    fn hazard(&self, x: f64) -> f64 {
+        self.pdf(x) / self.sf(x)
+    }
+
+    /// Cumulative hazard $H(x) = - \log(1 - F(x))$, the integral of the hazard rate up to $x$.
+    fn cumulative_hazard(&self, x: f64) -> f64 {
+        - log1p(- self.cdf(x))
+    }
+
+    /// Hazard rate evaluated at each of the given points, e.g. to draw a hazard plot.
+    fn hazard_over(&self, xs: &[f64]) -> Vec<f64> {
+        xs.iter().map(|&x| self.hazard(x)).collect()
+    }
+
+    /// Cumulative hazard evaluated at each of the given points.
+    fn cumulative_hazard_over(&self, xs: &[f64]) -> Vec<f64> {
+        xs.iter().map(|&x| self.cumulative_hazard(x)).collect()
+    }
+
+    /// Conditional CDF given an exceedance of the threshold $u$, i.e. $P(X \leq x \mid X > u)$:
+    /// $$ F_u(x) = \frac{F(x) - F(u)}{1 - F(u)}, \quad x \geq u. $$
+    /// This is the distribution of the exceedances modelled by the generalized Pareto distribution
+    /// in the peaks-over-threshold approach.
+    fn conditional_cdf(&self, x: f64, threshold: f64) -> f64 {
+        domain!(x >= threshold);
+        let cdf_u: f64 = self.cdf(threshold);
+        (self.cdf(x) - cdf_u) / (1.0 - cdf_u)
+    }
+
+    /// Percentile of the value `x` within the distribution, i.e. $100 \cdot F(x)$.
+    fn percentile(&self, x: f64) -> f64 {
+        100.0 * self.cdf(x)
+    }
+
+    /// Integral of the PDF over $[lo, hi]$ with the composite Simpson's rule on `n` sub-intervals
+    /// (rounded up to an even number). Infinite bounds are replaced by the quantiles at
+    /// probability `INTEGRATION_TAIL_PROB` (resp. one minus it), so that
+    /// `integrate_pdf(f64::NEG_INFINITY, f64::INFINITY, n)` $\approx 1$ and
+    /// `integrate_pdf(f64::NEG_INFINITY, x, n)` $\approx F(x)$.
+    /// Finite bounds must lie within the support of the distribution.
+    fn integrate_pdf(&self, lo: f64, hi: f64, n: usize) -> f64 {
+        let (lo, hi) = integration_bounds(self, lo, hi);
+        simpson(|x| self.pdf(x), lo, hi, n)
+    }
+
+    /// Integral of the PDF over $[lo, hi]$ with the adaptive Simpson's rule configured by `config`
+    /// (see `adaptive_simpson`), infinite bounds being handled as in `integrate_pdf`.
+    fn integrate_pdf_with(&self, lo: f64, hi: f64, config: Integration) -> f64 {
+        let (lo, hi) = integration_bounds(self, lo, hi);
+        adaptive_simpson(|x| self.pdf(x), lo, hi, config).0
+    }
+
+    /// Expectation $E[g(X)] = \int g(x) f(x) dx$ over the support, with the adaptive Simpson's rule
+    /// configured by `config` (see `adaptive_simpson`). The infinite end points of the support are
+    /// replaced as in `integrate_pdf`, so the truncated tails must be negligible for $g$, and the
+    /// result is only as accurate as the tolerance allows if the evaluation budget was exhausted.
+    fn expect<F: Fn(f64) -> f64>(&self, g: F, config: Integration) -> f64 where Self: Sized {
+        let (lo, hi) = integration_bounds(self, f64::NEG_INFINITY, f64::INFINITY);
+        adaptive_simpson(|x| g(x) * self.pdf(x), lo, hi, config).0
+    }
+
+    /// Kullback-Leibler divergence $KL(f \| g) = \int f(x) \log \frac{f(x)}{g(x)} dx$ of `other`
+    /// (density $g$) from this distribution (density $f$), integrated over the support of this
+    /// distribution as in `expect`. It is infinite if the support of `other` does not cover it.
+    fn kl_divergence(&self, other: &dyn DistQuant, config: Integration) -> f64 {
+        let ((lower, upper), (other_lower, other_upper)) = (self.support(), other.support());
+        if other_lower > lower || other_upper < upper {
+            return f64::INFINITY;
+        }
+        let (lo, hi) = integration_bounds(self, f64::NEG_INFINITY, f64::INFINITY);
+        let integrand = |x: f64| -> f64 {
+            let dens: f64 = self.pdf(x);
+            if dens == 0.0 { 0.0 } else { dens * log(dens / other.pdf(x)) }
+        };
+        adaptive_simpson(integrand, lo, hi, config).0
+    }
+
+    /// Sanity check of the numerics for the current parameters, with the absolute tolerance `tol`:
+    /// - `cdf(quantile(p))` $\approx p$ for $p$ from 0.001 to 0.999,
+    /// - the CDF is non-decreasing and the PDF non-negative on a grid of 101 points between these quantiles,
+    /// - the PDF integrates to 1 over the support (`integrate_pdf` with 5000 sub-intervals between
+    ///   each of the above quantiles, so the tolerance must allow for the quadrature error, e.g. for
+    ///   unbounded densities).
+    ///
+    /// Returns the first failed check.
+    fn self_check(&self, tol: f64) -> Result<(), SelfCheckError> {
+        let probs: [f64; 13] = [0.001, 0.01, 0.05, 0.1, 0.2, 0.35, 0.5, 0.65, 0.8, 0.9, 0.95, 0.99, 0.999];
+        for p in probs {
+            let err: f64 = (self.cdf(self.quantile(p)) - p).abs();
+            if err.is_nan() || err > tol {
+                return Err(SelfCheckError::QuantileMismatch { p });
+            }
+        }
+        let (lo, hi) = (self.quantile(0.001), self.quantile(0.999));
+        let mut previous: f64 = f64::NEG_INFINITY;
+        for i in 0..=100 {
+            let x: f64 = lo + (hi - lo) * i as f64 / 100.0;
+            let cdf: f64 = self.cdf(x);
+            if cdf.is_nan() || cdf < previous {
+                return Err(SelfCheckError::NonMonotoneCdf { x });
+            }
+            previous = cdf;
+            let pdf: f64 = self.pdf(x);
+            if pdf.is_nan() || pdf < 0.0 {
+                return Err(SelfCheckError::NegativePdf { x });
+            }
+        }
+        // piecewise between the quantiles, so that heavy tails are integrated accurately
+        let mut bounds: Vec<f64> = vec![f64::NEG_INFINITY];
+        bounds.extend(probs.iter().map(|&p| self.quantile(p)));
+        bounds.push(f64::INFINITY);
+        let integral: f64 = bounds.windows(2).map(|w| self.integrate_pdf(w[0], w[1], 5000)).sum::<f64>();
+        if integral.is_nan() || (integral - 1.0).abs() > tol {
+            return Err(SelfCheckError::IntegralMismatch { integral });
+        }
+        Ok(())
+    }
+
+    /// Expected shortfall (tail conditional expectation) $E[X \mid X > F^{-1}(p)]$ above the
+    /// value at risk $F^{-1}(p)$:
+    /// $$ ES_p = \frac{1}{1 - p} \int_{F^{-1}(p)}^{\infty} x f(x) dx, $$
+    /// computed with the composite Simpson's rule on `n` sub-intervals, the upper bound being
+    /// replaced by the quantile at probability $1 -$ `INTEGRATION_TAIL_PROB` (as in `integrate_pdf`).
+    /// For heavy tails the truncated part of the integral may not be negligible, and the expected
+    /// shortfall is infinite if the mean does not exist.
+    fn expected_shortfall(&self, p: f64, n: usize) -> f64 {
+        domain!(p > 0.0 && p < 1.0);
+        let var: f64 = self.quantile(p);
+        let hi: f64 = self.quantile(1.0 - INTEGRATION_TAIL_PROB);
+        simpson(|x| x * self.pdf(x), var, hi, n) / (1.0 - p)
+    }
+
+    /// Generate `n` random values from the distribution, all drawn from a single random number
+    /// generator initialized from `seed` (inverse transform sampling through the quantile function).
+    fn sample_n(&self, n: usize, seed: RandomSeed) -> Vec<f64> {
+        let mut rng: ChaCha8Rng = seed.rng();
+        (0..n).map(|_| self.quantile(rng.gen::<f64>())).collect()
+    }
+
+    /// Generate `n` random values like `sample_n`, reporting the progress to `progress` for long
+    /// simulations: after each chunk of `SAMPLE_CHUNK_SIZE` values (and after the last, possibly
+    /// shorter, chunk) `progress` is called with the number of values generated so far, and the
+    /// generation stops early, returning the values generated so far, if it returns `false`.
+    /// The values are the same as those of `sample_n` with the same seed.
+    fn sample_n_with<F: FnMut(usize) -> bool>(&self, n: usize, seed: RandomSeed, mut progress: F) -> Vec<f64> where Self: Sized {
+        let mut rng: ChaCha8Rng = seed.rng();
+        let mut samples: Vec<f64> = Vec::with_capacity(n);
+        while samples.len() < n {
+            let chunk: usize = SAMPLE_CHUNK_SIZE.min(n - samples.len());
+            for _ in 0..chunk {
+                samples.push(self.random_with(&mut rng));
+            }
+            if !progress(samples.len()) {
+                break;
+            }
+        }
+        samples
+    }
+
+    /// Iterator over `n` random values drawn lazily from a single random number generator
+    /// initialized from `seed`, with `random_with`. Its exact length (`ExactSizeIterator`) lets
+    /// `collect` pre-allocate; the values are the same as those of `sample_n` with the same seed.
+    fn sample_exact(&self, n: usize, seed: RandomSeed) -> SampleIter<'_, Self> where Self: Sized {
+        SampleIter { dist: self, rng: seed.rng(), remaining: n }
+    }
+
+    /// Maxima of `blocks` blocks of `block_size` draws each, taken from a single `sample_n` call
+    /// (see `blocks::block_maxima`).
+    /// For a large block size the block maxima are approximately GEV distributed; for the Gumbel
+    /// distribution they are exactly Gumbel, with the location shifted by $scale \log(block\_size)$.
+    fn sample_max(&self, block_size: usize, blocks: usize, seed: RandomSeed) -> Vec<f64> {
+        domain!(block_size > 0);
+        block_maxima(&self.sample_n(block_size * blocks, seed), block_size)
+    }
+
+    /// Monte Carlo estimate of the mean, the average of `sample_n(n, seed)`.
+    /// This is only an approximation, with a standard error of $\sigma / \sqrt{n}$: prefer the
+    /// analytic means where they exist (e.g. `Gumbel::mean`), and note that the estimate does not
+    /// converge if the mean is infinite (e.g. a GEV with $shape \geq 1$, see `GEV::moment_exists`).
+    fn mean_mc(&self, n: usize, seed: RandomSeed) -> f64 {
+        domain!(n > 0);
+        self.sample_n(n, seed).iter().sum::<f64>() / n as f64
+    }
+
+    /// Monte Carlo estimate of the variance, the unbiased sample variance of `sample_n(n, seed)`.
+    /// As `mean_mc`, it is only an approximation, which converges slowly for heavy tails and not at
+    /// all if the variance is infinite.
+    fn variance_mc(&self, n: usize, seed: RandomSeed) -> f64 {
+        domain!(n > 1);
+        let sample: Vec<f64> = self.sample_n(n, seed);
+        let mean: f64 = sample.iter().sum::<f64>() / n as f64;
+        sample.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1) as f64
+    }
+
+    /// Sampling distribution of $g(X)$: generate `n` values with `sample_n` and apply `f` to each of
+    /// them, e.g. to propagate a nonlinear damage function.
+    fn transform_sample<F: Fn(f64) -> f64>(&self, f: F, n: usize, seed: RandomSeed) -> Vec<f64> where Self: Sized {
+        self.sample_n(n, seed).into_iter().map(f).collect()
+    }
+
+    /// Generate `count` independent seeded streams of `n` values each, for ensemble simulations.
+    /// The child seeds are derived deterministically from `master_seed`: they are the first `count`
+    /// `u64` values drawn from a ChaCha8 generator seeded with `master_seed`. Each stream is then
+    /// `sample_n(n, RandomSeed::Seed(child_seed))`, and is returned along with its child seed for provenance.
+    fn seeded_streams(&self, count: usize, n: usize, master_seed: u64) -> Vec<(u64, Vec<f64>)> {
+        let mut master: ChaCha8Rng = ChaCha8Rng::seed_from_u64(master_seed);
+        let child_seeds: Vec<u64> = (0..count).map(|_| master.gen::<u64>()).collect();
+        child_seeds.into_iter()
+            .map(|child| (child, self.sample_n(n, RandomSeed::Seed(child))))
+            .collect()
+    }
+}
+
+/// Location-scale families, whose CDF is a function of the reduced variate
+/// $y = \frac{x - loc}{scale}$ only (for a fixed shape).
+pub trait LocationScale {
+    /// Obtain the location parameter
+    fn loc(&self) -> f64;
+    /// Obtain the scale parameter
+    fn scale(&self) -> f64;
+
+    /// Reduced (standardized) variate $y = \frac{x - loc}{scale}$.
+    #[inline(always)]
+    fn reduced(&self, x: f64) -> f64 {
+        (x - self.loc()) / self.scale()
+    }
+}
+
+/// Empirical percentile rank of `x` within the sample `data`, i.e. 100 times the proportion of
+/// observations less than or equal to `x`. This is the empirical analogue of `DistQuant::percentile`.
+pub fn percentile_rank_in_sample(data: &[f64], x: f64) -> f64 {
+    domain!(!data.is_empty());
+    let below: usize = data.iter().filter(|&&val| val <= x).count();
+    100.0 * below as f64 / data.len() as f64
+}
+
+/// Write a distribution as `Name(param=value, ...)` for the `Display` implementations, formatting
+/// the values with the precision of the formatter if given (e.g. `{:.3}`) and in full otherwise.
+pub(crate) fn fmt_params(f: &mut fmt::Formatter<'_>, name: &str, params: &[(&str, f64)]) -> fmt::Result {
+    write!(f, "{}(", name)?;
+    for (i, (param, value)) in params.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        match f.precision() {
+            Some(precision) => write!(f, "{}={:.*}", param, precision, value)?,
+            None => write!(f, "{}={}", param, value)?,
+        }
+    }
+    write!(f, ")")
+}
+
+/// Number of values generated between two calls of the progress callback of `DistQuant::sample_n_with`.
+pub const SAMPLE_CHUNK_SIZE: usize = 100;
+
+/// Tail probability left out when an infinite integration bound is replaced by a quantile.
+pub const INTEGRATION_TAIL_PROB: f64 = 1e-10;
+
+/// $\log(1 - e^{-t})$ from $\log t$, accurate for all $t > 0$: the survival function of the
+/// extreme value distributions is $1 - e^{-t}$ with $t$ computed in logarithmic form. Based on
+/// Mächler (2012): $\log t - t / 2$ for tiny $t$ (where $e^{-t}$ rounds to 1), $\log(- \mathrm{expm1}(-t))$
+/// for $t \leq \log 2$ and $\mathrm{log1p}(- e^{-t})$ above.
+pub(crate) fn log1mexp(log_t: f64) -> f64 {
+    let t: f64 = exp(log_t);
+    if t < 1e-8 {
+        log_t - 0.5 * t
+    } else if t <= std::f64::consts::LN_2 {
+        log(- expm1(- t))
+    } else {
+        log1p(- exp(- t))
+    }
+}
+
+/// Finite integration bounds for `dist`: the infinite bounds are replaced by the quantiles at
+/// probability `INTEGRATION_TAIL_PROB` (resp. one minus it).
+fn integration_bounds<D: DistQuant + ?Sized>(dist: &D, lo: f64, hi: f64) -> (f64, f64) {
+    let lo: f64 = if lo == f64::NEG_INFINITY { dist.quantile(INTEGRATION_TAIL_PROB) } else { lo };
+    let hi: f64 = if hi == f64::INFINITY { dist.quantile(1.0 - INTEGRATION_TAIL_PROB) } else { hi };
+    (lo, hi)
+}
+
+/// Composite Simpson's rule for `func` over $[lo, hi]$ with `n` sub-intervals (rounded up to an even number).
+pub(crate) fn simpson<F: Fn(f64) -> f64>(func: F, lo: f64, hi: f64, n: usize) -> f64 {
+    let n: usize = n.max(2).div_ceil(2) * 2;
+    let h: f64 = (hi - lo) / n as f64;
+    let mut total: f64 = func(lo) + func(hi);
+    for i in 1..n {
+        let weight: f64 = if i % 2 == 1 { 4.0 } else { 2.0 };
+        total += weight * func(lo + h * i as f64);
+    }
+    total * h / 3.0
+}
+
+/// Configuration of the adaptive numerical integration (see `adaptive_simpson`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Integration {
+    /// relative tolerance on the integral
+    pub tol: f64,
+    /// maximum number of evaluations of the integrand
+    pub max_evals: usize,
+}
+
+impl Default for Integration {
+    /// Relative tolerance of $10^{-10}$ with at most 100000 evaluations.
+    fn default() -> Self {
+        Integration { tol: 1e-10, max_evals: 100_000 }
+    }
+}
+
+/// Adaptive Simpson's rule for `func` over the finite interval $[lo, hi]$.
+/// The interval is first split into 16 panels, and each panel is halved recursively until the
+/// difference between its Simpson estimate and the sum of the estimates of its halves is below
+/// its share of the absolute tolerance, `config.tol` times the magnitude of the initial estimate.
+/// The refinement stops early once `config.max_evals` evaluations have been made, in which case
+/// the tolerance may not be met.
+/// Returns the integral (with the Richardson correction of the accepted panels) and the estimate
+/// of its absolute error, the sum of the panel differences divided by 15.
+pub fn adaptive_simpson<F: Fn(f64) -> f64>(func: F, lo: f64, hi: f64, config: Integration) -> (f64, f64) {
+    const PANELS: usize = 16;
+    const MAX_DEPTH: usize = 50;
+    // (a, b, f(a), f(midpoint), f(b), simpson estimate, depth)
+    let mut stack: Vec<(f64, f64, f64, f64, f64, f64, usize)> = Vec::new();
+    let h: f64 = (hi - lo) / PANELS as f64;
+    let mut f_a: f64 = func(lo);
+    let mut evals: usize = 1;
+    let mut initial: f64 = 0.0;
+    for i in 0..PANELS {
+        let a: f64 = lo + h * i as f64;
+        let b: f64 = if i + 1 == PANELS { hi } else { a + h };
+        let (f_m, f_b) = (func(0.5 * (a + b)), func(b));
+        evals += 2;
+        let whole: f64 = (b - a) / 6.0 * (f_a + 4.0 * f_m + f_b);
+        initial += whole;
+        stack.push((a, b, f_a, f_m, f_b, whole, 0));
+        f_a = f_b;
+    }
+    let abs_tol: f64 = config.tol * initial.abs().max(f64::MIN_POSITIVE);
+
+    let (mut total, mut error) = (0.0, 0.0);
+    while let Some((a, b, f_a, f_m, f_b, whole, depth)) = stack.pop() {
+        let m: f64 = 0.5 * (a + b);
+        let (f_lm, f_rm) = (func(0.5 * (a + m)), func(0.5 * (m + b)));
+        evals += 2;
+        let left: f64 = (m - a) / 6.0 * (f_a + 4.0 * f_lm + f_m);
+        let right: f64 = (b - m) / 6.0 * (f_m + 4.0 * f_rm + f_b);
+        let delta: f64 = left + right - whole;
+        let panel_tol: f64 = abs_tol * (b - a) / (hi - lo);
+        if delta.abs() <= 15.0 * panel_tol || depth >= MAX_DEPTH || evals >= config.max_evals || delta.is_nan() {
+            total += left + right + delta / 15.0;
+            error += delta.abs() / 15.0;
+        } else {
+            stack.push((a, m, f_a, f_lm, f_m, left, depth + 1));
+            stack.push((m, b, f_m, f_rm, f_b, right, depth + 1));
+        }
+    }
+    (total, error)
+}
+
+/// Errors returned by the checked evaluations `DistQuant::try_pdf` and `DistQuant::try_cdf`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EvalError {
+    /// The point lies outside of the support of the distribution.
+    OutOfSupport,
+    /// The evaluation gave NaN or an infinite value.
+    NonFinite,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::OutOfSupport => write!(f, "the point lies outside of the support"),
+            EvalError::NonFinite => write!(f, "the evaluation is not finite"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Failed check of `DistQuant::self_check`, with the point where it failed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelfCheckError {
+    /// `cdf(quantile(p))` differs from `p`.
+    QuantileMismatch { p: f64 },
+    /// The CDF decreases (or is NaN) at `x`.
+    NonMonotoneCdf { x: f64 },
+    /// The PDF is negative (or NaN) at `x`.
+    NegativePdf { x: f64 },
+    /// The PDF does not integrate to 1.
+    IntegralMismatch { integral: f64 },
+}
+
+impl fmt::Display for SelfCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfCheckError::QuantileMismatch { p } => write!(f, "cdf(quantile(p)) differs from p = {}", p),
+            SelfCheckError::NonMonotoneCdf { x } => write!(f, "the cdf decreases at x = {}", x),
+            SelfCheckError::NegativePdf { x } => write!(f, "the pdf is negative at x = {}", x),
+            SelfCheckError::IntegralMismatch { integral } => write!(f, "the pdf integrates to {} instead of 1", integral),
+        }
+    }
+}
+
+impl std::error::Error for SelfCheckError {}
+
+/// One-shot report of the main characteristics of a distribution (see e.g. `GEV::summary`).
+/// The moments are `None` where they do not exist for the given parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistSummary {
+    /// mean, if the first moment exists
+    pub mean: Option<f64>,
+    /// variance, if the second moment exists
+    pub variance: Option<f64>,
+    /// median, the quantile at probability 1/2
+    pub median: f64,
+    /// mode, the maximum of the PDF (or the end point of the support where the PDF is unbounded)
+    pub mode: f64,
+    /// skewness, if the third moment exists
+    pub skewness: Option<f64>,
+    /// support as `(lower, upper)` end points
+    pub support: (f64, f64),
+    /// differential entropy $- \int f \log f$
+    pub entropy: f64,
 }
 
 /// Seeding for the random generation of the distributions.
@@ -45,4 +698,644 @@ impl RandomSeed {
             RandomSeed::Seed(val) => Some(*val),
         }
     }
+
+    /// Seed from the system clock (nanoseconds since the Unix epoch), for quick experiments whose
+    /// seed can still be recovered with `get_seed` and reused to reproduce them.
+    /// Requires `std` for the clock, as does the rest of the crate.
+    pub fn from_time() -> Self {
+        let nanos: u128 = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        RandomSeed::Seed(nanos as u64)
+    }
+
+    /// Derive `n` child seeds from this seed, e.g. one per member of an ensemble.
+    /// The children of a given `Seed` are deterministic and drawn from the generator it seeds, so
+    /// they are distinct with overwhelming probability; the children of `Empty` are all `Empty`.
+    pub fn split(&self, n: usize) -> Vec<RandomSeed> {
+        match self {
+            RandomSeed::Empty => (0..n).map(|_| RandomSeed::Empty).collect(),
+            RandomSeed::Seed(_) => {
+                let mut rng: ChaCha8Rng = self.rng();
+                (0..n).map(|_| RandomSeed::Seed(rng.gen::<u64>())).collect()
+            }
+        }
+    }
+
+    /// Random number generator corresponding to the seed.
+    pub(crate) fn rng(&self) -> ChaCha8Rng {
+        match self {
+            RandomSeed::Empty => ChaCha8Rng::from_entropy(),
+            RandomSeed::Seed(val) => ChaCha8Rng::seed_from_u64(*val),
+        }
+    }
+}
+
+/// Iterator over a fixed number of random values of a distribution, see `DistQuant::sample_exact`.
+#[derive(Debug, Clone)]
+pub struct SampleIter<'a, D: DistQuant> {
+    dist: &'a D,
+    rng: ChaCha8Rng,
+    remaining: usize,
+}
+
+impl<D: DistQuant> Iterator for SampleIter<'_, D> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.dist.random_with(&mut self.rng))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<D: DistQuant> ExactSizeIterator for SampleIter<'_, D> {}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::gumbel::Gumbel;
+    use crate::dist::frechet::Frechet;
+    use crate::dist::weibull::Weibull;
+    use crate::dist::gev::GEV;
+    use crate::dist::gpd::GeneralizedPareto;
+    use crate::dist::gof::chi_squared_gof;
+    use crate::dist::extreme::ExtremeValue;
+    use crate::dist::mixture::Mixture;
+    use libm::log;
+
+    // check the total integral of the pdf and the partial integral against the cdf at x
+    fn check_integral<D: DistQuant>(dist: &D, x: f64) {
+        let total: f64 = dist.integrate_pdf(f64::NEG_INFINITY, f64::INFINITY, 20000);
+        assert!((total - 1.0).abs() < 1e-6);
+        let partial: f64 = dist.integrate_pdf(f64::NEG_INFINITY, x, 20000);
+        assert!((partial - dist.cdf(x)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn adaptive_simpson_test() {
+        let (val, err) = adaptive_simpson(libm::exp, 0.0, 1.0, Integration::default());
+        assert!((val - (libm::exp(1.0) - 1.0)).abs() < 1e-12);
+        assert!(err < 1e-9);
+        // the budget limits the refinement
+        let config: Integration = Integration { tol: 1e-14, max_evals: 40 };
+        let (coarse, _) = adaptive_simpson(libm::sqrt, 0.0, 1.0, config);
+        assert!((coarse - 2.0 / 3.0).abs() > 1e-8);
+    }
+
+    #[test]
+    fn expect_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let loose: f64 = gumb.expect(|x| x, Integration { tol: 1e-2, max_evals: 100_000 });
+        let tight: f64 = gumb.expect(|x| x, Integration { tol: 1e-11, max_evals: 100_000 });
+        assert!((tight - gumb.mean()).abs() < (loose - gumb.mean()).abs());
+        assert!((tight - gumb.mean()).abs() < 1e-7);
+        assert!((gumb.integrate_pdf_with(f64::NEG_INFINITY, 2.0, Integration::default()) - gumb.cdf(2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn kl_divergence_test() {
+        let (f, g) = (Gumbel::new(0.0, 1.0), Gumbel::new(0.5, 2.0));
+        assert!(f.kl_divergence(&f, Integration::default()).abs() < 1e-9);
+        // closed form for two Gumbel distributions
+        let ratio: f64 = 0.5;
+        let exact: f64 = libm::log(2.0) + 0.5772156649015329 * (ratio - 1.0) + libm::exp(0.25) * libm::tgamma(ratio + 1.0) - 1.0 - 0.25;
+        assert!((f.kl_divergence(&g, Integration::default()) - exact).abs() < 1e-8);
+        // the support of a reversed Weibull does not cover the real line
+        assert_eq!(f.kl_divergence(&Weibull::new(2.0, 1.0, 2.0), Integration::default()), f64::INFINITY);
+    }
+
+    #[test]
+    fn integrate_pdf_test() {
+        check_integral(&Gumbel::new(0.5, 2.0), 2.0);
+        check_integral(&Frechet::new(1.0, 0.5, 5.0), 1.6);
+        check_integral(&Weibull::new(2.0, 2.0, 2.0), 1.0);
+        check_integral(&GEV::new(2.0, 2.0, 0.2), 3.0);
+        check_integral(&GEV::new(2.0, 2.0, -0.3), 3.0);
+    }
+
+    #[test]
+    fn percentile_rank_in_sample_test() {
+        let data: [f64; 4] = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile_rank_in_sample(&data, 0.5), 0.0);
+        assert_eq!(percentile_rank_in_sample(&data, 2.0), 50.0);
+        assert_eq!(percentile_rank_in_sample(&data, 10.0), 100.0);
+    }
+
+    #[test]
+    fn percentile_matches_sample_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let data: Vec<f64> = (0..5000).map(|i| gumb.random(RandomSeed::Seed(i))).collect();
+        for x in [-1.0, 0.5, 2.0, 5.0] {
+            assert!((gumb.percentile(x) - percentile_rank_in_sample(&data, x)).abs() < 2.0);
+        }
+    }
+
+    #[test]
+    fn boxed_dist_quant_test() {
+        let dists: Vec<Box<dyn DistQuant>> = vec![Box::new(Gumbel::new(0.5, 2.0)), Box::new(GEV::new(2.0, 2.0, 0.1))];
+        for dist in dists.iter() {
+            let cdf: f64 = dist.cdf(3.0);
+            assert!(cdf > 0.0 && cdf < 1.0);
+            assert!(dist.pdf(3.0) > 0.0);
+            assert!((dist.cdf(dist.quantile(0.7)) - 0.7).abs() < 1e-12);
+            assert!(dist.random(RandomSeed::Seed(1)).is_finite());
+            assert_eq!(dist.sample_n(10, RandomSeed::Seed(1)).len(), 10);
+        }
+    }
+
+    #[test]
+    fn random_with_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let draw: f64 = gumb.random_with(ChaCha8Rng::seed_from_u64(5));
+        assert_eq!(draw, gumb.random_with(ChaCha8Rng::seed_from_u64(5)));
+        assert_eq!(draw, gumb.sample_n(1, RandomSeed::Seed(5))[0]);
+    }
+
+    #[test]
+    fn log_sf_test() {
+        let dists: Vec<Box<dyn DistQuant>> = vec![
+            Box::new(Gumbel::new(0.5, 2.0)),
+            Box::new(Frechet::new(0.0, 1.0, 3.0)),
+            Box::new(GEV::new(0.5, 2.0, 0.2)),
+            Box::new(GEV::new(0.5, 2.0, -0.2)),
+            Box::new(GeneralizedPareto::new(0.5, 2.0, 0.2)),
+        ];
+        // agrees with the plain form in the bulk
+        for dist in dists.iter() {
+            for &p in [0.01, 0.3, 0.5, 0.9, 0.999].iter() {
+                let x: f64 = dist.quantile(p);
+                assert!((dist.log_sf(x) - log(dist.sf(x))).abs() < 1e-10);
+            }
+        }
+        let weib: Weibull = Weibull::new(0.0, 1.0, 3.0);
+        assert!((weib.log_sf(-0.5) - log(1.0 - weib.cdf(-0.5))).abs() < 1e-12);
+        // finite far in the upper tail, where the survival functions underflow
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        assert_eq!(gumb.sf(2000.0), 0.0);
+        assert!((gumb.log_sf(2000.0) + (2000.0 - 0.5) / 2.0).abs() < 1e-12);
+        assert!((GEV::new(0.5, 2.0, 0.0).log_sf(2000.0) - gumb.log_sf(2000.0)).abs() < 1e-12);
+        // the Weibull survival function tends to 1 from below at its lower tail: log_sf ~ -t
+        assert!((weib.log_sf(-3.0) / (- exp(-27.0)) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn tail_ratio_test() {
+        let frechet: Frechet = Frechet::new(0.0, 1.0, 3.0);
+        let gumb: Gumbel = Gumbel::new(0.0, 1.0);
+        let xs: [f64; 6] = [5.0, 10.0, 50.0, 100.0, 500.0, 1000.0];
+        let ratios: Vec<f64> = xs.iter().map(|&x| frechet.tail_ratio(&gumb, x)).collect();
+        // the heavier Fréchet tail: the ratio grows without bound
+        assert!(ratios.windows(2).all(|w| w[1] > w[0]));
+        assert!(ratios[3] > 1e30);
+        // both survival functions have underflowed, yet the ratio is not NaN
+        assert_eq!((frechet.sf(5000.0), gumb.sf(5000.0)), (frechet.sf(5000.0), 0.0));
+        assert_eq!(frechet.tail_ratio(&gumb, 5000.0), f64::INFINITY);
+        assert_eq!(gumb.tail_ratio(&frechet, 5000.0), 0.0);
+        assert!((gumb.tail_ratio(&gumb, 1000.0) - 1.0).abs() < 1e-15);
+        assert!((frechet.tail_ratio(&gumb, 5.0) - frechet.sf(5.0) / gumb.sf(5.0)).abs() < 1e-9 * ratios[0]);
+    }
+
+    #[test]
+    fn gumbel_reduced_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        for i in -200..200 {
+            let x: f64 = i as f64;
+            assert_eq!(gumb.gumbel_reduced(x), (x - 0.5) / 2.0);
+        }
+        // finite deep in the lower tail, where the CDF underflows to 0
+        assert_eq!(gumb.cdf(-2000.0), 0.0);
+        assert!(gumb.gumbel_reduced(-2000.0).is_finite());
+        let gev: GEV = GEV::new(0.5, 2.0, 0.2);
+        assert_eq!(gev.cdf(-9.49), 0.0);
+        assert!(gev.gumbel_reduced(-9.49).is_finite());
+        // the override agrees with the generic form on log_cdf, and with the plain CDF away from the tails
+        let frechet: Frechet = Frechet::new(0.0, 1.0, 3.0);
+        let weibull: Weibull = Weibull::new(0.0, 1.0, 3.0);
+        for &x in [-1.0, 0.0, 1.0, 5.0].iter() {
+            assert!((gev.gumbel_reduced(x) + log(- log(gev.cdf(x)))).abs() < 1e-12);
+            assert!((gev.gumbel_reduced(x) + log(- gev.log_cdf(x))).abs() < 1e-12);
+        }
+        for &x in [0.5, 1.0, 2.0].iter() {
+            assert!((frechet.gumbel_reduced(x) + log(- log(frechet.cdf(x)))).abs() < 1e-12);
+            assert!((weibull.gumbel_reduced(- x) + log(- log(weibull.cdf(- x)))).abs() < 1e-12);
+        }
+        // curved for the other distributions: concave for the Frechet
+        let mid: f64 = frechet.gumbel_reduced(1.5);
+        assert!(mid > 0.5 * (frechet.gumbel_reduced(1.0) + frechet.gumbel_reduced(2.0)));
+    }
+
+    #[test]
+    fn random_with_rng_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let gev: GEV = GEV::new(0.5, 2.0, 0.1);
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(5);
+        let first: f64 = gumb.random_with_rng(&mut rng);
+        let second: f64 = gumb.random_with_rng(&mut rng);
+        let third: f64 = gev.random_with_rng(&mut rng);
+        assert_ne!(first, second);
+        // the generator advances by one draw per call, as in sample_n
+        assert_eq!(vec![first, second], gumb.sample_n(2, RandomSeed::Seed(5)));
+        assert_eq!(third, gev.sample_n(3, RandomSeed::Seed(5))[2]);
+        let mut again: ChaCha8Rng = ChaCha8Rng::seed_from_u64(5);
+        assert_eq!(gumb.random_with_rng(&mut again), first);
+    }
+
+    #[test]
+    fn random_stream_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let draws: Vec<f64> = (0..100).map(|stream| gumb.random_stream(5, stream)).collect();
+        for (i, a) in draws.iter().enumerate() {
+            assert!(draws[(i + 1)..].iter().all(|b| b != a));
+        }
+        assert_eq!(draws[3], gumb.random_stream(5, 3));
+        assert_ne!(draws[3], gumb.random_stream(6, 3));
+        // stream 0 is the default stream of the seed
+        assert_eq!(draws[0], gumb.random_with(ChaCha8Rng::seed_from_u64(5)));
+    }
+
+    #[test]
+    fn conditional_cdf_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        assert_eq!(gumb.conditional_cdf(3.0, 3.0), 0.0);
+        assert_eq!(gumb.conditional_cdf(1e3, 3.0), 1.0);
+        let mid: f64 = gumb.conditional_cdf(5.0, 3.0);
+        assert!(mid > 0.0 && mid < 1.0);
+        // bounded upper tail: the conditional cdf reaches 1 at the upper end point 6
+        let gev: GEV = GEV::new(2.0, 2.0, -0.5);
+        assert_eq!(gev.conditional_cdf(4.0, 4.0), 0.0);
+        assert!((gev.conditional_cdf(6.0 - 1e-9, 4.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn support_test() {
+        assert_eq!(Gumbel::new(0.5, 2.0).support(), (f64::NEG_INFINITY, f64::INFINITY));
+        assert_eq!(Frechet::new(1.0, 0.5, 5.0).support(), (1.0, f64::INFINITY));
+        assert_eq!(Weibull::new(2.0, 2.0, 2.0).support(), (f64::NEG_INFINITY, 2.0));
+        assert_eq!(GEV::new(2.0, 2.0, 0.5).support(), (-2.0, f64::INFINITY));
+        assert_eq!(GEV::new(2.0, 2.0, -0.5).support(), (f64::NEG_INFINITY, 6.0));
+    }
+
+    #[test]
+    fn quantile_clamped_test() {
+        let gev: GEV = GEV::new(2.0, 2.0, -0.5);
+        assert_eq!(gev.quantile_clamped(1.0000001), 6.0);
+        assert_eq!(gev.quantile_clamped(-1e-9), f64::NEG_INFINITY);
+        let frech: Frechet = Frechet::new(1.0, 0.5, 5.0);
+        assert_eq!(frech.quantile_clamped(-1e-9), 1.0);
+        assert_eq!(frech.quantile_clamped(1.0000001), f64::INFINITY);
+        for p in [1e-6, 0.3, 0.7, 1.0 - 1e-6] {
+            assert_eq!(gev.quantile_clamped(p), gev.quantile(p));
+            assert_eq!(frech.quantile_clamped(p), frech.quantile(p));
+        }
+    }
+
+    #[cfg(feature = "strict-domain")]
+    #[test]
+    #[should_panic]
+    fn strict_domain_test() {
+        // panics whatever the build profile with the strict-domain feature
+        Gumbel::new(0.5, 2.0).quantile(1.5);
+    }
+
+    #[test]
+    fn try_pdf_cdf_test() {
+        let gev: GEV = GEV::new(0.0, 1.0, 0.5);
+        assert_eq!(gev.try_pdf(1.0), Ok(gev.pdf(1.0)));
+        assert_eq!(gev.try_cdf(1.0), Ok(gev.cdf(1.0)));
+        assert_eq!(gev.try_pdf(-3.0), Err(EvalError::OutOfSupport));
+        assert_eq!(gev.try_cdf(f64::NAN), Err(EvalError::OutOfSupport));
+
+        // with a nearly zero shape, t(x) overflows in the far lower tail and the pdf is NaN
+        let gev: GEV = GEV::new(0.0, 1.0, 1e-17);
+        assert!(gev.pdf(-800.0).is_nan());
+        assert_eq!(gev.try_pdf(-800.0), Err(EvalError::NonFinite));
+        assert_eq!(gev.try_cdf(-800.0), Ok(0.0));
+    }
+
+    #[test]
+    fn location_scale_test() {
+        let x: f64 = 2.5;
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        assert_eq!(gumb.reduced(x), (x - 0.5) / 2.0);
+        assert_eq!(gumb.cdf(x), libm::exp(- libm::exp(- gumb.reduced(x))));
+        let frech: Frechet = Frechet::new(0.5, 2.0, 3.0);
+        assert_eq!(frech.reduced(x), (x - frech.loc()) / frech.scale());
+        assert_eq!(frech.cdf(x), libm::exp(- libm::pow(frech.reduced(x), - 3.0)));
+        let weib: Weibull = Weibull::new(3.0, 2.0, 3.0);
+        assert_eq!(weib.reduced(x), (x - 3.0) / 2.0);
+        assert_eq!(weib.cdf(x), libm::exp(- libm::pow(- weib.reduced(x), 3.0)));
+        let gev: GEV = GEV::new(0.5, 2.0, 0.2);
+        assert_eq!(gev.reduced(x), (x - 0.5) / 2.0);
+        assert_eq!(gev.cdf(x), libm::exp(- libm::pow(1.0 + 0.2 * gev.reduced(x), - 1.0 / 0.2)));
+        // usable generically
+        fn reduced_median<D: DistQuant + LocationScale>(dist: &D) -> f64 {
+            dist.reduced(dist.quantile(0.5))
+        }
+        assert!((reduced_median(&gumb) + libm::log(libm::log(2.0))).abs() < 1e-15);
+    }
+
+    #[test]
+    fn expected_shortfall_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let var: f64 = gumb.quantile(0.99);
+        let es: f64 = gumb.expected_shortfall(0.99, 2000);
+        assert!(es > var);
+        // Monte Carlo estimate: mean of the draws above the value at risk
+        let tail: Vec<f64> = gumb.sample_n(400_000, RandomSeed::Seed(1)).into_iter().filter(|&x| x > var).collect();
+        let mc: f64 = tail.iter().sum::<f64>() / tail.len() as f64;
+        assert!((es - mc).abs() < 0.1);
+    }
+
+    #[test]
+    fn quantile_density_test() {
+        let dists: Vec<Box<dyn DistQuant>> = vec![
+            Box::new(Gumbel::new(0.5, 2.0)),
+            Box::new(Frechet::new(0.0, 1.0, 3.0)),
+            Box::new(Weibull::new(0.0, 1.0, 3.0)),
+            Box::new(GEV::new(0.5, 2.0, 0.2)),
+            Box::new(GEV::new(0.5, 2.0, 0.0)),
+            Box::new(GEV::new(0.5, 2.0, -0.3)),
+            Box::new(GeneralizedPareto::new(0.5, 2.0, 0.2)),
+            Box::new(GeneralizedPareto::new(0.5, 2.0, 0.0)),
+            Box::new(ExtremeValue::Frechet(Frechet::new(0.0, 1.0, 3.0))),
+        ];
+        for dist in dists.iter() {
+            for &p in [0.01_f64, 0.2, 0.5, 0.8, 0.99].iter() {
+                let h: f64 = 1e-6 * p.min(1.0 - p);
+                let finite_diff: f64 = (dist.quantile(p + h) - dist.quantile(p - h)) / (2.0 * h);
+                let closed: f64 = dist.quantile_density(p);
+                assert!((closed / finite_diff - 1.0).abs() < 1e-6);
+                assert!((closed * dist.pdf(dist.quantile(p)) - 1.0).abs() < 1e-10);
+            }
+        }
+        // the default, through the density
+        let mixture: Mixture = Mixture::new(vec![(0.5, ExtremeValue::Gumbel(Gumbel::new(0.0, 1.0))), (0.5, ExtremeValue::Gumbel(Gumbel::new(3.0, 1.0)))]).unwrap();
+        assert!((mixture.quantile_density(0.5) * mixture.pdf(mixture.quantile(0.5)) - 1.0).abs() < 1e-12);
+        // at the end points: infinite where the density vanishes, never NaN nor negative
+        let expected: [(f64, f64); 9] = [
+            (f64::INFINITY, f64::INFINITY),
+            (f64::INFINITY, f64::INFINITY),
+            (f64::INFINITY, f64::INFINITY),
+            (f64::INFINITY, f64::INFINITY),
+            (f64::INFINITY, f64::INFINITY),
+            (f64::INFINITY, f64::INFINITY),
+            (2.0, f64::INFINITY),
+            (2.0, f64::INFINITY),
+            (f64::INFINITY, f64::INFINITY),
+        ];
+        for (dist, &(at_zero, at_one)) in dists.iter().zip(expected.iter()) {
+            for &p in [0.0, 1.0].iter() {
+                let closed: f64 = dist.quantile_density(p);
+                assert!(!closed.is_nan() && closed >= 0.0);
+            }
+            assert_eq!((dist.quantile_density(0.0), dist.quantile_density(1.0)), (at_zero, at_one));
+        }
+        // finite limits at the upper end point, where the density does not vanish
+        assert_eq!(GEV::new(0.5, 2.0, -1.0).quantile_density(1.0), 2.0);
+        assert_eq!(GEV::new(0.5, 2.0, -1.5).quantile_density(1.0), 0.0);
+        assert_eq!(GEV::new(0.5, 2.0, -1.5).quantile_density(0.0), f64::INFINITY);
+        assert_eq!(Weibull::new(0.0, 2.0, 1.0).quantile_density(1.0), 2.0);
+        assert_eq!(Weibull::new(0.0, 2.0, 0.5).quantile_density(1.0), 0.0);
+        assert_eq!(GeneralizedPareto::new(0.5, 2.0, -1.0).quantile_density(1.0), 2.0);
+        assert_eq!(GeneralizedPareto::new(0.5, 2.0, -1.5).quantile_density(1.0), 0.0);
+        assert_eq!(ExtremeValue::GEV(GEV::new(0.5, 2.0, 0.2)).quantile_density(0.0), f64::INFINITY);
+        // the default, through the density
+        assert!(!mixture.quantile_density(0.0).is_nan() && !mixture.quantile_density(1.0).is_nan());
+    }
+
+    #[test]
+    fn quantile_numeric_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        for p in [1e-6, 0.01, 0.2, 0.5, 0.8, 0.99, 0.9999] {
+            assert!((gumb.quantile_numeric(p) - gumb.quantile(p)).abs() < 1e-10);
+        }
+        // bounded supports, on either side
+        let frech: Frechet = Frechet::new(1.0, 2.0, 3.0);
+        let weib: Weibull = Weibull::new(1.0, 2.0, 3.0);
+        let gev: GEV = GEV::new(1000.0, 2.0, -0.5);
+        for p in [0.01, 0.5, 0.99] {
+            assert!((frech.quantile_numeric(p) - frech.quantile(p)).abs() < 1e-10);
+            assert!((weib.quantile_numeric(p) - weib.quantile(p)).abs() < 1e-10);
+            assert!((gev.quantile_numeric(p) - gev.quantile(p)).abs() < 1e-9);
+        }
+        assert_eq!(frech.quantile_numeric(0.0), 1.0);
+        assert_eq!(gumb.quantile_numeric(1.0), f64::INFINITY);
+    }
+
+    // deliberately corrupted distributions, built on a Gumbel distribution
+    enum Corruption { Quantile, Pdf, Cdf }
+    struct Corrupted { inner: Gumbel, corruption: Corruption }
+
+    impl DistQuant for Corrupted {
+        fn cdf(&self, x: f64) -> f64 {
+            match self.corruption {
+                // dips between the quantiles at 0.5 and 0.65
+                Corruption::Cdf if 1.5 < x && x < 2.0 => self.inner.cdf(x) - 0.1,
+                _ => self.inner.cdf(x),
+            }
+        }
+        fn pdf(&self, x: f64) -> f64 {
+            match self.corruption {
+                Corruption::Pdf => 2.0 * self.inner.pdf(x),
+                _ => self.inner.pdf(x),
+            }
+        }
+        fn quantile(&self, x: f64) -> f64 {
+            match self.corruption {
+                Corruption::Quantile => self.inner.quantile(x) + 0.1,
+                _ => self.inner.quantile(x),
+            }
+        }
+        fn random(&self, seed: RandomSeed) -> f64 {
+            self.inner.random(seed)
+        }
+    }
+
+    #[test]
+    fn evaluate_test() {
+        let dists: Vec<Box<dyn DistQuant>> = vec![
+            Box::new(Gumbel::new(0.5, 2.0)),
+            Box::new(Frechet::new(-5.0, 2.0, 3.0)),
+            Box::new(GEV::new(0.5, 2.0, 0.2)),
+            Box::new(GEV::new(0.5, 2.0, 0.0)),
+            Box::new(GEV::new(0.5, 2.0, -0.3)),
+        ];
+        for dist in dists.iter() {
+            for x in [-1.0, 0.5, 2.0, 5.0] {
+                let (pdf, cdf) = dist.evaluate(x);
+                assert!((pdf - dist.pdf(x)).abs() <= 1e-15 * dist.pdf(x));
+                assert!((cdf - dist.cdf(x)).abs() <= 1e-15 * dist.cdf(x));
+            }
+        }
+    }
+
+    #[test]
+    fn isf_test() {
+        let dists: Vec<Box<dyn DistQuant>> = vec![
+            Box::new(Gumbel::new(0.5, 2.0)),
+            Box::new(Frechet::new(0.5, 2.0, 3.0)),
+            Box::new(Weibull::new(0.5, 2.0, 3.0)),
+            Box::new(GEV::new(0.5, 2.0, 0.2)),
+            Box::new(GEV::new(0.5, 2.0, -0.3)),
+            Box::new(GeneralizedPareto::new(0.5, 2.0, 0.2)),
+            Box::new(GeneralizedPareto::new(0.5, 2.0, 0.0)),
+        ];
+        for dist in dists.iter() {
+            for p in [0.9, 0.5, 0.1, 0.01] {
+                assert!((dist.isf(p) - dist.quantile(1.0 - p)).abs() < 1e-12);
+            }
+            assert_eq!(dist.return_level_precise(100.0), dist.isf(0.01));
+        }
+        // the value exceeded with a tiny probability, checked with the stable survival functions
+        for dist in [&Gumbel::new(0.5, 2.0) as &dyn DistQuant, &GEV::new(0.5, 2.0, 0.2)] {
+            assert!((dist.sf(dist.isf(1e-12)) / 1e-12 - 1.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn self_check_test() {
+        assert_eq!(Gumbel::new(0.5, 2.0).self_check(1e-6), Ok(()));
+        assert_eq!(GEV::new(0.5, 2.0, 0.2).self_check(1e-6), Ok(()));
+        assert_eq!(GEV::new(0.5, 2.0, -0.3).self_check(1e-6), Ok(()));
+        assert_eq!(Frechet::new(0.5, 2.0, 3.0).self_check(1e-6), Ok(()));
+        assert_eq!(Weibull::new(0.5, 2.0, 3.0).self_check(1e-6), Ok(()));
+
+        let inner: Gumbel = Gumbel::new(0.5, 2.0);
+        let corrupted = |corruption: Corruption| Corrupted { inner, corruption }.self_check(1e-6);
+        assert_eq!(corrupted(Corruption::Quantile), Err(SelfCheckError::QuantileMismatch { p: 0.001 }));
+        assert!(matches!(corrupted(Corruption::Cdf), Err(SelfCheckError::NonMonotoneCdf { .. })));
+        match corrupted(Corruption::Pdf) {
+            Err(SelfCheckError::IntegralMismatch { integral }) => assert!((integral - 2.0).abs() < 1e-6),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn hazard_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        assert!((gumb.hazard(1.0) - gumb.pdf(1.0) / (1.0 - gumb.cdf(1.0))).abs() < 1e-15);
+        assert!((gumb.cumulative_hazard(1.0) + log(1.0 - gumb.cdf(1.0))).abs() < 1e-15);
+        // the Gumbel hazard increases towards 1 / scale
+        let hazards: Vec<f64> = gumb.hazard_over(&[0.0, 5.0, 10.0, 20.0]);
+        assert!(hazards.windows(2).all(|w| w[0] < w[1]));
+        assert!((hazards[3] - 0.5).abs() < 1e-3);
+        // the exponential distribution (GPD with zero shape) has a constant hazard
+        let expo: GeneralizedPareto = GeneralizedPareto::new(0.0, 2.0, 0.0);
+        assert!(expo.hazard_over(&[0.5, 1.0, 3.0]).iter().all(|h| (h - 0.5).abs() < 1e-12));
+        assert!((expo.cumulative_hazard_over(&[3.0])[0] - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sample_n_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let first: Vec<f64> = gumb.sample_n(100, RandomSeed::Seed(7));
+        assert_eq!(first.len(), 100);
+        assert_eq!(first, gumb.sample_n(100, RandomSeed::Seed(7)));
+        assert_ne!(first, gumb.sample_n(100, RandomSeed::Seed(8)));
+    }
+
+    #[test]
+    fn sample_n_with_test() {
+        let gev: GEV = GEV::new(0.5, 2.0, 0.1);
+        let mut calls: Vec<usize> = Vec::new();
+        let all: Vec<f64> = gev.sample_n_with(250, RandomSeed::Seed(7), |i| { calls.push(i); true });
+        assert_eq!(all, gev.sample_n(250, RandomSeed::Seed(7)));
+        assert_eq!(calls, vec![100, 200, 250]);
+        // cancelled once 100 values have been generated
+        let partial: Vec<f64> = gev.sample_n_with(10_000, RandomSeed::Seed(7), |i| i < 100);
+        assert_eq!(partial.len(), 100);
+        assert_eq!(partial[..], all[..100]);
+    }
+
+    #[test]
+    fn sample_exact_test() {
+        let gev: GEV = GEV::new(0.5, 2.0, 0.1);
+        let mut iter = gev.sample_exact(3, RandomSeed::Seed(7));
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert_eq!(iter.len(), 3);
+        assert!(iter.next().is_some());
+        assert_eq!(iter.len(), 2);
+        assert!(iter.next().is_some() && iter.next().is_some());
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.len(), 0);
+        let all: Vec<f64> = gev.sample_exact(250, RandomSeed::Seed(7)).collect();
+        assert_eq!(all, gev.sample_n(250, RandomSeed::Seed(7)));
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        assert_eq!(gumb.sample_exact(10, RandomSeed::Seed(3)).collect::<Vec<f64>>(), gumb.sample_n(10, RandomSeed::Seed(3)));
+        assert_eq!(gumb.sample_exact(0, RandomSeed::Seed(3)).count(), 0);
+    }
+
+    #[test]
+    fn sample_max_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let maxima: Vec<f64> = gumb.sample_max(50, 1000, RandomSeed::Seed(5));
+        assert_eq!(maxima.len(), 1000);
+        assert_eq!(maxima[0], gumb.sample_n(50, RandomSeed::Seed(5)).into_iter().fold(f64::NEG_INFINITY, f64::max));
+
+        // the maxima of 50 draws are Gumbel distributed with location 0.5 + 2 log(50)
+        let edges: Vec<f64> = vec![f64::NEG_INFINITY, 7.0, 8.0, 8.5, 9.0, 9.5, 10.0, 11.0, 12.0, f64::INFINITY];
+        let counts: Vec<usize> = edges.windows(2)
+            .map(|w| maxima.iter().filter(|&&x| w[0] <= x && x < w[1]).count())
+            .collect();
+        let (stat, dof) = chi_squared_gof(&Gumbel::new(0.5 + 2.0 * log(50.0), 2.0), &edges, &counts);
+        assert_eq!(dof, 8);
+        assert!(stat < 26.1);
+        let (stat, _) = chi_squared_gof(&gumb, &edges, &counts);
+        assert!(stat > 1000.0);
+    }
+
+    #[test]
+    fn transform_sample_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        assert_eq!(gumb.transform_sample(|x| x, 100, RandomSeed::Seed(3)), gumb.sample_n(100, RandomSeed::Seed(3)));
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+        let base: Vec<f64> = gumb.sample_n(1000, RandomSeed::Seed(3));
+        let shifted: Vec<f64> = gumb.transform_sample(|x| 2.0 * x + 3.0, 1000, RandomSeed::Seed(3));
+        assert!((mean(&shifted) - (2.0 * mean(&base) + 3.0)).abs() < 1e-10);
+    }
+
+    #[test]
+    fn random_seed_split_test() {
+        let children: Vec<Option<u64>> = RandomSeed::Seed(42).split(5).iter().map(|s| s.get_seed()).collect();
+        let again: Vec<Option<u64>> = RandomSeed::Seed(42).split(5).iter().map(|s| s.get_seed()).collect();
+        assert_eq!(children, again);
+        for (i, child) in children.iter().enumerate() {
+            assert!(child.is_some() && *child != Some(42));
+            assert!(children[i + 1..].iter().all(|other| other != child));
+        }
+        assert!(RandomSeed::Empty.split(3).iter().all(|s| s.get_seed().is_none()));
+    }
+
+    #[test]
+    fn random_seed_from_time_test() {
+        // a reproducible seed: the same draw is obtained from the recovered seed
+        let seed: RandomSeed = RandomSeed::from_time();
+        let gumb: Gumbel = Gumbel::new(1.0, 2.0);
+        let value: u64 = seed.get_seed().unwrap();
+        assert_eq!(gumb.random(seed), gumb.random(RandomSeed::Seed(value)));
+    }
+
+    #[test]
+    fn seeded_streams_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let streams: Vec<(u64, Vec<f64>)> = gumb.seeded_streams(5, 20, 42);
+        assert_eq!(streams, gumb.seeded_streams(5, 20, 42));
+        assert_eq!(streams.len(), 5);
+        for (child, samples) in &streams {
+            assert_eq!(samples.len(), 20);
+            assert_eq!(*samples, gumb.sample_n(20, RandomSeed::Seed(*child)));
+        }
+        // the child seeds are distinct and depend on the master seed
+        assert_ne!(streams[0].0, streams[1].0);
+        assert_ne!(streams[0].0, gumb.seeded_streams(1, 20, 43)[0].0);
+    }
 }
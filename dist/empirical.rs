@@ -0,0 +1,159 @@
+//! The empirical distribution of a sample, to compare the data with a fitted distribution.
+use libm::{log, sqrt};
+
+/// Empirical CDF $F_n(x) = \frac{1}{n} \#\{i : x_i \leq x\}$ of a sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmpiricalCdf {
+    /// the observations, sorted in increasing order
+    sorted: Vec<f64>,
+}
+
+impl EmpiricalCdf {
+    /// Create the empirical CDF of the data, which must be non-empty and free of NaNs.
+    pub fn new(data: &[f64]) -> Self {
+        domain!(!data.is_empty() && data.iter().all(|x| !x.is_nan()));
+        let mut sorted: Vec<f64> = data.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        EmpiricalCdf{sorted}
+    }
+
+    /// Number of observations
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Whether the sample is empty (never the case for a constructed `EmpiricalCdf`)
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// Obtain the sorted observations
+    #[inline(always)]
+    pub fn values(&self) -> &[f64] {
+        &self.sorted
+    }
+
+    /// Proportion of the observations less than or equal to `x`.
+    pub fn cdf(&self, x: f64) -> f64 {
+        let below: usize = self.sorted.partition_point(|&val| val <= x);
+        below as f64 / self.len() as f64
+    }
+
+    /// Empirical quantile, the inverse of the step function `cdf`: the smallest observation $x$ with
+    /// $F_n(x) \geq p$, i.e. the order statistic $x_{(\lceil n p \rceil)}$ (the minimum for $p = 0$).
+    pub fn quantile(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        let rank: usize = (p * self.len() as f64).ceil() as usize;
+        self.sorted[rank.clamp(1, self.len()) - 1]
+    }
+
+    /// Confidence band of level $1 - \alpha$ for the true CDF, from the Dvoretzky-Kiefer-Wolfowitz
+    /// inequality $P(\sup_x |F_n(x) - F(x)| > \varepsilon) \leq 2 e^{-2 n \varepsilon^2}$, i.e.
+    /// $F_n(x) \pm \varepsilon$ with $\varepsilon = \sqrt{\frac{\log(2 / \alpha)}{2 n}}$, clamped to $[0, 1]$.
+    /// Returns `(x, lower, upper)` at each distinct observation $x$ (where $F_n$ jumps).
+    /// The band holds simultaneously for all $x$ and does not depend on the distribution.
+    pub fn confidence_band(&self, alpha: f64) -> Vec<(f64, f64, f64)> {
+        domain!(alpha > 0.0 && alpha < 1.0);
+        let eps: f64 = sqrt(log(2.0 / alpha) / (2.0 * self.len() as f64));
+        let mut band: Vec<(f64, f64, f64)> = Vec::new();
+        for (i, &x) in self.sorted.iter().enumerate() {
+            // only keep the last of tied observations, where the step is complete
+            if i + 1 < self.len() && self.sorted[i + 1] == x {
+                continue;
+            }
+            let ecdf: f64 = (i + 1) as f64 / self.len() as f64;
+            band.push((x, (ecdf - eps).max(0.0), (ecdf + eps).min(1.0)));
+        }
+        band
+    }
+}
+
+/// Plotting position formulas $p_i = \frac{i - a}{n + 1 - 2a}$, the probability assigned to the
+/// $i$-th smallest of $n$ observations (rank $i$ from 1) on probability plots, with the constant $a$:
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlottingPosition {
+    /// Weibull $\frac{i}{n + 1}$ ($a = 0$), unbiased for the probabilities of the order statistics
+    Weibull,
+    /// Gringorten $\frac{i - 0.44}{n + 0.12}$ ($a = 0.44$), nearly unbiased for the Gumbel quantiles
+    Gringorten,
+    /// Hazen $\frac{i - 0.5}{n}$ ($a = 0.5$)
+    Hazen,
+    /// Cunnane $\frac{i - 0.4}{n + 0.2}$ ($a = 0.4$), a compromise for most distributions
+    Cunnane,
+}
+
+impl PlottingPosition {
+    /// Probability $p_i$ assigned to the observation of rank `rank` ($1 \leq rank \leq n$) in a
+    /// sample of size `n`, always in $(0, 1)$.
+    pub fn probability(&self, rank: usize, n: usize) -> f64 {
+        domain!((1..=n).contains(&rank));
+        let a: f64 = match self {
+            PlottingPosition::Weibull => 0.0,
+            PlottingPosition::Gringorten => 0.44,
+            PlottingPosition::Hazen => 0.5,
+            PlottingPosition::Cunnane => 0.4,
+        };
+        (rank as f64 - a) / (n as f64 + 1.0 - 2.0 * a)
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plotting_position_test() {
+        assert_eq!(PlottingPosition::Weibull.probability(1, 9), 0.1);
+        assert_eq!(PlottingPosition::Hazen.probability(3, 10), 0.25);
+        assert!((PlottingPosition::Gringorten.probability(1, 10) - 0.56 / 10.12).abs() < 1e-15);
+        assert!((PlottingPosition::Cunnane.probability(10, 10) - 9.6 / 10.2).abs() < 1e-15);
+        // symmetric around 1/2
+        for kind in [PlottingPosition::Weibull, PlottingPosition::Gringorten, PlottingPosition::Hazen, PlottingPosition::Cunnane] {
+            assert!((kind.probability(2, 7) + kind.probability(6, 7) - 1.0).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn empirical_cdf_test() {
+        let ecdf: EmpiricalCdf = EmpiricalCdf::new(&[3.0, 1.0, 2.0, 2.0]);
+        assert_eq!(ecdf.len(), 4);
+        assert_eq!(ecdf.values(), &[1.0, 2.0, 2.0, 3.0]);
+        assert_eq!(ecdf.cdf(0.5), 0.0);
+        assert_eq!(ecdf.cdf(2.0), 0.75);
+        assert_eq!(ecdf.cdf(2.5), 0.75);
+        assert_eq!(ecdf.cdf(3.0), 1.0);
+        assert_eq!(ecdf.quantile(0.0), 1.0);
+        assert_eq!(ecdf.quantile(0.25), 1.0);
+        assert_eq!(ecdf.quantile(0.3), 2.0);
+        assert_eq!(ecdf.quantile(0.75), 2.0);
+        assert_eq!(ecdf.quantile(1.0), 3.0);
+    }
+
+    #[test]
+    fn confidence_band_test() {
+        let data: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let band: Vec<(f64, f64, f64)> = EmpiricalCdf::new(&data).confidence_band(0.05);
+        assert_eq!(band.len(), 100);
+        let eps: f64 = sqrt(log(2.0 / 0.05) / 200.0);
+        // unclamped in the middle of the sample
+        let (x, lower, upper) = band[49];
+        assert_eq!(x, 49.0);
+        assert!((upper - lower - 2.0 * eps).abs() < 1e-12);
+        assert!((lower - (0.5 - eps)).abs() < 1e-12);
+        assert_eq!(band[0].1, 0.0);
+        assert_eq!(band[99].2, 1.0);
+
+        // the band narrows as the sample grows
+        let data: Vec<f64> = (0..400).map(|i| i as f64).collect();
+        let (_, lower, upper) = EmpiricalCdf::new(&data).confidence_band(0.05)[199];
+        assert!((upper - lower - eps).abs() < 1e-12);
+
+        // ties give a single point at the top of the step
+        let band: Vec<(f64, f64, f64)> = EmpiricalCdf::new(&[1.0, 2.0, 2.0, 3.0]).confidence_band(0.5);
+        assert_eq!(band.len(), 3);
+        assert!((band[1].1 - (0.75 - sqrt(log(4.0) / 8.0))).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,387 @@
+//! A single type over the extreme value families, for code that handles a distribution whose
+//! family is only known at runtime (e.g. selected from the data).
+use std::fmt;
+
+use crate::dist::distutils::*;
+use crate::dist::fit::{FitError, FitMethod, Fittable};
+use crate::dist::params::{ParamError, ParseDistError};
+use crate::dist::gumbel::Gumbel;
+use crate::dist::frechet::Frechet;
+use crate::dist::weibull::Weibull;
+use crate::dist::gev::GEV;
+
+use rand::Rng;
+
+/// One of the extreme value distributions of the crate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtremeValue {
+    /// Gumbel distribution
+    Gumbel(Gumbel),
+    /// Fréchet distribution
+    Frechet(Frechet),
+    /// (reversed) Weibull distribution
+    Weibull(Weibull),
+    /// Generalized extreme value distribution
+    GEV(GEV),
+}
+
+/// The extreme value families, to select one at runtime from its name (see `ExtremeValue::from_params`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtremeValueType {
+    /// Gumbel distribution, parameters `[loc, scale]`
+    Gumbel,
+    /// Fréchet distribution, parameters `[loc, scale, shape]`
+    Frechet,
+    /// (reversed) Weibull distribution, parameters `[loc, scale, shape]`
+    Weibull,
+    /// Generalized extreme value distribution, parameters `[loc, scale, shape]`
+    GEV,
+}
+
+impl ExtremeValue {
+    /// Create the distribution of the family `which` from raw parameters, validated by the
+    /// `from_params` constructor of the family (number of parameters, finite values, positive
+    /// scale and, for the Fréchet and Weibull distributions, positive shape).
+    pub fn from_params(params: &[f64], which: ExtremeValueType) -> Result<ExtremeValue, ParamError> {
+        match which {
+            ExtremeValueType::Gumbel => Gumbel::from_params(params).map(ExtremeValue::Gumbel),
+            ExtremeValueType::Frechet => Frechet::from_params(params).map(ExtremeValue::Frechet),
+            ExtremeValueType::Weibull => Weibull::from_params(params).map(ExtremeValue::Weibull),
+            ExtremeValueType::GEV => GEV::from_params(params).map(ExtremeValue::GEV),
+        }
+    }
+
+    /// Number of parameters of the family
+    pub fn n_params(&self) -> usize {
+        match self {
+            ExtremeValue::Gumbel(_) => 2,
+            ExtremeValue::Frechet(_) | ExtremeValue::Weibull(_) | ExtremeValue::GEV(_) => 3,
+        }
+    }
+
+    /// Log-likelihood of the data under the distribution ($-\infty$ if an observation lies outside of the support).
+    pub fn log_likelihood(&self, data: &[f64]) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.log_likelihood(data),
+            ExtremeValue::Frechet(dist) => dist.log_likelihood(data),
+            ExtremeValue::Weibull(dist) => dist.log_likelihood(data),
+            ExtremeValue::GEV(dist) => dist.log_likelihood(data),
+        }
+    }
+
+    /// Akaike information criterion $AIC = 2 k - 2 \ell$ of the distribution on the data, with $k$
+    /// the number of parameters and $\ell$ the log-likelihood.
+    pub fn aic(&self, data: &[f64]) -> f64 {
+        2.0 * self.n_params() as f64 - 2.0 * self.log_likelihood(data)
+    }
+}
+
+/// `(pdf(x), cdf(x))` of the distribution of the family `which` with the raw parameters `params`,
+/// or `None` if the parameters are invalid (see `ExtremeValue::from_params`), `x` is not finite or
+/// outside of the support, or the result is not finite (e.g. an overflow with extreme parameters).
+/// It never panics, whatever the inputs and even with the `strict-domain` feature, so it can sanitize
+/// external inputs and serve as a fuzz target of the evaluation.
+pub fn evaluate_safe(params: &[f64], x: f64, which: ExtremeValueType) -> Option<(f64, f64)> {
+    let dist: ExtremeValue = ExtremeValue::from_params(params, which).ok()?;
+    if !x.is_finite() || !dist.is_in_support(x) {
+        return None;
+    }
+    let (pdf, cdf) = dist.evaluate(x);
+    if pdf.is_finite() && cdf.is_finite() { Some((pdf, cdf)) } else { None }
+}
+
+/// Build a distribution from its kind and `key = value` pairs, e.g. read from a simple
+/// configuration file, without any serialization dependency. The kind is one of `gumbel`,
+/// `frechet`, `weibull` and `gev` (ignoring case), and the keys are `loc`, `scale` and, except for
+/// the Gumbel distribution, `shape`. Missing keys take the default values `loc = 0`, `scale = 1`
+/// and `shape = 1` for the Fréchet and Weibull distributions (`shape = 0` for the GEV, i.e. the
+/// Gumbel distribution). The parameters are then validated as by `ExtremeValue::from_params`.
+pub fn from_kv(kind: &str, pairs: &[(&str, f64)]) -> Result<ExtremeValue, ParseDistError> {
+    let which: ExtremeValueType = match kind.to_ascii_lowercase().as_str() {
+        "gumbel" => ExtremeValueType::Gumbel,
+        "frechet" => ExtremeValueType::Frechet,
+        "weibull" => ExtremeValueType::Weibull,
+        "gev" => ExtremeValueType::GEV,
+        _ => return Err(ParseDistError::UnknownKind(kind.to_string())),
+    };
+    let keys: [&str; 3] = ["loc", "scale", "shape"];
+    let mut params: Vec<f64> = match which {
+        ExtremeValueType::Gumbel => vec![0.0, 1.0],
+        ExtremeValueType::GEV => vec![0.0, 1.0, 0.0],
+        ExtremeValueType::Frechet | ExtremeValueType::Weibull => vec![0.0, 1.0, 1.0],
+    };
+    let keys: &[&str] = &keys[..params.len()];
+    let mut given: Vec<bool> = vec![false; keys.len()];
+    for (key, value) in pairs.iter() {
+        let pos: usize = keys.iter().position(|k| k == key).ok_or_else(|| ParseDistError::UnknownKey(key.to_string()))?;
+        if given[pos] {
+            return Err(ParseDistError::DuplicateKey(key.to_string()));
+        }
+        given[pos] = true;
+        params[pos] = *value;
+    }
+    ExtremeValue::from_params(&params, which).map_err(ParseDistError::InvalidParams)
+}
+
+/// Fit the candidate families to the data with the given method and return the one with the
+/// smallest AIC (see `ExtremeValue::aic`) along with its AIC, whatever the method used for fitting.
+/// The candidates are, in this order, the Gumbel, GEV, Fréchet and Weibull distributions; the
+/// families for which the method is not available or the fit fails are skipped, and on a tie the
+/// earliest candidate is kept, so the Gumbel distribution is preferred over the GEV distribution
+/// with the same likelihood.
+/// Returns the error of the Gumbel fit if no candidate could be fitted.
+pub fn best_fit(data: &[f64], method: FitMethod) -> Result<(ExtremeValue, f64), FitError> {
+    let candidates: [Result<ExtremeValue, FitError>; 4] = [
+        Gumbel::fit(data, method).map(ExtremeValue::Gumbel),
+        GEV::fit(data, method).map(ExtremeValue::GEV),
+        Frechet::fit(data, method).map(ExtremeValue::Frechet),
+        Weibull::fit(data, method).map(ExtremeValue::Weibull),
+    ];
+    let mut best: Option<(ExtremeValue, f64)> = None;
+    let mut first_err: Option<FitError> = None;
+    for candidate in candidates {
+        match candidate {
+            Ok(dist) => {
+                let aic: f64 = dist.aic(data);
+                if aic.is_finite() && best.is_none_or(|(_, best_aic)| aic < best_aic) {
+                    best = Some((dist, aic));
+                }
+            }
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+    best.ok_or(first_err.unwrap_or(FitError::NoConvergence))
+}
+
+impl fmt::Display for ExtremeValue {
+    /// Display the underlying distribution, honoring the precision of the formatter.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.fmt(f),
+            ExtremeValue::Frechet(dist) => dist.fmt(f),
+            ExtremeValue::Weibull(dist) => dist.fmt(f),
+            ExtremeValue::GEV(dist) => dist.fmt(f),
+        }
+    }
+}
+
+impl DistQuant for ExtremeValue {
+    fn cdf(&self, x: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.cdf(x),
+            ExtremeValue::Frechet(dist) => dist.cdf(x),
+            ExtremeValue::Weibull(dist) => dist.cdf(x),
+            ExtremeValue::GEV(dist) => dist.cdf(x),
+        }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.pdf(x),
+            ExtremeValue::Frechet(dist) => dist.pdf(x),
+            ExtremeValue::Weibull(dist) => dist.pdf(x),
+            ExtremeValue::GEV(dist) => dist.pdf(x),
+        }
+    }
+
+    fn quantile(&self, x: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.quantile(x),
+            ExtremeValue::Frechet(dist) => dist.quantile(x),
+            ExtremeValue::Weibull(dist) => dist.quantile(x),
+            ExtremeValue::GEV(dist) => dist.quantile(x),
+        }
+    }
+
+    fn quantile_density(&self, p: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.quantile_density(p),
+            ExtremeValue::Frechet(dist) => dist.quantile_density(p),
+            ExtremeValue::Weibull(dist) => dist.quantile_density(p),
+            ExtremeValue::GEV(dist) => dist.quantile_density(p),
+        }
+    }
+
+    fn random(&self, seed: RandomSeed) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.random(seed),
+            ExtremeValue::Frechet(dist) => dist.random(seed),
+            ExtremeValue::Weibull(dist) => dist.random(seed),
+            ExtremeValue::GEV(dist) => dist.random(seed),
+        }
+    }
+
+    fn evaluate(&self, x: f64) -> (f64, f64) {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.evaluate(x),
+            ExtremeValue::Frechet(dist) => dist.evaluate(x),
+            ExtremeValue::Weibull(dist) => dist.evaluate(x),
+            ExtremeValue::GEV(dist) => dist.evaluate(x),
+        }
+    }
+
+    fn log_cdf(&self, x: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.log_cdf(x),
+            ExtremeValue::Frechet(dist) => dist.log_cdf(x),
+            ExtremeValue::Weibull(dist) => dist.log_cdf(x),
+            ExtremeValue::GEV(dist) => dist.log_cdf(x),
+        }
+    }
+
+    fn log_sf(&self, x: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.log_sf(x),
+            ExtremeValue::Frechet(dist) => dist.log_sf(x),
+            ExtremeValue::Weibull(dist) => dist.log_sf(x),
+            ExtremeValue::GEV(dist) => dist.log_sf(x),
+        }
+    }
+
+    fn gumbel_reduced(&self, x: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.gumbel_reduced(x),
+            ExtremeValue::Frechet(dist) => dist.gumbel_reduced(x),
+            ExtremeValue::Weibull(dist) => dist.gumbel_reduced(x),
+            ExtremeValue::GEV(dist) => dist.gumbel_reduced(x),
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.sf(x),
+            ExtremeValue::Frechet(dist) => dist.sf(x),
+            ExtremeValue::Weibull(dist) => dist.sf(x),
+            ExtremeValue::GEV(dist) => dist.sf(x),
+        }
+    }
+
+    fn support(&self) -> (f64, f64) {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.support(),
+            ExtremeValue::Frechet(dist) => dist.support(),
+            ExtremeValue::Weibull(dist) => dist.support(),
+            ExtremeValue::GEV(dist) => dist.support(),
+        }
+    }
+
+    fn is_in_support(&self, x: f64) -> bool {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.is_in_support(x),
+            ExtremeValue::Frechet(dist) => dist.is_in_support(x),
+            ExtremeValue::Weibull(dist) => dist.is_in_support(x),
+            ExtremeValue::GEV(dist) => dist.is_in_support(x),
+        }
+    }
+
+    fn sample_n(&self, n: usize, seed: RandomSeed) -> Vec<f64> {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.sample_n(n, seed),
+            ExtremeValue::Frechet(dist) => dist.sample_n(n, seed),
+            ExtremeValue::Weibull(dist) => dist.sample_n(n, seed),
+            ExtremeValue::GEV(dist) => dist.sample_n(n, seed),
+        }
+    }
+
+    fn random_with<R: Rng>(&self, rng: R) -> f64 where Self: Sized {
+        match self {
+            ExtremeValue::Gumbel(dist) => dist.random_with(rng),
+            ExtremeValue::Frechet(dist) => dist.random_with(rng),
+            ExtremeValue::Weibull(dist) => dist.random_with(rng),
+            ExtremeValue::GEV(dist) => dist.random_with(rng),
+        }
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extreme_value_dispatch_test() {
+        let gev: GEV = GEV::new(1.0, 2.0, 0.2);
+        let dist: ExtremeValue = ExtremeValue::GEV(gev);
+        assert_eq!(dist.cdf(3.0), gev.cdf(3.0));
+        assert_eq!(dist.pdf(3.0), gev.pdf(3.0));
+        assert_eq!(dist.quantile(0.3), gev.quantile(0.3));
+        assert_eq!(dist.support(), gev.support());
+        assert_eq!(dist.sample_n(10, RandomSeed::Seed(1)), gev.sample_n(10, RandomSeed::Seed(1)));
+        assert_eq!(dist.n_params(), 3);
+        assert_eq!(ExtremeValue::Gumbel(Gumbel::new(1.0, 2.0)).n_params(), 2);
+        let data: Vec<f64> = vec![1.0, 2.0, 4.0];
+        assert_eq!(dist.aic(&data), 6.0 - 2.0 * gev.log_likelihood(&data));
+        assert_eq!(format!("{:.1}", dist), format!("{:.1}", gev));
+    }
+
+    #[test]
+    fn best_fit_test() {
+        let data: Vec<f64> = Gumbel::new(0.5, 2.0).sample_n(500, RandomSeed::Seed(2));
+        let (dist, aic) = best_fit(&data, FitMethod::Mle).unwrap();
+        assert!(matches!(dist, ExtremeValue::Gumbel(_)));
+        assert_eq!(aic, dist.aic(&data));
+
+        let data: Vec<f64> = Frechet::new(0.0, 1.0, 3.0).sample_n(500, RandomSeed::Seed(1));
+        let (dist, _) = best_fit(&data, FitMethod::Mle).unwrap();
+        match dist {
+            ExtremeValue::Frechet(_) => (),
+            ExtremeValue::GEV(gev) => assert!(gev.shape() > 0.0),
+            _ => panic!("unexpected family: {:?}", dist),
+        }
+
+        // only the Gumbel and GEV distributions have a PWM fit
+        let (dist, _) = best_fit(&data, FitMethod::Pwm).unwrap();
+        assert!(matches!(dist, ExtremeValue::Gumbel(_) | ExtremeValue::GEV(_)));
+        assert_eq!(best_fit(&[1.0], FitMethod::Mle).err(), Some(FitError::InsufficientData));
+    }
+
+    #[test]
+    fn evaluate_safe_test() {
+        let gev: GEV = GEV::new(1.0, 2.0, 0.2);
+        assert_eq!(evaluate_safe(&[1.0, 2.0, 0.2], 3.0, ExtremeValueType::GEV), Some(gev.evaluate(3.0)));
+        assert_eq!(evaluate_safe(&[1.0, 2.0], 3.0, ExtremeValueType::Gumbel), Some(Gumbel::new(1.0, 2.0).evaluate(3.0)));
+        let types: [ExtremeValueType; 4] = [ExtremeValueType::Gumbel, ExtremeValueType::Frechet, ExtremeValueType::Weibull, ExtremeValueType::GEV];
+        let bad: [f64; 5] = [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -1.0, 0.0];
+        for &which in types.iter() {
+            for &val in bad.iter() {
+                // invalid scale
+                assert_eq!(evaluate_safe(&[0.0, val, 1.0], 0.5, which), None);
+                // invalid location, shape or point
+                assert_eq!(evaluate_safe(&[f64::NAN, 1.0, val], 0.5, which), None);
+                assert_eq!(evaluate_safe(&[0.0, 1.0, 1.0], val / 0.0, which), None);
+            }
+            assert_eq!(evaluate_safe(&[], 0.5, which), None);
+            // a subnormal scale is valid, and the evaluation at the far end of the support is finite or `None`
+            if let Some((pdf, cdf)) = evaluate_safe(&[0.0, 1e-320, 1.0], 0.5, which) {
+                assert!(pdf.is_finite() && cdf.is_finite());
+            }
+            assert_eq!(evaluate_safe(&[0.0, 1.0, 1.0, 1.0], 0.5, which), None);
+        }
+        // outside of the support, and non-positive shapes of the Fréchet and Weibull distributions
+        assert_eq!(evaluate_safe(&[0.0, 1.0, 0.5], -3.0, ExtremeValueType::GEV), None);
+        assert_eq!(evaluate_safe(&[0.0, 1.0, 2.0], -1.0, ExtremeValueType::Frechet), None);
+        assert_eq!(evaluate_safe(&[0.0, 1.0, -2.0], 1.0, ExtremeValueType::Frechet), None);
+        assert_eq!(evaluate_safe(&[0.0, 1.0, 0.0], -1.0, ExtremeValueType::Weibull), None);
+    }
+
+    #[test]
+    fn from_kv_test() {
+        assert_eq!(from_kv("gumbel", &[("loc", 0.5), ("scale", 2.0)]), Ok(ExtremeValue::Gumbel(Gumbel::new(0.5, 2.0))));
+        assert_eq!(from_kv("Frechet", &[("shape", 3.0), ("loc", 1.0)]), Ok(ExtremeValue::Frechet(Frechet::new(1.0, 1.0, 3.0))));
+        assert_eq!(from_kv("weibull", &[("scale", 2.0)]), Ok(ExtremeValue::Weibull(Weibull::new(0.0, 2.0, 1.0))));
+        assert_eq!(from_kv("GEV", &[("loc", 1.0), ("scale", 2.0), ("shape", 0.2)]), Ok(ExtremeValue::GEV(GEV::new(1.0, 2.0, 0.2))));
+        // defaults for the missing keys
+        assert_eq!(from_kv("gev", &[]), Ok(ExtremeValue::GEV(GEV::new(0.0, 1.0, 0.0))));
+        assert_eq!(from_kv("gumbel", &[]), Ok(ExtremeValue::Gumbel(Gumbel::new(0.0, 1.0))));
+        // errors
+        assert_eq!(from_kv("normal", &[]), Err(ParseDistError::UnknownKind("normal".to_string())));
+        assert_eq!(from_kv("gumbel", &[("shape", 0.1)]), Err(ParseDistError::UnknownKey("shape".to_string())));
+        assert_eq!(from_kv("gev", &[("location", 0.1)]), Err(ParseDistError::UnknownKey("location".to_string())));
+        assert_eq!(from_kv("gev", &[("loc", 0.1), ("loc", 0.2)]), Err(ParseDistError::DuplicateKey("loc".to_string())));
+        assert_eq!(from_kv("gev", &[("scale", -1.0)]), Err(ParseDistError::InvalidParams(ParamError::NonPositiveScale)));
+        assert_eq!(from_kv("frechet", &[("shape", 0.0)]), Err(ParseDistError::InvalidParams(ParamError::NonPositiveShape)));
+        assert_eq!(format!("{}", from_kv("gev", &[("k", 1.0)]).unwrap_err()), "unknown parameter 'k'");
+    }
+}
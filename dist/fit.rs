@@ -0,0 +1,336 @@
+//! Tools shared by the fitting routines of the distributions (error type and optimizer).
+use std::fmt;
+
+/// Errors that can be returned when fitting a distribution to data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FitError {
+    /// Not enough observations to fit the distribution.
+    InsufficientData,
+    /// The data contains NaN or infinite values.
+    NonFiniteData,
+    /// The optimizer did not converge to a valid set of parameters.
+    NoConvergence,
+    /// The arguments do not define a valid distribution (e.g. probabilities outside of (0, 1)).
+    InvalidInput,
+    /// The fitting method is not available for this distribution.
+    UnsupportedMethod,
+    /// The data takes too few distinct values (e.g. constant data) for the parameters to be
+    /// identified, which would give a zero scale.
+    Degenerate,
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitError::InsufficientData => write!(f, "not enough observations to fit the distribution"),
+            FitError::NonFiniteData => write!(f, "the data contains NaN or infinite values"),
+            FitError::NoConvergence => write!(f, "the optimizer did not converge"),
+            FitError::InvalidInput => write!(f, "the arguments do not define a valid distribution"),
+            FitError::UnsupportedMethod => write!(f, "the fitting method is not available for this distribution"),
+            FitError::Degenerate => write!(f, "the data takes too few distinct values to fit the distribution"),
+        }
+    }
+}
+
+impl std::error::Error for FitError {}
+
+/// The available fitting methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitMethod {
+    /// Maximum likelihood
+    Mle,
+    /// Probability weighted moments (equivalently L-moments)
+    Pwm,
+    /// Matching of the mean and variance (method of moments)
+    MomentMatching,
+}
+
+/// Uniform interface over the fitting routines of the distributions.
+pub trait Fittable: Sized {
+    /// Fit the distribution to the data with the given method, dispatching to the concrete
+    /// fitter (e.g. `GEV::fit_mle`). Returns `FitError::UnsupportedMethod` if the method is not
+    /// available for the distribution.
+    fn fit(data: &[f64], method: FitMethod) -> Result<Self, FitError>;
+}
+
+/// Fit the distribution (e.g. `GEV` or `Gumbel`) separately on the windows `data[start..start + window]`
+/// for `start` $= 0$, `step`, $2 \cdot$ `step`, ... as long as the window fits in the data, returning
+/// the start index and the fit of each window. Watching the parameters move across overlapping
+/// windows screens for nonstationarity (e.g. a drifting location) without fitting a full
+/// non-stationary model. Empty if the data is shorter than `window`; `window` and `step` must be positive.
+pub fn fit_sliding<D: Fittable>(data: &[f64], window: usize, step: usize, method: FitMethod) -> Vec<(usize, Result<D, FitError>)> {
+    domain!(window > 0 && step > 0);
+    if data.len() < window {
+        return Vec::new();
+    }
+    (0..=data.len() - window).step_by(step)
+        .map(|start| (start, D::fit(&data[start..start + window], method)))
+        .collect()
+}
+
+/// Check that the sample has at least `min_len` observations, all of them finite.
+pub(crate) fn check_data(data: &[f64], min_len: usize) -> Result<(), FitError> {
+    if data.len() < min_len {
+        return Err(FitError::InsufficientData);
+    }
+    if data.iter().any(|x| !x.is_finite()) {
+        return Err(FitError::NonFiniteData);
+    }
+    Ok(())
+}
+
+/// Check that the sample takes at least `min_distinct` distinct values, as many as the parameters
+/// of the distribution, so that a constant sample is rejected rather than fitted with a zero scale.
+pub(crate) fn check_distinct(data: &[f64], min_distinct: usize) -> Result<(), FitError> {
+    let mut sorted: Vec<f64> = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted.dedup();
+    if sorted.len() < min_distinct {
+        return Err(FitError::Degenerate);
+    }
+    Ok(())
+}
+
+/// Sample mean and (unbiased) sample standard deviation.
+pub(crate) fn mean_sd(data: &[f64]) -> (f64, f64) {
+    let n: f64 = data.len() as f64;
+    let mean: f64 = data.iter().sum::<f64>() / n;
+    let var: f64 = data.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1.0);
+    (mean, var.sqrt())
+}
+
+/// Result of a maximum likelihood fit with diagnostics of its reliability.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitReport<D> {
+    /// the fitted distribution
+    pub dist: D,
+    /// log-likelihood of the data at the estimate
+    pub log_likelihood: f64,
+    /// observed information matrix, the Hessian of the negative log-likelihood at the estimate
+    /// (by central finite differences), in the order of the parameters of the distribution
+    pub observed_information: Vec<Vec<f64>>,
+    /// condition number $\frac{\max_i |\lambda_i|}{\min_i |\lambda_i|}$ of the observed information,
+    /// with $\lambda_i$ its eigenvalues. A large value (say above $10^8$) means that the likelihood
+    /// is nearly flat along some direction of the parameters (near non-identifiability, e.g. a shape
+    /// near a boundary of the regular region), so that the standard errors derived from the
+    /// inverse of the information are unreliable. Infinite if the information is singular or could not be computed.
+    pub hessian_condition_number: f64,
+}
+
+/// Hessian of `func` at `x` by central finite differences, with the step $10^{-4} \max(|x_i|, 1)$
+/// along the coordinate $i$.
+pub(crate) fn numerical_hessian<F: Fn(&[f64]) -> f64>(func: F, x: &[f64]) -> Vec<Vec<f64>> {
+    let dim: usize = x.len();
+    let steps: Vec<f64> = x.iter().map(|v| 1e-4 * v.abs().max(1.0)).collect();
+    let eval = |di: (usize, f64), dj: (usize, f64)| -> f64 {
+        let mut point: Vec<f64> = x.to_vec();
+        point[di.0] += di.1;
+        point[dj.0] += dj.1;
+        func(&point)
+    };
+    let center: f64 = func(x);
+    let mut hessian: Vec<Vec<f64>> = vec![vec![0.0; dim]; dim];
+    for i in 0..dim {
+        let hi: f64 = steps[i];
+        hessian[i][i] = (eval((i, hi), (i, 0.0)) - 2.0 * center + eval((i, - hi), (i, 0.0))) / (hi * hi);
+        for j in 0..i {
+            let hj: f64 = steps[j];
+            let val: f64 = (eval((i, hi), (j, hj)) - eval((i, hi), (j, - hj)) - eval((i, - hi), (j, hj)) + eval((i, - hi), (j, - hj))) / (4.0 * hi * hj);
+            hessian[i][j] = val;
+            hessian[j][i] = val;
+        }
+    }
+    hessian
+}
+
+/// Eigenvalues of the symmetric matrix `m`, by the cyclic Jacobi method.
+pub(crate) fn symmetric_eigenvalues(m: &[Vec<f64>]) -> Vec<f64> {
+    let dim: usize = m.len();
+    let mut a: Vec<Vec<f64>> = m.to_vec();
+    for _ in 0..100 {
+        let off: f64 = (0..dim).flat_map(|i| (0..i).map(move |j| (i, j))).map(|(i, j)| a[i][j] * a[i][j]).sum::<f64>();
+        let diag: f64 = (0..dim).map(|i| a[i][i] * a[i][i]).sum::<f64>();
+        if off <= 1e-30 * diag || off == 0.0 {
+            break;
+        }
+        for p in 0..dim {
+            for q in (p + 1)..dim {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+                // rotation annihilating a[p][q]
+                let theta: f64 = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t: f64 = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c: f64 = 1.0 / (t * t + 1.0).sqrt();
+                let s: f64 = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                a[p] = row_p.iter().zip(&row_q).map(|(apk, aqk)| c * apk - s * aqk).collect();
+                a[q] = row_p.iter().zip(&row_q).map(|(apk, aqk)| s * apk + c * aqk).collect();
+            }
+        }
+    }
+    (0..dim).map(|i| a[i][i]).collect()
+}
+
+/// Condition number $\frac{\max_i |\lambda_i|}{\min_i |\lambda_i|}$ of the symmetric matrix `m`,
+/// infinite if it is singular or not finite.
+pub(crate) fn condition_number(m: &[Vec<f64>]) -> f64 {
+    if m.iter().flatten().any(|v| !v.is_finite()) {
+        return f64::INFINITY;
+    }
+    let abs_eig: Vec<f64> = symmetric_eigenvalues(m).iter().map(|l| l.abs()).collect();
+    let max: f64 = abs_eig.iter().cloned().fold(0.0, f64::max);
+    let min: f64 = abs_eig.iter().cloned().fold(f64::INFINITY, f64::min);
+    if min == 0.0 { f64::INFINITY } else { max / min }
+}
+
+/// Minimize `func` with the Nelder-Mead simplex method, starting from `start` with an initial
+/// simplex built by moving each coordinate by `step`.
+/// Returns the minimizer and the minimum value, or `None` if no finite value was ever reached.
+pub(crate) fn nelder_mead<F: Fn(&[f64]) -> f64>(func: F, start: &[f64], step: &[f64], max_iter: usize, tol: f64) -> Option<(Vec<f64>, f64)> {
+    let dim: usize = start.len();
+    // objective where NaN is treated as +infinity, so invalid regions are always rejected
+    let obj = |x: &[f64]| -> f64 {
+        let val: f64 = func(x);
+        if val.is_nan() { f64::INFINITY } else { val }
+    };
+
+    let mut simplex: Vec<Vec<f64>> = vec![start.to_vec()];
+    for i in 0..dim {
+        let mut point: Vec<f64> = start.to_vec();
+        point[i] += step[i];
+        simplex.push(point);
+    }
+    let mut values: Vec<f64> = simplex.iter().map(|p| obj(p)).collect();
+
+    for _ in 0..max_iter {
+        // order the simplex from best to worst
+        let mut order: Vec<usize> = (0..=dim).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+
+        if values[0].is_finite() && (values[dim] - values[0]).abs() <= tol * (1.0 + values[0].abs()) {
+            break;
+        }
+
+        // centroid of all points but the worst
+        let mut centroid: Vec<f64> = vec![0.0; dim];
+        for point in simplex.iter().take(dim) {
+            for (c, p) in centroid.iter_mut().zip(point) {
+                *c += p / dim as f64;
+            }
+        }
+        let towards = |coef: f64| -> Vec<f64> {
+            centroid.iter().zip(&simplex[dim]).map(|(c, w)| c + coef * (w - c)).collect()
+        };
+
+        let reflected: Vec<f64> = towards(-1.0);
+        let f_reflected: f64 = obj(&reflected);
+        if f_reflected < values[0] {
+            let expanded: Vec<f64> = towards(-2.0);
+            let f_expanded: f64 = obj(&expanded);
+            if f_expanded < f_reflected {
+                simplex[dim] = expanded;
+                values[dim] = f_expanded;
+            } else {
+                simplex[dim] = reflected;
+                values[dim] = f_reflected;
+            }
+        } else if f_reflected < values[dim - 1] {
+            simplex[dim] = reflected;
+            values[dim] = f_reflected;
+        } else {
+            let contracted: Vec<f64> = if f_reflected < values[dim] { towards(-0.5) } else { towards(0.5) };
+            let f_contracted: f64 = obj(&contracted);
+            if f_contracted < values[dim].min(f_reflected) {
+                simplex[dim] = contracted;
+                values[dim] = f_contracted;
+            } else {
+                // shrink everything towards the best point
+                for i in 1..=dim {
+                    let shrunk: Vec<f64> = simplex[0].iter().zip(&simplex[i]).map(|(b, p)| b + 0.5 * (p - b)).collect();
+                    values[i] = obj(&shrunk);
+                    simplex[i] = shrunk;
+                }
+            }
+        }
+    }
+
+    let best: usize = (0..=dim).min_by(|&a, &b| values[a].total_cmp(&values[b]))?;
+    if values[best].is_finite() {
+        Some((simplex[best].clone(), values[best]))
+    } else {
+        None
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::distutils::{DistQuant, RandomSeed};
+    use crate::dist::gev::GEV;
+    use crate::dist::gumbel::Gumbel;
+
+    #[test]
+    fn nelder_mead_quadratic_test() {
+        let quad = |x: &[f64]| (x[0] - 1.0).powi(2) + 2.0 * (x[1] + 3.0).powi(2);
+        let (x_min, f_min) = nelder_mead(quad, &[0.0, 0.0], &[0.5, 0.5], 1000, 1e-14).unwrap();
+        assert!((x_min[0] - 1.0).abs() < 1e-5);
+        assert!((x_min[1] + 3.0).abs() < 1e-5);
+        assert!(f_min < 1e-9);
+    }
+
+    #[test]
+    fn fit_sliding_test() {
+        // location increasing by 0.01 per observation
+        let data: Vec<f64> = Gumbel::new(0.0, 1.0).sample_n(600, RandomSeed::Seed(3)).iter()
+            .enumerate().map(|(i, x)| x + 0.01 * i as f64).collect();
+        let fits: Vec<(usize, Result<GEV, FitError>)> = fit_sliding(&data, 200, 50, FitMethod::Mle);
+        assert_eq!(fits.iter().map(|(start, _)| *start).collect::<Vec<usize>>(), vec![0, 50, 100, 150, 200, 250, 300, 350, 400]);
+        let locs: Vec<f64> = fits.iter().map(|(_, fit)| fit.as_ref().unwrap().loc).collect();
+        assert!(locs.windows(2).all(|w| w[1] > w[0]));
+        assert!((locs[8] - locs[0] - 4.0).abs() < 1.0);
+        let gumbel_fits: Vec<(usize, Result<Gumbel, FitError>)> = fit_sliding(&data, 300, 300, FitMethod::Pwm);
+        assert_eq!(gumbel_fits.len(), 2);
+        assert!(gumbel_fits[1].1.as_ref().unwrap().loc > gumbel_fits[0].1.as_ref().unwrap().loc + 2.0);
+        // the failure of a window is reported with its start index
+        let short: Vec<(usize, Result<GEV, FitError>)> = fit_sliding(&[1.0, 1.0, 1.0, 2.0, 3.0, 4.0], 3, 3, FitMethod::Mle);
+        assert_eq!(short[0], (0, Err(FitError::Degenerate)));
+        assert!(short[1].1.is_ok());
+        assert!(fit_sliding::<GEV>(&data, 601, 1, FitMethod::Mle).is_empty());
+    }
+
+    #[test]
+    fn check_data_test() {
+        assert_eq!(check_data(&[1.0], 2), Err(FitError::InsufficientData));
+        assert_eq!(check_data(&[1.0, f64::NAN], 2), Err(FitError::NonFiniteData));
+        assert_eq!(check_data(&[1.0, 2.0], 2), Ok(()));
+        assert_eq!(check_distinct(&[5.0; 10], 2), Err(FitError::Degenerate));
+        assert_eq!(check_distinct(&[1.0, 2.0, 1.0, 2.0], 3), Err(FitError::Degenerate));
+        assert_eq!(check_distinct(&[1.0, 2.0, 1.0, 3.0], 3), Ok(()));
+    }
+
+    #[test]
+    fn hessian_condition_number_test() {
+        // f(x, y) = x^2 + x y + 2 y^2 has the Hessian [[2, 1], [1, 4]], with eigenvalues 3 -+ sqrt(2)
+        let hessian: Vec<Vec<f64>> = numerical_hessian(|p| p[0] * p[0] + p[0] * p[1] + 2.0 * p[1] * p[1], &[0.3, -2.0]);
+        assert!((hessian[0][0] - 2.0).abs() < 1e-6 && (hessian[0][1] - 1.0).abs() < 1e-6 && (hessian[1][1] - 4.0).abs() < 1e-6);
+        let mut eig: Vec<f64> = symmetric_eigenvalues(&hessian);
+        eig.sort_by(|a, b| a.total_cmp(b));
+        assert!((eig[0] - (3.0 - 2.0_f64.sqrt())).abs() < 1e-6 && (eig[1] - (3.0 + 2.0_f64.sqrt())).abs() < 1e-6);
+        let m: Vec<Vec<f64>> = vec![vec![4.0, 1.0, 0.0], vec![1.0, 3.0, 1.0], vec![0.0, 1.0, 2.0]];
+        let eig: Vec<f64> = symmetric_eigenvalues(&m);
+        // the trace and the determinant are preserved
+        assert!((eig.iter().sum::<f64>() - 9.0).abs() < 1e-12 && (eig.iter().product::<f64>() - 18.0).abs() < 1e-10);
+        assert_eq!(condition_number(&[vec![2.0, 0.0], vec![0.0, -8.0]]), 4.0);
+        assert_eq!(condition_number(&[vec![1.0, 1.0], vec![1.0, 1.0]]), f64::INFINITY);
+        assert_eq!(condition_number(&[vec![f64::NAN]]), f64::INFINITY);
+    }
+}
@@ -1,14 +1,22 @@
 //! The Fréchet Distribution
-use libm::{exp, log, pow};
+use std::fmt;
+
+use libm::{exp, log, log1p, nextafter, pow, tgamma};
 
 use crate::dist::distutils::*;
+use crate::dist::viz::scale_to_peak;
+use crate::dist::constants::EULER_GAMMA;
+use crate::dist::fit::{check_data, nelder_mead, FitError, FitMethod, Fittable};
+use crate::dist::gev::GEV;
+use crate::dist::weibull::Weibull;
+use crate::dist::params::{check_params, decode, encode, DecodeError, ParamError};
 
 use rand_chacha::ChaCha8Rng;
 use rand::SeedableRng;
 use rand::Rng;
 
 /// Fréchet Dist. struct
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Frechet {
     /// location parameter
     pub loc:   f64,
@@ -27,16 +35,70 @@ impl Frechet {
         Frechet{loc, scale, shape}
     }
 
-    /// Obtain the location parameter
-    #[inline(always)]
-    pub fn loc(&self) -> f64 {
-        self.loc
+    /// Parameters packed into a vector, in the order (loc, scale, shape).
+    pub fn as_params(&self) -> Vec<f64> {
+        vec![self.loc, self.scale, self.shape]
     }
 
-    /// Obtain the scale parameter
-    #[inline(always)]
-    pub fn scale(&self) -> f64 {
-        self.scale
+    /// Bounds `(lo, hi)` of each parameter, in the order (loc, scale, shape) of `as_params`, for bounded
+    /// optimizers. The scale and the shape, both strictly positive, are bounded below by `f64::EPSILON`.
+    pub fn param_bounds() -> Vec<(f64, f64)> {
+        vec![(f64::NEG_INFINITY, f64::INFINITY), (f64::EPSILON, f64::INFINITY), (f64::EPSILON, f64::INFINITY)]
+    }
+
+    /// Create the distribution from parameters packed in the order (loc, scale, shape), e.g. by an optimizer.
+    /// Exactly 3 finite parameters are required and the scale must be positive as well as the shape.
+    pub fn from_params(params: &[f64]) -> Result<Self, ParamError> {
+        check_params(params, 3)?;
+        if params[1] <= 0.0 {
+            return Err(ParamError::NonPositiveScale);
+        }
+        if params[2] <= 0.0 {
+            return Err(ParamError::NonPositiveShape);
+        }
+        Ok(Frechet{ loc: params[0], scale: params[1], shape: params[2] })
+    }
+
+    /// Distribution of $a X + b$ for $X$ following this distribution, $a > 0$: the location becomes
+    /// $a \cdot loc + b$ and the scale $a \cdot scale$. The resulting scale must be finite and
+    /// positive (checked by `domain!`, see `try_affine` for the checked version).
+    pub fn affine(&self, a: f64, b: f64) -> Self {
+        let dist: Frechet = Frechet { loc: a * self.loc + b, scale: a * self.scale, shape: self.shape };
+        domain!(dist.loc.is_finite() && dist.scale.is_finite() && dist.scale > 0.0);
+        dist
+    }
+
+    /// Checked version of `affine`, see `ParamError`.
+    pub fn try_affine(&self, a: f64, b: f64) -> Result<Self, ParamError> {
+        Frechet::from_params(&[a * self.loc + b, a * self.scale, self.shape])
+    }
+
+    /// The same distribution with the given scale parameter, which must be finite and positive
+    /// (checked by `domain!`, see `try_with_scale` for the checked version).
+    pub fn with_scale(&self, scale: f64) -> Self {
+        domain!(scale.is_finite() && scale > 0.0);
+        Frechet { scale, ..*self }
+    }
+
+    /// Checked version of `with_scale`, see `ParamError`.
+    pub fn try_with_scale(&self, scale: f64) -> Result<Self, ParamError> {
+        Frechet::from_params(&[self.loc, scale, self.shape])
+    }
+
+    /// Tag byte identifying the Fréchet distribution in its binary form.
+    pub const TAG: u8 = 1;
+
+    /// Compact binary form: the tag byte `Frechet::TAG` followed by the 3 parameters (see `as_params`)
+    /// as little-endian `f64`s.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        encode(Frechet::TAG, &self.as_params())
+    }
+
+    /// Decode the distribution from its binary form (see `to_le_bytes`), validating the length,
+    /// the tag and the parameters.
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let params: Vec<f64> = decode(bytes, Frechet::TAG, 3)?;
+        Frechet::from_params(&params).map_err(DecodeError::InvalidParams)
     }
 
     /// Obtain the shape parameter
@@ -45,6 +107,202 @@ impl Frechet {
         self.shape
     }
 
+    /// Create the Fréchet distribution going through the three given (probability, quantile) pairs,
+    /// by solving for the GEV (see `GEV::from_quantiles`) and mapping it to the Fréchet parameters
+    /// ($shape = 1 / \zeta$, $scale = \sigma / \zeta$, $loc = \mu - scale$).
+    /// Returns `FitError::InvalidInput` if the implied GEV shape is not positive.
+    pub fn from_quantiles(quantiles: &[(f64, f64); 3]) -> Result<Frechet, FitError> {
+        let gev: GEV = GEV::from_quantiles(quantiles)?;
+        if gev.shape() <= 0.0 {
+            return Err(FitError::InvalidInput);
+        }
+        let scale: f64 = gev.scale() / gev.shape();
+        Ok(Frechet { loc: gev.loc() - scale, scale, shape: 1.0 / gev.shape() })
+    }
+
+    /// Move a quantile that rounded onto the end point $loc$ to the first float above it,
+    /// when `inside` (the probability is positive) and the quantile must lie in the open support.
+    fn stay_inside(&self, x: f64, inside: bool) -> f64 {
+        if inside && x <= self.loc { nextafter(self.loc, f64::INFINITY) } else { x }
+    }
+
+    /// Mode of the Fréchet distribution, $loc + scale \left( \frac{shape}{1 + shape} \right)^{1 / shape}$.
+    pub fn mode(&self) -> f64 {
+        self.loc + self.scale * pow(self.shape / (1.0 + self.shape), 1.0 / self.shape)
+    }
+
+    /// Maximum value of the PDF, attained at the mode (the density is always bounded).
+    pub fn pdf_max(&self) -> f64 {
+        self.pdf(self.mode())
+    }
+
+    /// PDF over `xs` scaled so that its peak `pdf_max` has the height `peak_height`, e.g. to overlay
+    /// the fitted density on a histogram with an arbitrary vertical scale. The maximum of the
+    /// returned values is `peak_height` when `xs` contains the mode.
+    pub fn pdf_scaled(&self, xs: &[f64], peak_height: f64) -> Vec<f64> {
+        scale_to_peak(self.pdf_over(xs), self.pdf_max(), peak_height)
+    }
+
+    /// Reciprocal duality with the (reversed) Weibull distribution: if $Y$ follows this Fréchet
+    /// distribution, then $X = - \frac{1}{Y - loc}$ follows the Weibull distribution with location 0,
+    /// scale $1 / scale$ and the same shape, since
+    /// $P(X \leq x) = P(Y \leq loc - 1/x) = \exp \{ - (- x \cdot scale)^{shape} \}$ for $x < 0$.
+    /// This is the inverse of `Weibull::to_frechet` up to the location of the Weibull distribution.
+    pub fn to_weibull(&self) -> Weibull {
+        Weibull::new(0.0, 1.0 / self.scale, self.shape)
+    }
+
+    /// Whether the moment of the given order is finite, i.e. $shape > order$ (the tail is too heavy
+    /// otherwise). The skewness and kurtosis need the moments of order 3 and 4.
+    pub fn moment_exists(&self, order: u32) -> bool {
+        self.shape > order as f64
+    }
+
+    /// $g_k = \Gamma(1 - k / shape)$, the building block of the Fréchet moments.
+    #[inline(always)]
+    fn g_k(&self, k: f64) -> f64 {
+        tgamma(1.0 - k / self.shape)
+    }
+
+    /// Mean of the Fréchet distribution, $loc + scale \cdot g_1$.
+    /// Only exists for $shape > 1$, otherwise `None` is returned.
+    pub fn mean(&self) -> Option<f64> {
+        if self.moment_exists(1) { Some(self.loc + self.scale * self.g_k(1.0)) } else { None }
+    }
+
+    /// Variance of the Fréchet distribution, $scale^2 (g_2 - g_1^2)$.
+    /// Only exists for $shape > 2$, otherwise `None` is returned.
+    pub fn variance(&self) -> Option<f64> {
+        if self.moment_exists(2) {
+            let g1: f64 = self.g_k(1.0);
+            Some(self.scale * self.scale * (self.g_k(2.0) - g1 * g1))
+        } else {
+            None
+        }
+    }
+
+    /// Median of the Fréchet distribution, $loc + scale (\log 2)^{-1 / shape}$.
+    pub fn median(&self) -> f64 {
+        self.loc + self.scale * pow(log(2.0), - 1.0 / self.shape)
+    }
+
+    /// Differential entropy of the Fréchet distribution, $1 + \frac{\gamma}{shape} + \gamma + \log \frac{scale}{shape}$.
+    pub fn entropy(&self) -> f64 {
+        1.0 + EULER_GAMMA / self.shape + EULER_GAMMA + log(self.scale / self.shape)
+    }
+
+    /// Summary of the distribution, the moments of order $k \geq shape$ being `None`.
+    pub fn summary(&self) -> DistSummary {
+        DistSummary {
+            mean: self.mean(),
+            variance: self.variance(),
+            median: self.median(),
+            mode: self.mode(),
+            skewness: self.skewness(),
+            support: self.support(),
+            entropy: self.entropy(),
+        }
+    }
+
+    /// Skewness of the Fréchet distribution,
+    /// $$ \frac{g_3 - 3 g_1 g_2 + 2 g_1^3}{(g_2 - g_1^2)^{3/2}}, \quad g_k = \Gamma(1 - k / shape). $$
+    /// Only exists for $shape > 3$, otherwise `None` is returned.
+    pub fn skewness(&self) -> Option<f64> {
+        if !self.moment_exists(3) {
+            return None;
+        }
+        let (g1, g2, g3) = (self.g_k(1.0), self.g_k(2.0), self.g_k(3.0));
+        Some((g3 - 3.0 * g1 * g2 + 2.0 * pow(g1, 3.0)) / pow(g2 - g1 * g1, 1.5))
+    }
+
+    /// Excess kurtosis of the Fréchet distribution,
+    /// $$ \frac{g_4 - 4 g_1 g_3 + 6 g_1^2 g_2 - 3 g_1^4}{(g_2 - g_1^2)^2} - 3, \quad g_k = \Gamma(1 - k / shape). $$
+    /// Only exists for $shape > 4$, otherwise `None` is returned.
+    pub fn kurtosis(&self) -> Option<f64> {
+        if !self.moment_exists(4) {
+            return None;
+        }
+        let (g1, g2, g3, g4) = (self.g_k(1.0), self.g_k(2.0), self.g_k(3.0), self.g_k(4.0));
+        let num: f64 = g4 - 4.0 * g1 * g3 + 6.0 * g1 * g1 * g2 - 3.0 * pow(g1, 4.0);
+        Some(num / pow(g2 - g1 * g1, 2.0) - 3.0)
+    }
+
+    /// Log-likelihood of the data under the Fréchet distribution,
+    /// $$ \ell = \sum_i \left( \log \frac{shape}{scale} - (1 + shape) \log y_i - y_i^{-shape} \right), \quad y_i = \frac{x_i - loc}{scale}. $$
+    /// Returns $-\infty$ if an observation is not above $loc$ or if a parameter is not positive.
+    pub fn log_likelihood(&self, data: &[f64]) -> f64 {
+        if self.scale <= 0.0 || self.shape <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let log_const: f64 = log(self.shape / self.scale);
+        let mut total: f64 = 0.0;
+        for &x in data {
+            let y: f64 = self.reduced(x);
+            if y <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            total += log_const - (1.0 + self.shape) * log(y) - pow(y, - self.shape);
+        }
+        total
+    }
+
+    /// Fit the Fréchet distribution to the data by maximum likelihood over the three parameters.
+    /// The optimizer works on $(\log(\min_i x_i - loc), \log scale, \log shape)$, so that all the
+    /// data always lie strictly above $loc$, and is started from the L-moment (PWM) GEV fit mapped
+    /// to the Fréchet parameters ($shape = 1 / \zeta$, $scale = \sigma / \zeta$, $loc = \mu - scale$).
+    /// At least 3 finite observations are required.
+    pub fn fit_mle(data: &[f64]) -> Result<Frechet, FitError> {
+        check_data(data, 3)?;
+        let min: f64 = data.iter().cloned().fold(f64::INFINITY, f64::min);
+        let init: GEV = GEV::fit_pwm(data)?;
+
+        // an L-moment fit with a non-positive shape is not of Fréchet type, use a default shape instead
+        let shape_init: f64 = if init.shape() > 0.05 { 1.0 / init.shape() } else { 5.0 };
+        let scale_init: f64 = init.scale() * shape_init;
+        let offset_init: f64 = (min - init.loc() + scale_init).max(0.05 * scale_init);
+
+        let neg_lik = |p: &[f64]| - Frechet { loc: min - exp(p[0]), scale: exp(p[1]), shape: exp(p[2]) }.log_likelihood(data);
+        let start: [f64; 3] = [log(offset_init), log(scale_init), log(shape_init)];
+        let (params, _) = nelder_mead(neg_lik, &start, &[0.2, 0.1, 0.1], 5000, 1e-12).ok_or(FitError::NoConvergence)?;
+
+        let fitted: Frechet = Frechet { loc: min - exp(params[0]), scale: exp(params[1]), shape: exp(params[2]) };
+        if fitted.loc.is_finite() && fitted.scale.is_finite() && fitted.shape.is_finite() {
+            Ok(fitted)
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+}
+
+/// Fitting methods available for the Fréchet Distribution: maximum likelihood.
+impl Fittable for Frechet {
+    fn fit(data: &[f64], method: FitMethod) -> Result<Self, FitError> {
+        match method {
+            FitMethod::Mle => Frechet::fit_mle(data),
+            FitMethod::Pwm | FitMethod::MomentMatching => Err(FitError::UnsupportedMethod),
+        }
+    }
+}
+
+impl fmt::Display for Frechet {
+    /// Format as `Frechet(loc=..., scale=..., shape=...)`, honoring the precision of the formatter
+    /// (e.g. `format!("{:.2}", dist)`), the parameters being printed in full by default.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_params(f, "Frechet", &[("loc", self.loc), ("scale", self.scale), ("shape", self.shape)])
+    }
+}
+
+impl LocationScale for Frechet {
+    #[inline(always)]
+    fn loc(&self) -> f64 {
+        self.loc
+    }
+
+    #[inline(always)]
+    fn scale(&self) -> f64 {
+        self.scale
+    }
 }
 
 impl DistQuant for Frechet {
@@ -52,24 +310,64 @@ impl DistQuant for Frechet {
     /// for $x > loc$
     fn cdf(&self, x: f64) -> f64 {
         domain!(x > self.loc);
-        let y: f64 = (x - self.loc) / self.scale;
+        let y: f64 = self.reduced(x);
         exp(- pow(y, - self.shape))
     }
+
+    /// Logarithm of the CDF, exactly $\log F(x) = - \left( \frac{x - loc}{scale} \right)^{-shape}$.
+    fn log_cdf(&self, x: f64) -> f64 {
+        domain!(x > self.loc);
+        - pow(self.reduced(x), - self.shape)
+    }
+
+    /// Logarithm of the survival function, $\log(1 - e^{-t})$ with
+    /// $\log t = - shape \log \frac{x - loc}{scale}$, without underflow in the upper tail.
+    fn log_sf(&self, x: f64) -> f64 {
+        domain!(x > self.loc);
+        log1mexp(- self.shape * log(self.reduced(x)))
+    }
     
     /// PDF of the Frechet distribution.
     /// $$f (x) = \frac{shape}{scale} \left(\frac{ x - loc }{scale}\right)^{-1 - shape} \exp \left \{ - \left( \frac{x - loc}{scale} \right)^{- shape}  \right \} $$
     fn pdf(&self, x: f64) -> f64 {
         domain!(x > self.loc);
-        let y: f64 = (x - self.loc) / self.scale;
+        let y: f64 = self.reduced(x);
         let pow_const: f64 = self.shape / self.scale;
         pow_const * pow(y, -1.0 - self.shape) * exp(- pow(y, - self.shape))
     }
 
     /// Quantile (inverse CDF) function.
     /// $F^{-1}(x) = loc + scale \left(- \log x \right )^{- \frac{1}{shape}}$
+    /// Near $x = 1$, $\log x$ keeps all its digits since $x - 1$ is exact, but near $x = 0$ the
+    /// offset from $loc$ can be smaller than the spacing of the floats around $loc$ (for a small
+    /// shape), and the sum would round onto the end point $loc$, where the CDF is not defined.
+    /// The quantile of a positive probability is kept inside the support, at worst on the first
+    /// float above $loc$ (see `stay_inside`).
     fn quantile(&self, x: f64) -> f64 {
-        domain!(x >= 0.0 && x <= 1.0);
-        self.loc + self.scale * pow(-log(x), - 1.0 / self.shape)
+        domain!((0.0..=1.0).contains(&x));
+        self.stay_inside(self.loc + self.scale * pow(-log(x), - 1.0 / self.shape), x > 0.0)
+    }
+
+    /// Inverse survival function $S^{-1}(p) = loc + scale \left(- \mathrm{log1p}(- p) \right)^{- \frac{1}{shape}}$,
+    /// kept inside the support like `quantile`.
+    fn isf(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        self.stay_inside(self.loc + self.scale * pow(- log1p(- p), - 1.0 / self.shape), p < 1.0)
+    }
+
+    /// Density quantile function $\frac{dQ}{dp} = \frac{scale}{shape \cdot p} (- \log p)^{- \frac{1}{shape} - 1}$,
+    /// infinite at $p = 0$ and $p = 1$ (the end point $loc$) where the density vanishes.
+    fn quantile_density(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        if p == 0.0 || p == 1.0 {
+            return f64::INFINITY;
+        }
+        self.scale / (self.shape * p) * pow(- log(p), - 1.0 / self.shape - 1.0)
+    }
+
+    /// Support: $(loc, \infty)$
+    fn support(&self) -> (f64, f64) {
+        (self.loc, f64::INFINITY)
     }
 
     /// Return a randomly generated value from the Frechet distribution.
@@ -118,4 +416,138 @@ mod tests {
         let quant_frechet: f64 = frech.quantile(0.7);
         assert_eq!(ans, quant_frechet);
     }
+
+    #[test]
+    fn frechet_quantile_round_trip_test() {
+        let probs: Vec<f64> = (1..=10).flat_map(|k| [pow(10.0, - k as f64), 1.0 - pow(10.0, - k as f64)]).chain([0.5]).collect();
+        for &(loc, shape) in [(0.0, 0.3), (0.0, 3.0), (1.0, 1.0), (1.0, 3.0), (-5.0, 20.0)].iter() {
+            let frech: Frechet = new_frechet!(loc, 2.0, shape);
+            for &p in probs.iter() {
+                assert!((frech.cdf(frech.quantile(p)) - p).abs() < 1e-9);
+                assert!((frech.sf(frech.isf(p)) - p).abs() < 1e-9);
+            }
+        }
+        // the quantile of a small probability rounds onto loc = 1 for a small shape: it is kept
+        // inside the support, although the closest float cannot reproduce the probability
+        let frech: Frechet = new_frechet!(1.0, 2.0, 0.05);
+        assert_eq!(frech.quantile(1e-10), nextafter(1.0, 2.0));
+        assert!(frech.cdf(frech.quantile(1e-10)) > 0.0);
+        assert_eq!(frech.quantile(0.0), 1.0);
+    }
+
+    #[test]
+    fn frechet_param_bounds_test() {
+        let bounds: Vec<(f64, f64)> = Frechet::param_bounds();
+        assert_eq!(bounds.len(), 3);
+        // the lower bounds are valid parameters, and the values just outside are rejected
+        assert!(Frechet::from_params(&[0.5, f64::EPSILON, f64::EPSILON]).is_ok());
+        assert!(Frechet::from_params(&[0.5, 2.0, 0.0]).is_err());
+        assert!(bounds.iter().all(|(lo, hi)| lo < hi));
+    }
+
+    #[test]
+    fn frechet_params_test() {
+        let frech: Frechet = new_frechet!(1.0, 0.1, 1.0);
+        assert_eq!(frech.as_params(), vec![1.0, 0.1, 1.0]);
+        assert_eq!(Frechet::from_params(&frech.as_params()), Ok(frech));
+        assert_eq!(Frechet::from_params(&[1.0, 0.1]), Err(ParamError::WrongLength { expected: 3, found: 2 }));
+        assert_eq!(Frechet::from_params(&[1.0, 0.1, -1.0]), Err(ParamError::NonPositiveShape));
+    }
+
+    #[test]
+    fn frechet_le_bytes_test() {
+        let frech: Frechet = new_frechet!(1.0, 0.1, 1.0);
+        assert_eq!(Frechet::from_le_bytes(&frech.to_le_bytes()), Ok(frech));
+        assert_eq!(Frechet::from_le_bytes(&frech.to_le_bytes()[..3]), Err(DecodeError::WrongLength { expected: 25, found: 3 }));
+    }
+
+    #[test]
+    fn frechet_from_quantiles_test() {
+        let orig: Frechet = new_frechet!(1.0, 0.5, 4.0);
+        let points: [(f64, f64); 3] = [(0.1, orig.quantile(0.1)), (0.5, orig.quantile(0.5)), (0.99, orig.quantile(0.99))];
+        let frech: Frechet = Frechet::from_quantiles(&points).unwrap();
+        for (p, q) in points {
+            assert!((frech.quantile(p) - q).abs() < 1e-8);
+        }
+        assert!((frech.shape() - 4.0).abs() < 1e-6);
+        // symmetric-looking quantiles imply a non-positive shape
+        assert_eq!(Frechet::from_quantiles(&[(0.1, 1.0), (0.5, 2.0), (0.9, 3.0)]).err(), Some(FitError::InvalidInput));
+    }
+
+    #[test]
+    fn frechet_pdf_max_test() {
+        let frech: Frechet = new_frechet!(1.0, 0.5, 2.0);
+        let grid_max: f64 = (1..20001).map(|i| frech.pdf(1.0 + 0.0002 * i as f64)).fold(0.0, f64::max);
+        assert!((frech.pdf_max() - grid_max).abs() < 1e-6);
+        assert!(frech.pdf_max() >= grid_max);
+    }
+
+    #[test]
+    fn frechet_fit_mle_test() {
+        let frech: Frechet = new_frechet!(1.0, 2.0, 4.0);
+        let data: Vec<f64> = frech.sample_n(3000, RandomSeed::Seed(2));
+        let fitted: Frechet = Frechet::fit(&data, FitMethod::Mle).unwrap();
+        assert!(data.iter().all(|&x| x > fitted.loc()));
+        assert!((fitted.loc() - 1.0).abs() < 0.3);
+        assert!((fitted.scale() - 2.0).abs() < 0.3);
+        assert!((fitted.shape() - 4.0).abs() < 0.5);
+        assert_eq!(Frechet::fit(&data, FitMethod::Pwm), Err(FitError::UnsupportedMethod));
+    }
+
+    #[test]
+    fn frechet_to_weibull_test() {
+        let frech: Frechet = new_frechet!(1.0, 2.0, 3.0);
+        let weib: Weibull = frech.to_weibull();
+        for y in [1.5, 2.0, 3.0, 10.0] {
+            let x: f64 = - 1.0 / (y - 1.0);
+            assert!((frech.cdf(y) - weib.cdf(x)).abs() < 1e-14);
+        }
+        assert_eq!(weib.to_frechet(), new_frechet!(0.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn frechet_summary_test() {
+        let frech: Frechet = new_frechet!(1.0, 2.0, 5.0);
+        let summary: DistSummary = frech.summary();
+        assert_eq!((summary.mean, summary.variance, summary.skewness), (frech.mean(), frech.variance(), frech.skewness()));
+        assert_eq!((summary.median, summary.mode, summary.entropy), (frech.median(), frech.mode(), frech.entropy()));
+        // the same as the equivalent GEV distribution
+        let gev: GEV = GEV::new(1.0 + 2.0, 2.0 / 5.0, 1.0 / 5.0);
+        assert!((summary.mean.unwrap() - gev.mean().unwrap()).abs() < 1e-12);
+        assert!((summary.variance.unwrap() - gev.variance().unwrap()).abs() < 1e-12);
+        assert!((summary.entropy - gev.entropy()).abs() < 1e-12);
+        assert!((summary.median - gev.median()).abs() < 1e-12);
+        assert_eq!(new_frechet!(1.0, 2.0, 1.5).summary().variance, None);
+    }
+
+    #[test]
+    fn frechet_moment_exists_test() {
+        let frech: Frechet = new_frechet!(1.0, 0.1, 2.0);
+        assert!(frech.moment_exists(0));
+        assert!(frech.moment_exists(1));
+        // boundary: the moment of order shape is infinite
+        assert!(!frech.moment_exists(2));
+        assert!(!frech.moment_exists(3));
+        assert!(new_frechet!(1.0, 0.1, 0.5).moment_exists(0));
+        assert!(!new_frechet!(1.0, 0.1, 0.5).moment_exists(1));
+    }
+
+    #[test]
+    fn frechet_skewness_test() {
+        let frech: Frechet = new_frechet!(1.0, 0.1, 5.0);
+        let ans: f64 = 3.535071604621361;
+        assert!((frech.skewness().unwrap() - ans).abs() < 1e-10);
+        assert!(new_frechet!(1.0, 0.1, 3.0).skewness().is_none());
+    }
+
+    #[test]
+    fn frechet_kurtosis_test() {
+        let frech: Frechet = new_frechet!(1.0, 0.1, 5.0);
+        let ans: f64 = 45.09151212581566;
+        assert!((frech.kurtosis().unwrap() - ans).abs() < 1e-8);
+        // skewness exists but not the kurtosis
+        let frech: Frechet = new_frechet!(1.0, 0.1, 3.5);
+        assert!(frech.skewness().is_some());
+        assert!(frech.kurtosis().is_none());
+    }
 }
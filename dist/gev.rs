@@ -1,15 +1,24 @@
 //! The Generalized Extreme Value (GEV) Distribution, which generalizes the Gumbel, Frechet and
 //! Weibull distributions.
-use libm::{exp, log, pow};
+use std::fmt;
+
+use libm::{erfc, exp, expm1, log, log10, log1p, pow, sqrt, tgamma};
 
 use crate::dist::distutils::*;
+use crate::dist::viz::scale_to_peak;
+use crate::dist::constants::{EULER_GAMMA, PI_SQUARED_OVER_6, ZETA_3};
+use crate::dist::bootstrap::{bootstrap, BiasCorrection};
+use crate::dist::gumbel::Gumbel;
+use crate::dist::fit::{check_data, check_distinct, condition_number, mean_sd, nelder_mead, numerical_hessian, FitError, FitMethod, FitReport, Fittable};
+use crate::dist::lmoments::{sample_lmoments, LMoments};
+use crate::dist::params::{check_params, decode, encode, DecodeError, Scale, Shape, ParamError};
 
 use rand_chacha::ChaCha8Rng;
 use rand::SeedableRng;
 use rand::Rng;
 
 /// Fréchet Dist. struct
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct GEV {
     /// location parameter
     pub loc:   f64,
@@ -28,16 +37,109 @@ impl GEV {
         GEV{loc, scale, shape}
     }
 
-    /// Obtain the location parameter
-    #[inline(always)]
-    pub fn loc(&self) -> f64 {
-        self.loc
+    /// Create the GEV distribution from parameters given in another tool's sign convention for the
+    /// shape (see `Convention`), e.g. straight from a `scipy.stats.genextreme` fit.
+    /// The shape is stored in the native convention of the crate.
+    pub fn new_with_convention(loc: f64, scale: f64, shape: f64, convention: Convention) -> Self {
+        GEV::new(loc, scale, convention.to_native(shape))
     }
 
-    /// Obtain the scale parameter
-    #[inline(always)]
-    pub fn scale(&self) -> f64 {
-        self.scale
+    /// Create the GEV distribution from the estimates reported by R's `extRemes::fevd` fitted with
+    /// `use.phi = TRUE`, which optimizes the log of the scale parameter and reports it as `log.scale`.
+    /// The scale is recovered as $\exp(log\_scale)$ and the shape has the native sign convention.
+    /// The parameters must be finite and the recovered scale positive (`exp` can underflow to 0).
+    pub fn from_r_mle(loc: f64, log_scale: f64, shape: f64) -> Result<Self, ParamError> {
+        GEV::from_params(&[loc, exp(log_scale), shape])
+    }
+
+    /// Shape parameter expressed in the given convention.
+    pub fn shape_in(&self, convention: Convention) -> f64 {
+        convention.to_native(self.shape)
+    }
+
+    /// Parameters packed into a vector, in the order (loc, scale, shape).
+    pub fn as_params(&self) -> Vec<f64> {
+        vec![self.loc, self.scale, self.shape]
+    }
+
+    /// Bounds `(lo, hi)` of each parameter, in the order (loc, scale, shape) of `as_params`, for bounded
+    /// optimizers. The strictly positive scale is bounded below by `f64::EPSILON`.
+    pub fn param_bounds() -> Vec<(f64, f64)> {
+        vec![(f64::NEG_INFINITY, f64::INFINITY), (f64::EPSILON, f64::INFINITY), (f64::NEG_INFINITY, f64::INFINITY)]
+    }
+
+    /// Create the distribution from parameters packed in the order (loc, scale, shape), e.g. by an optimizer.
+    /// Exactly 3 finite parameters are required and the scale must be positive.
+    pub fn from_params(params: &[f64]) -> Result<Self, ParamError> {
+        check_params(params, 3)?;
+        if params[1] <= 0.0 {
+            return Err(ParamError::NonPositiveScale);
+        }
+        Ok(GEV{ loc: params[0], scale: params[1], shape: params[2] })
+    }
+
+    /// Parameters mapped to the unconstrained space $(loc, \log scale, shape)$, in which every point of
+    /// $\mathbb{R}^3$ is a valid distribution: unbounded optimizers can search it directly.
+    pub fn to_unconstrained(&self) -> [f64; 3] {
+        [self.loc, log(self.scale), self.shape]
+    }
+
+    /// Inverse of `to_unconstrained`, from parameters packed in the order (loc, log_scale, shape).
+    /// The scale $\exp(log\_scale)$ is clamped to `[f64::MIN_POSITIVE, f64::MAX]`, so that it stays
+    /// positive and finite however far an optimizer strays. Exactly 3 parameters are required.
+    pub fn from_unconstrained(params: &[f64]) -> GEV {
+        domain!(params.len() == 3);
+        let scale: f64 = exp(params[1]).clamp(f64::MIN_POSITIVE, f64::MAX);
+        GEV{ loc: params[0], scale, shape: params[2] }
+    }
+
+    /// Distribution of $a X + b$ for $X$ following this distribution, $a > 0$: the location becomes
+    /// $a \cdot loc + b$ and the scale $a \cdot scale$. The resulting scale must be finite and
+    /// positive (checked by `domain!`, see `try_affine` for the checked version).
+    pub fn affine(&self, a: f64, b: f64) -> Self {
+        let dist: GEV = GEV { loc: a * self.loc + b, scale: a * self.scale, shape: self.shape };
+        domain!(dist.loc.is_finite() && dist.scale.is_finite() && dist.scale > 0.0);
+        dist
+    }
+
+    /// Checked version of `affine`, see `ParamError`.
+    pub fn try_affine(&self, a: f64, b: f64) -> Result<Self, ParamError> {
+        GEV::from_params(&[a * self.loc + b, a * self.scale, self.shape])
+    }
+
+    /// The same distribution with the given scale parameter, which must be finite and positive
+    /// (checked by `domain!`, see `try_with_scale` for the checked version).
+    pub fn with_scale(&self, scale: f64) -> Self {
+        domain!(scale.is_finite() && scale > 0.0);
+        GEV { scale, ..*self }
+    }
+
+    /// Checked version of `with_scale`, see `ParamError`.
+    pub fn try_with_scale(&self, scale: f64) -> Result<Self, ParamError> {
+        GEV::from_params(&[self.loc, scale, self.shape])
+    }
+
+    /// Create an instance of the GEV Distribution from already validated scale and shape
+    /// parameters, so no domain check is needed.
+    #[inline]
+    pub fn from_validated(loc: f64, scale: Scale, shape: Shape) -> Self {
+        GEV{loc, scale: scale.get(), shape: shape.get()}
+    }
+
+    /// Tag byte identifying the GEV distribution in its binary form.
+    pub const TAG: u8 = 3;
+
+    /// Compact binary form: the tag byte `GEV::TAG` followed by the 3 parameters (see `as_params`)
+    /// as little-endian `f64`s.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        encode(GEV::TAG, &self.as_params())
+    }
+
+    /// Decode the distribution from its binary form (see `to_le_bytes`), validating the length,
+    /// the tag and the parameters.
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let params: Vec<f64> = decode(bytes, GEV::TAG, 3)?;
+        GEV::from_params(&params).map_err(DecodeError::InvalidParams)
     }
 
     /// Obtain the shape parameter
@@ -51,7 +153,7 @@ impl GEV {
     /// or $t(x) = \exp \left \{ - \frac{x - loc}{ scale}  \right \}$ if $\zeta = 0$t
     #[inline(always)]
     fn t_func(&self, x: f64) -> f64 {
-        let y: f64 = (x - self.loc) / self.scale;
+        let y: f64 = self.reduced(x);
         if self.shape == 0.0 {
             exp(- y)
         } else {
@@ -59,6 +161,880 @@ impl GEV {
         }
     }
 
+    /// Create the GEV distribution going through the three given (probability, quantile) pairs.
+    /// Writing $q_i = loc + scale \cdot h(p_i)$ with $h(p) = \frac{(- \log p)^{-shape} - 1}{shape}$, the ratio
+    /// $\frac{q_3 - q_2}{q_2 - q_1}$ only depends on the shape, which is found by bisection on $[-10, 10]$;
+    /// the scale and location then follow exactly.
+    /// Returns `FitError::InvalidInput` if a probability is not in $(0, 1)$, the probabilities are not
+    /// distinct, the quantiles are not increasing with the probabilities, or no shape in $[-10, 10]$ matches.
+    pub fn from_quantiles(quantiles: &[(f64, f64); 3]) -> Result<GEV, FitError> {
+        let mut points: [(f64, f64); 3] = *quantiles;
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let [(p1, q1), (p2, q2), (p3, q3)] = points;
+        let valid_p = |p: f64| p > 0.0 && p < 1.0;
+        if !(valid_p(p1) && valid_p(p3)) || p1 == p2 || p2 == p3 || !(q1 < q2 && q2 < q3) || !q3.is_finite() {
+            return Err(FitError::InvalidInput);
+        }
+        // h(p) written with expm1 so that it is accurate for a shape close to 0
+        let h = |shape: f64, p: f64| -> f64 {
+            let log_y: f64 = log(- log(p));
+            if shape.abs() < 1e-12 { - log_y } else { expm1(- shape * log_y) / shape }
+        };
+        let target: f64 = (q3 - q2) / (q2 - q1);
+        let ratio_gap = |shape: f64| (h(shape, p3) - h(shape, p2)) / (h(shape, p2) - h(shape, p1)) - target;
+
+        let (mut lo, mut hi) = (-10.0, 10.0);
+        if ratio_gap(lo) > 0.0 || ratio_gap(hi) < 0.0 {
+            return Err(FitError::InvalidInput);
+        }
+        for _ in 0..200 {
+            let mid: f64 = 0.5 * (lo + hi);
+            if ratio_gap(mid) < 0.0 { lo = mid } else { hi = mid }
+        }
+        let shape: f64 = 0.5 * (lo + hi);
+        let scale: f64 = (q2 - q1) / (h(shape, p2) - h(shape, p1));
+        Ok(GEV { loc: q1 - scale * h(shape, p1), scale, shape })
+    }
+
+    /// Create the GEV distribution from design `(return period, return level)` pairs, e.g. the
+    /// 10, 100 and 1000-year levels. The return periods must be larger than 1 and the levels finite.
+    /// With exactly three points the GEV goes through them (see `GEV::from_quantiles`, with the
+    /// probabilities $1 - 1 / T$). With more points the system is overdetermined and the parameters
+    /// minimize the sum of the squared differences between the return levels and the given levels,
+    /// starting from the exact fit of the smallest, median and largest return periods.
+    /// Fewer than three points cannot determine the three parameters: `FitError::InsufficientData`
+    /// is returned. Invalid periods or levels (e.g. levels decreasing with the return period) give
+    /// `FitError::InvalidInput`.
+    pub fn from_return_levels(points: &[(f64, f64)]) -> Result<GEV, FitError> {
+        if points.len() < 3 {
+            return Err(FitError::InsufficientData);
+        }
+        if points.iter().any(|&(period, level)| period.is_nan() || period <= 1.0 || !level.is_finite()) {
+            return Err(FitError::InvalidInput);
+        }
+        let mut sorted: Vec<(f64, f64)> = points.to_vec();
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let as_quantile = |(period, level): (f64, f64)| (1.0 - 1.0 / period, level);
+        let exact: GEV = GEV::from_quantiles(&[
+            as_quantile(sorted[0]),
+            as_quantile(sorted[sorted.len() / 2]),
+            as_quantile(sorted[sorted.len() - 1]),
+        ])?;
+        if points.len() == 3 {
+            return Ok(exact);
+        }
+        let sum_squares = |gev: &GEV| -> f64 {
+            sorted.iter().map(|&(period, level)| (gev.return_level(period) - level).powi(2)).sum()
+        };
+        GEV::maximize(exact, |gev| - sum_squares(gev))
+    }
+
+    /// Inverse transform sampling of the uniform draw `u`, kept strictly inside the support.
+    /// For extreme shapes, `quantile` can round to an end point of the support (e.g. the upper
+    /// end point when $(- \log u)^{- shape}$ underflows for a very negative shape), where `pdf`
+    /// is not defined. Such values are moved inside the support by steps of
+    /// `SAMPLE_SUPPORT_EPS` $\cdot scale$ (at most 1000 of them). A draw $u = 0$ is replaced by the smallest positive `f64`
+    /// so that the sample is always finite.
+    fn sample_from_uniform(&self, u: f64) -> f64 {
+        let mut x: f64 = self.quantile(u.max(f64::MIN_POSITIVE));
+        let step: f64 = if self.shape < 0.0 { - SAMPLE_SUPPORT_EPS * self.scale } else { SAMPLE_SUPPORT_EPS * self.scale };
+        // bounded loop, so that invalid (e.g. NaN) parameters cannot hang the sampling
+        for _ in 0..1000 {
+            if self.is_in_support(x) {
+                break;
+            }
+            x += step;
+        }
+        x
+    }
+
+    /// Generate a random value from the GEV distribution from a standard exponential draw, without
+    /// the support guarantee of `random`: for extreme shapes a draw can round to an end point of the support.
+    /// If $X \sim GEV(loc, scale, shape)$ then $t(X) = (1 + shape \frac{X - loc}{scale})^{-1/shape}$
+    /// satisfies $P(t(X) > e) = P(X < t^{-1}(e)) = \exp(- e)$, i.e. $t(X) \sim Exp(1)$. Hence with
+    /// $E \sim Exp(1)$,
+    /// $$ X = loc + scale \frac{E^{- shape} - 1}{shape} \quad (loc - scale \log E \text{ if } shape = 0) $$
+    /// is GEV distributed, computed as $scale \cdot \mathrm{expm1}(- shape \log E) / shape$.
+    /// With $E = - \log U$ this is the inverse transform of `random` without the domain check,
+    /// the branch between `pow` and `expm1` and the nudging inside the support, so both
+    /// produce the same distribution (and the same draws up to rounding).
+    ///
+    /// On performance: the exponential draw still costs a `log` per sample, so the transform is
+    /// only modestly cheaper than the inverse transform, and the gain is negligible per call once
+    /// the generator is seeded for every draw. A larger gain would need a ziggurat exponential
+    /// sampler, which is not available with the current dependencies.
+    pub fn random_fast(&self, seed: RandomSeed) -> f64 {
+        let mut rng: ChaCha8Rng = seed.rng();
+        let e: f64 = - log(rng.gen::<f64>().max(f64::MIN_POSITIVE)); // standard exponential
+        if self.shape == 0.0 {
+            self.loc - self.scale * log(e)
+        } else {
+            self.loc + self.scale * expm1(- self.shape * log(e)) / self.shape
+        }
+    }
+
+    /// Mode of the GEV distribution, $loc + scale \frac{(1 + shape)^{-shape} - 1}{shape}$ ($loc$ if $shape = 0$).
+    /// For $shape \leq -1$ the mode is the upper end point $loc - scale / shape$ of the support.
+    pub fn mode(&self) -> f64 {
+        if self.shape == 0.0 {
+            self.loc
+        } else if self.shape <= -1.0 {
+            self.loc - self.scale / self.shape
+        } else {
+            self.loc + self.scale * (pow(1.0 + self.shape, - self.shape) - 1.0) / self.shape
+        }
+    }
+
+    /// Maximum value of the PDF, attained at the mode.
+    /// For $shape = -1$ it is the limit $\frac{1}{scale}$ at the upper end point, and for $shape < -1$
+    /// the density is unbounded at the upper end point, so `f64::INFINITY` is returned.
+    pub fn pdf_max(&self) -> f64 {
+        if self.shape > -1.0 {
+            self.pdf(self.mode())
+        } else if self.shape == -1.0 {
+            1.0 / self.scale
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// PDF over `xs` scaled so that its peak `pdf_max` has the height `peak_height`, e.g. to overlay
+    /// the fitted density on a histogram with an arbitrary vertical scale. The maximum of the
+    /// returned values is `peak_height` when `xs` contains the mode.
+    /// For $shape < -1$, where the density is unbounded, the largest value over `xs` is scaled instead.
+    pub fn pdf_scaled(&self, xs: &[f64], peak_height: f64) -> Vec<f64> {
+        scale_to_peak(self.pdf_over(xs), self.pdf_max(), peak_height)
+    }
+
+    /// Standardized residuals $r_i = - \log F(x_i) = t(x_i)$ of the data, which follow the unit
+    /// exponential distribution when the data follows this GEV (as $F(X)$ is uniform), whatever the
+    /// shape. Used for QQ plots against the exponential quantiles $- \log(1 - p)$ to check a fit.
+    /// The observations must lie in the support.
+    pub fn residuals(&self, data: &[f64]) -> Vec<f64> {
+        data.iter()
+            .map(|&x| {
+                domain!(1.0 + self.shape * self.reduced(x) > 0.0);
+                self.t_func(x)
+            })
+            .collect()
+    }
+
+    /// Map the observation `x` to the standard scale, where it follows the standard Gumbel
+    /// distribution (the standard GEV with loc = 0, scale = 1 and shape = 0) if $x$ follows this GEV:
+    /// $$ z = \frac{1}{shape} \log \left( 1 + shape \frac{x - loc}{scale} \right), $$
+    /// the reduced variate $\frac{x - loc}{scale}$ generalized by the shape, which it is when $shape = 0$.
+    /// Equivalently $z = - \log t(x)$, so $F(x) = \exp \{ - e^{-z} \}$. The observation must lie in the support.
+    pub fn to_standard(&self, x: f64) -> f64 {
+        let y: f64 = self.reduced(x);
+        domain!(1.0 + self.shape * y > 0.0);
+        if self.shape == 0.0 {
+            y
+        } else {
+            log1p(self.shape * y) / self.shape
+        }
+    }
+
+    /// Inverse of `to_standard`: $x = loc + scale \frac{e^{shape \cdot z} - 1}{shape}$, or
+    /// $x = loc + scale \cdot z$ if $shape = 0$.
+    pub fn from_standard(&self, z: f64) -> f64 {
+        if self.shape == 0.0 {
+            self.loc + self.scale * z
+        } else {
+            self.loc + self.scale * expm1(self.shape * z) / self.shape
+        }
+    }
+
+    /// Whether the moment of the given order $k$ is finite, i.e. $shape < 1/k$. It is always the
+    /// case for $shape \leq 0$, while for $shape > 0$ the tail is as heavy as a Fréchet tail with
+    /// index $1 / shape$: e.g. the mean requires $shape < 1$ and the variance $shape < 1/2$.
+    pub fn moment_exists(&self, order: u32) -> bool {
+        self.shape < 1.0 / order as f64
+    }
+
+    /// $g_k = \Gamma(1 - k \cdot shape)$, the building block of the GEV moments.
+    #[inline(always)]
+    fn g_k(&self, k: f64) -> f64 {
+        tgamma(1.0 - k * self.shape)
+    }
+
+    /// Mean of the GEV distribution, $loc + scale \frac{g_1 - 1}{shape}$ ($loc + \gamma \cdot scale$ if
+    /// $shape = 0$). Only exists for $shape < 1$, otherwise `None` is returned.
+    pub fn mean(&self) -> Option<f64> {
+        if !self.moment_exists(1) {
+            None
+        } else if self.shape == 0.0 {
+            Some(self.loc + EULER_GAMMA * self.scale)
+        } else {
+            Some(self.loc + self.scale * (self.g_k(1.0) - 1.0) / self.shape)
+        }
+    }
+
+    /// Variance of the GEV distribution, $scale^2 \frac{g_2 - g_1^2}{shape^2}$ ($\frac{\pi^2}{6} scale^2$
+    /// if $shape = 0$). Only exists for $shape < 1/2$, otherwise `None` is returned.
+    pub fn variance(&self) -> Option<f64> {
+        if !self.moment_exists(2) {
+            None
+        } else if self.shape == 0.0 {
+            Some(PI_SQUARED_OVER_6 * self.scale * self.scale)
+        } else {
+            let g1: f64 = self.g_k(1.0);
+            Some(self.scale * self.scale * (self.g_k(2.0) - g1 * g1) / (self.shape * self.shape))
+        }
+    }
+
+    /// Skewness of the GEV distribution,
+    /// $$ \mathrm{sign}(shape) \frac{g_3 - 3 g_1 g_2 + 2 g_1^3}{(g_2 - g_1^2)^{3/2}}, $$
+    /// the Gumbel skewness $\frac{12 \sqrt{6} \zeta(3)}{\pi^3}$ if $shape = 0$.
+    /// Only exists for $shape < 1/3$, otherwise `None` is returned.
+    pub fn skewness(&self) -> Option<f64> {
+        if !self.moment_exists(3) {
+            None
+        } else if self.shape == 0.0 {
+            Some(12.0 * sqrt(6.0) * ZETA_3 / pow(std::f64::consts::PI, 3.0))
+        } else {
+            let (g1, g2, g3) = (self.g_k(1.0), self.g_k(2.0), self.g_k(3.0));
+            Some(self.shape.signum() * (g3 - 3.0 * g1 * g2 + 2.0 * pow(g1, 3.0)) / pow(g2 - g1 * g1, 1.5))
+        }
+    }
+
+    /// Median of the GEV distribution, $loc + scale \frac{(\log 2)^{-shape} - 1}{shape}$.
+    pub fn median(&self) -> f64 {
+        self.quantile(0.5)
+    }
+
+    /// Differential entropy of the GEV distribution, $\log scale + \gamma \cdot shape + \gamma + 1$.
+    pub fn entropy(&self) -> f64 {
+        log(self.scale) + EULER_GAMMA * self.shape + EULER_GAMMA + 1.0
+    }
+
+    /// Summary of the distribution, the moments that do not exist for the shape (see
+    /// `moment_exists`) being `None`.
+    pub fn summary(&self) -> DistSummary {
+        DistSummary {
+            mean: self.mean(),
+            variance: self.variance(),
+            median: self.median(),
+            mode: self.mode(),
+            skewness: self.skewness(),
+            support: self.support(),
+            entropy: self.entropy(),
+        }
+    }
+
+    /// Log-likelihood of the data under the GEV distribution,
+    /// $$ \ell = \sum_i \left( - \log scale - (1 + \frac{1}{shape}) \log z_i - z_i^{-\frac{1}{shape}} \right), \quad z_i = 1 + shape \frac{x_i - loc}{scale} $$
+    /// (with the Gumbel form when $shape = 0$).
+    /// Returns $-\infty$ if an observation lies outside of the support or if the scale is not positive,
+    /// so it can safely be used as an objective function.
+    pub fn log_likelihood(&self, data: &[f64]) -> f64 {
+        let mut total: f64 = 0.0;
+        for &x in data {
+            let log_dens: f64 = self.log_density(x);
+            if log_dens == f64::NEG_INFINITY {
+                return f64::NEG_INFINITY;
+            }
+            total += log_dens;
+        }
+        total
+    }
+
+    /// Negative log-likelihood $- \ell$ of the data, $+\infty$ outside of the support, to be minimized.
+    /// Composed with `from_unconstrained`, `|p| GEV::from_unconstrained(p).negative_log_likelihood(data)`
+    /// is an objective defined on all of $\mathbb{R}^3$.
+    pub fn negative_log_likelihood(&self, data: &[f64]) -> f64 {
+        - self.log_likelihood(data)
+    }
+
+    /// Log-density at `x`, $-\infty$ outside of the support or if the scale is not positive.
+    fn log_density(&self, x: f64) -> f64 {
+        if self.scale <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let y: f64 = self.reduced(x);
+        if self.shape == 0.0 {
+            - log(self.scale) - y - exp(- y)
+        } else {
+            let z: f64 = 1.0 + self.shape * y;
+            if z <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            - log(self.scale) - (1.0 + 1.0 / self.shape) * log(z) - pow(z, - 1.0 / self.shape)
+        }
+    }
+
+    /// Maximize `objective` over the GEV parameters with Nelder-Mead on $(loc, \log scale, shape)$,
+    /// starting from `init`. Used by the likelihood based fitters.
+    fn maximize<F: Fn(&GEV) -> f64>(init: GEV, objective: F) -> Result<GEV, FitError> {
+        let neg_obj = |p: &[f64]| - objective(&GEV { loc: p[0], scale: exp(p[1]), shape: p[2] });
+        let start: [f64; 3] = [init.loc, log(init.scale), init.shape];
+        let step: [f64; 3] = [0.1 * init.scale, 0.1, 0.1];
+        let (params, _) = nelder_mead(neg_obj, &start, &step, 5000, 1e-12).ok_or(FitError::NoConvergence)?;
+        let fitted: GEV = GEV { loc: params[0], scale: exp(params[1]), shape: params[2] };
+        if fitted.loc.is_finite() && fitted.scale.is_finite() && fitted.scale > 0.0 && fitted.shape.is_finite() {
+            Ok(fitted)
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+    /// Starting point of the likelihood based fitters: the Gumbel moment estimates with a small
+    /// positive shape.
+    fn moment_start(mean: f64, sd: f64) -> GEV {
+        let scale: f64 = (6.0_f64).sqrt() * sd / std::f64::consts::PI;
+        GEV { loc: mean - EULER_GAMMA * scale, scale, shape: 0.1 }
+    }
+
+    /// Fit the GEV distribution to the data by maximum likelihood.
+    /// The optimizer (Nelder-Mead) works on $(loc, \log scale, shape)$ and is started from the
+    /// Gumbel moment estimates with a small positive shape.
+    /// At least 3 finite observations taking at least 3 distinct values are required.
+    pub fn fit_mle(data: &[f64]) -> Result<GEV, FitError> {
+        check_data(data, 3)?;
+        check_distinct(data, 3)?;
+        let (mean, sd) = mean_sd(data);
+        GEV::fit_mle_from(data, GEV::moment_start(mean, sd))
+    }
+
+    /// Fit the GEV distribution to the data by maximum likelihood like `GEV::fit_mle`, and report
+    /// the log-likelihood, the observed information matrix in $(loc, scale, shape)$ and its
+    /// condition number (see `FitReport`). A large condition number warns that the standard errors
+    /// are unreliable, typically because the shape is close to $-1$ or below: the maximum likelihood
+    /// estimator is no longer regular and the end point of the support nears the sample maximum
+    /// (the condition number is infinite once the finite differences leave the support).
+    pub fn fit_mle_report(data: &[f64]) -> Result<FitReport<GEV>, FitError> {
+        let gev: GEV = GEV::fit_mle(data)?;
+        let neg_lik = |p: &[f64]| if p[1] > 0.0 { - GEV { loc: p[0], scale: p[1], shape: p[2] }.log_likelihood(data) } else { f64::INFINITY };
+        let observed_information: Vec<Vec<f64>> = numerical_hessian(neg_lik, &[gev.loc, gev.scale, gev.shape]);
+        let hessian_condition_number: f64 = condition_number(&observed_information);
+        Ok(FitReport { dist: gev, log_likelihood: gev.log_likelihood(data), observed_information, hessian_condition_number })
+    }
+
+    /// Fit the GEV distribution to the data by maximum likelihood, starting the optimizer from
+    /// `init` rather than from the default moment based start. The likelihood may have several
+    /// local maxima (and is $-\infty$ wherever an observation falls outside of the support), so a
+    /// start close to the optimum, typically the PWM estimate `GEV::fit_pwm`, is more reliable.
+    /// At least 3 finite observations taking at least 3 distinct values are required.
+    pub fn fit_mle_from(data: &[f64], init: GEV) -> Result<GEV, FitError> {
+        check_data(data, 3)?;
+        check_distinct(data, 3)?;
+        GEV::maximize(init, |gev| gev.log_likelihood(data))
+    }
+
+    /// Fit the GEV distribution to the data by weighted maximum likelihood, maximizing
+    /// $\sum_i w_i \log f(x_i)$, e.g. to downweight less reliable records.
+    /// Multiplying all the weights by a constant does not change the estimate, but the weights
+    /// reduce the effective sample size to $\frac{(\sum_i w_i)^2}{\sum_i w_i^2}$ (Kish), which is $n$ only
+    /// for uniform weights. Observations with a zero weight are ignored.
+    /// Returns `FitError::InvalidInput` if the lengths differ or a weight is negative or not finite,
+    /// `FitError::InsufficientData` if fewer than 3 observations have a positive weight and
+    /// `FitError::Degenerate` if they take fewer than 3 distinct values.
+    pub fn fit_mle_weighted(data: &[f64], weights: &[f64]) -> Result<GEV, FitError> {
+        if data.len() != weights.len() || weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(FitError::InvalidInput);
+        }
+        let kept: Vec<(f64, f64)> = data.iter().cloned().zip(weights.iter().cloned()).filter(|(_, w)| *w > 0.0).collect();
+        let kept_data: Vec<f64> = kept.iter().map(|(x, _)| *x).collect();
+        check_data(&kept_data, 3)?;
+        check_distinct(&kept_data, 3)?;
+
+        let total: f64 = kept.iter().map(|(_, w)| w).sum::<f64>();
+        let mean: f64 = kept.iter().map(|(x, w)| w * x).sum::<f64>() / total;
+        let var: f64 = kept.iter().map(|(x, w)| w * (x - mean) * (x - mean)).sum::<f64>() / total;
+        let weighted_lik = |gev: &GEV| -> f64 {
+            let mut lik: f64 = 0.0;
+            for (x, w) in kept.iter() {
+                let log_dens: f64 = gev.log_density(*x);
+                if log_dens == f64::NEG_INFINITY {
+                    return f64::NEG_INFINITY;
+                }
+                lik += w * log_dens;
+            }
+            lik
+        };
+        GEV::maximize(GEV::moment_start(mean, var.sqrt()), weighted_lik)
+    }
+
+    /// Fit the GEV distribution to the data by penalized maximum likelihood, maximizing
+    /// $$ \ell(loc, scale, shape) - penalty \cdot (shape - shape\_prior)^2, $$
+    /// which shrinks the shape toward `shape_prior` (e.g. a regional estimate) to stabilize fits
+    /// on short records. This is the maximum a posteriori (MAP) estimator under flat priors on the
+    /// location and scale and a normal prior on the shape with mean `shape_prior` and variance
+    /// $\frac{1}{2 \, penalty}$. With `penalty = 0` it is the plain `GEV::fit_mle`.
+    /// Returns `FitError::InvalidInput` if `shape_prior` is not finite or `penalty` is negative or
+    /// not finite; the data requirements are those of `GEV::fit_mle`.
+    pub fn fit_penalized(data: &[f64], shape_prior: f64, penalty: f64) -> Result<GEV, FitError> {
+        if !shape_prior.is_finite() || !penalty.is_finite() || penalty < 0.0 {
+            return Err(FitError::InvalidInput);
+        }
+        check_data(data, 3)?;
+        check_distinct(data, 3)?;
+        let (mean, sd) = mean_sd(data);
+        GEV::maximize(GEV::moment_start(mean, sd), |gev| {
+            gev.log_likelihood(data) - penalty * (gev.shape - shape_prior) * (gev.shape - shape_prior)
+        })
+    }
+
+    /// Fit the GEV distribution to the data by maximum product of spacings (MPS): with the sorted
+    /// data $x_{(1)} \leq \dots \leq x_{(n)}$, maximize
+    /// $$ \sum_{i=1}^{n+1} \log \left( F(x_{(i)}) - F(x_{(i-1)}) \right), \quad F(x_{(0)}) = 0, \; F(x_{(n+1)}) = 1, $$
+    /// where the log-density $\log f(x_{(i)})$ replaces the spacing of tied observations.
+    /// Unlike the likelihood, the product of spacings stays bounded when the end point of the
+    /// support approaches the sample extreme, so MPS remains consistent for shapes below $-1$
+    /// where the maximum likelihood estimator does not exist.
+    /// The optimizer is the same as for `GEV::fit_mle`, started from the PWM fit when it is valid.
+    /// At least 3 finite observations taking at least 3 distinct values are required.
+    pub fn fit_mps(data: &[f64]) -> Result<GEV, FitError> {
+        check_data(data, 3)?;
+        check_distinct(data, 3)?;
+        let mut sorted: Vec<f64> = data.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let log_spacings = |gev: &GEV| -> f64 {
+            if gev.scale <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            let mut total: f64 = 0.0;
+            let mut prev: f64 = 0.0;
+            for (i, &x) in sorted.iter().enumerate() {
+                let cdf: f64 = cdf_extended(gev, x);
+                if i > 0 && x == sorted[i - 1] {
+                    total += gev.log_density(x);
+                } else {
+                    total += log(cdf - prev);
+                }
+                prev = cdf;
+            }
+            total + log(1.0 - prev)
+        };
+
+        let init: GEV = match GEV::fit_pwm(data) {
+            Ok(gev) if log_spacings(&gev).is_finite() => gev,
+            _ => {
+                let (mean, sd) = mean_sd(data);
+                GEV { shape: 0.0, ..GEV::moment_start(mean, sd) }
+            }
+        };
+        GEV::maximize(init, log_spacings)
+    }
+
+    /// Fit the GEV distribution to the data by probability weighted moments (equivalently
+    /// L-moments), with Hosking's approximation of the shape from the sample L-skewness $\tau_3$:
+    /// $$ c = \frac{2}{3 + \tau_3} - \frac{\log 2}{\log 3}, \quad k = 7.8590 c + 2.9554 c^2, \quad shape = -k. $$
+    /// The scale and location then follow from $\lambda_2$ and $\lambda_1$, the L-moments being computed from
+    /// the sample probability weighted moments $b_0, b_1, b_2$ (see `lmoments::sample_pwm`).
+    /// At least 3 finite observations taking at least 3 distinct values are required.
+    pub fn fit_pwm(data: &[f64]) -> Result<GEV, FitError> {
+        check_data(data, 3)?;
+        check_distinct(data, 3)?;
+        let lmom: LMoments = sample_lmoments(data);
+        let c: f64 = 2.0 / (3.0 + lmom.t3()) - log(2.0) / log(3.0);
+        let k: f64 = 7.8590 * c + 2.9554 * c * c;
+
+        let fitted: GEV = if k.abs() < 1e-8 {
+            let scale: f64 = lmom.l2 / log(2.0);
+            GEV { loc: lmom.l1 - EULER_GAMMA * scale, scale, shape: 0.0 }
+        } else {
+            let gam: f64 = tgamma(1.0 + k);
+            let scale: f64 = lmom.l2 * k / ((1.0 - pow(2.0, -k)) * gam);
+            GEV { loc: lmom.l1 - scale * (1.0 - gam) / k, scale, shape: -k }
+        };
+        if fitted.loc.is_finite() && fitted.scale.is_finite() && fitted.scale > 0.0 {
+            Ok(fitted)
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+    /// First-order stochastic dominance of `self` over `other`, checked on a grid: whether
+    /// $F_{self}(x) \leq F_{other}(x)$, i.e. `self` puts at least as much probability above every
+    /// level. The CDFs (extended to 0 below and 1 above the supports) are compared at `n` equally
+    /// spaced points from `lo` to `hi` (both included). Returns `Some(true)` if `self` dominates on
+    /// the grid, `Some(false)` if `other` dominates, and `None` if the CDFs cross.
+    /// This is an approximation: a crossing between two grid points or outside of $[lo, hi]$
+    /// is missed, so the grid should cover the bulk and tails of both distributions
+    /// (e.g. from the 0.1% quantile to the 99.9% quantile). Identical CDFs give `Some(true)`.
+    /// `n` must be at least 2 and `lo < hi`.
+    pub fn dominates(&self, other: &GEV, lo: f64, hi: f64, n: usize) -> Option<bool> {
+        domain!(n >= 2 && lo < hi);
+        let (mut below, mut above) = (false, false);
+        for i in 0..n {
+            let x: f64 = lo + (hi - lo) * i as f64 / (n - 1) as f64;
+            let (mine, theirs) = (cdf_extended(self, x), cdf_extended(other, x));
+            below |= mine < theirs;
+            above |= mine > theirs;
+        }
+        match (below, above) {
+            (true, true) => None,
+            (_, false) => Some(true),
+            (false, true) => Some(false),
+        }
+    }
+
+    /// Likelihood-ratio test of the Gumbel distribution (shape = 0) against the GEV distribution,
+    /// both fitted to the data by maximum likelihood. Returns the statistic
+    /// $D = 2 (\ell_{GEV} - \ell_{Gumbel})$ and its p-value under the $\chi^2_1$ distribution,
+    /// $P(\chi^2_1 > D) = \mathrm{erfc}(\sqrt{D / 2})$; a small p-value means the shape differs from 0.
+    /// The Gumbel model is nested in the GEV model (one parameter fixed at an interior value), which
+    /// is what justifies the asymptotic $\chi^2_1$ distribution; it is only approximate for small samples.
+    /// The GEV fit is started from the Gumbel fit, so $D \geq 0$ (it is clamped at 0 against rounding).
+    /// At least 3 finite observations are required.
+    pub fn lr_test_gumbel(data: &[f64]) -> Result<(f64, f64), FitError> {
+        let gumbel: Gumbel = Gumbel::fit_mle(data)?;
+        let gev: GEV = GEV::fit_mle_from(data, GEV { loc: gumbel.loc, scale: gumbel.scale, shape: 0.0 })?;
+        let stat: f64 = (2.0 * (gev.log_likelihood(data) - gumbel.log_likelihood(data))).max(0.0);
+        Ok((stat, erfc(sqrt(stat / 2.0))))
+    }
+
+    /// Return level for the given return period (in blocks, e.g. years for annual maxima), i.e.
+    /// the level exceeded on average once every `return_period` blocks: $F^{-1}(1 - 1 / T)$.
+    /// The return period must be larger than 1.
+    pub fn return_level(&self, return_period: f64) -> f64 {
+        domain!(return_period > 1.0);
+        self.quantile(1.0 - 1.0 / return_period)
+    }
+
+    /// Return level among the exceedances of `threshold`: the level exceeded by one in
+    /// `return_period` of the events above the threshold, i.e. the quantile $1 - 1 / T$ of the
+    /// conditional distribution $F_u$ of $X$ given $X > u$ (see `DistQuant::conditional_cdf`).
+    /// As $P(X > x \mid X > u) = \frac{S(x)}{S(u)}$, it is `isf(sf(u) / T)`, computed from the stable
+    /// survival functions so that high thresholds keep their accuracy. "Given a flood occurs (exceeds
+    /// $u$), how large is the $T$-flood among floods": for a high threshold it is close to the
+    /// peaks-over-threshold return level of the GPD approximation of the exceedances.
+    /// The threshold must lie in the support and the return period must be larger than 1.
+    pub fn conditional_return_level(&self, threshold: f64, return_period: f64) -> f64 {
+        domain!(return_period > 1.0 && self.is_in_support(threshold));
+        self.isf(self.sf(threshold) / return_period)
+    }
+
+    /// Return level for a return period given on a base-10 log scale, i.e. `return_level(10^log10_period)`,
+    /// for hydrology tables using a $\log_{10}(T)$ axis.
+    pub fn return_level_log10(&self, log10_period: f64) -> f64 {
+        self.return_level(pow(10.0, log10_period))
+    }
+
+    /// Base-10 logarithm of the return period of the given level (inverse of `return_level_log10`),
+    /// $- \log_{10} S(level)$, from the stable `sf` so that it stays finite far in the upper tail.
+    pub fn return_period_log10(&self, level: f64) -> f64 {
+        - log10(self.sf(level))
+    }
+
+    /// Return level estimate with an optional bias correction, where `self` is the maximum
+    /// likelihood fit of `data` (maximum likelihood return levels are biased on short records).
+    ///
+    /// With `BiasCorrection::Bootstrap { reps, seed }`, the data is resampled with replacement
+    /// `reps` times, the GEV is refitted by maximum likelihood on each resample and its return level
+    /// computed (failed refits are discarded). The bias is estimated by the mean bootstrap return
+    /// level minus the plug-in estimate, and the corrected estimate is the plug-in estimate minus
+    /// that bias. If every refit fails, the plug-in estimate is returned.
+    pub fn return_level_bias_corrected(&self, data: &[f64], return_period: f64, method: BiasCorrection) -> f64 {
+        let plug_in: f64 = self.return_level(return_period);
+        match method {
+            BiasCorrection::None => plug_in,
+            BiasCorrection::Bootstrap { reps, seed } => {
+                let refit = |sample: &[f64]| match GEV::fit_mle(sample) {
+                    Ok(gev) => gev.return_level(return_period),
+                    Err(_) => f64::NAN,
+                };
+                let levels: Vec<f64> = bootstrap(data, reps, RandomSeed::Seed(seed), refit)
+                    .into_iter()
+                    .filter(|x| x.is_finite())
+                    .collect();
+                if levels.is_empty() {
+                    return plug_in;
+                }
+                let bias: f64 = levels.iter().sum::<f64>() / levels.len() as f64 - plug_in;
+                plug_in - bias
+            }
+        }
+    }
+
+    /// Predictive interval of level `level` for the next block maximum, accounting for the
+    /// uncertainty of the parameters, where `self` is the maximum likelihood fit of `data`.
+    ///
+    /// The data is resampled with replacement `reps` times and the GEV refitted by maximum likelihood
+    /// on each resample (failed refits are discarded), which approximates the distribution of the
+    /// parameters in the spirit of a Bayesian bootstrap. The predictive CDF is the average of the
+    /// refitted CDFs, $\tilde F(x) = \frac{1}{B} \sum_b F_b(x)$, i.e. the CDF integrated over the
+    /// bootstrap parameter distribution, and the interval is made of its quantiles at
+    /// $\frac{1 - level}{2}$ and $\frac{1 + level}{2}$. It is wider than the plug-in interval of
+    /// `self`, which ignores the estimation error; it is returned if every refit fails.
+    pub fn prediction_interval(&self, data: &[f64], level: f64, reps: usize, seed: RandomSeed) -> (f64, f64) {
+        domain!(level > 0.0 && level < 1.0 && !data.is_empty());
+        let (p_lo, p_hi) = (0.5 * (1.0 - level), 0.5 * (1.0 + level));
+        let mut rng: ChaCha8Rng = seed.rng();
+        let mut resample: Vec<f64> = vec![0.0; data.len()];
+        let mut fits: Vec<GEV> = Vec::with_capacity(reps);
+        for _ in 0..reps {
+            for x in resample.iter_mut() {
+                *x = data[rng.gen_range(0..data.len())];
+            }
+            if let Ok(gev) = GEV::fit_mle(&resample) {
+                fits.push(gev);
+            }
+        }
+        if fits.is_empty() {
+            return (self.quantile(p_lo), self.quantile(p_hi));
+        }
+        (mixture_quantile(&fits, p_lo), mixture_quantile(&fits, p_hi))
+    }
+
+    /// Return period $1 / S(x)$ implied by the distribution for each observation, e.g. to flag the
+    /// observations that are rare under a fitted model. Values below the lower end point of the
+    /// support get a return period of 1 and values above the upper end point an infinite one.
+    pub fn observed_return_periods(&self, data: &[f64]) -> Vec<f64> {
+        let (lower, upper) = self.support();
+        data.iter()
+            .map(|&x| {
+                if x <= lower {
+                    1.0
+                } else if x >= upper {
+                    f64::INFINITY
+                } else {
+                    1.0 / self.sf(x)
+                }
+            })
+            .collect()
+    }
+
+    /// Sampling distribution of the maximum likelihood estimate of the return level for the return
+    /// period $T$, by parametric bootstrap from `self` (typically a fitted model): `reps` samples of
+    /// `data_len` observations are simulated from `self`, the GEV is refitted by maximum likelihood
+    /// on each of them and its return level recorded. Failed refits are discarded, so fewer than
+    /// `reps` values may be returned. All the samples are drawn from a single generator seeded with `seed`.
+    pub fn return_level_distribution(&self, data_len: usize, return_period: f64, reps: usize, seed: RandomSeed) -> Vec<f64> {
+        let mut rng: ChaCha8Rng = seed.rng();
+        let mut sample: Vec<f64> = vec![0.0; data_len];
+        let mut levels: Vec<f64> = Vec::with_capacity(reps);
+        for _ in 0..reps {
+            for x in sample.iter_mut() {
+                *x = self.sample_from_uniform(rng.gen::<f64>());
+            }
+            if let Ok(gev) = GEV::fit_mle(&sample) {
+                let level: f64 = gev.return_level(return_period);
+                if level.is_finite() {
+                    levels.push(level);
+                }
+            }
+        }
+        levels
+    }
+
+    /// Log-likelihood of the data evaluated over a (loc, scale) grid at a fixed shape, e.g. to
+    /// draw a heatmap of the likelihood surface around the optimum.
+    /// The outer vector is indexed by `loc_grid` and the inner vectors by `scale_grid`, i.e.
+    /// `surface[i][j]` is the log-likelihood at `(loc_grid[i], scale_grid[j], shape)`.
+    pub fn loglik_surface(data: &[f64], loc_grid: &[f64], scale_grid: &[f64], shape: f64) -> Vec<Vec<f64>> {
+        loc_grid.iter()
+            .map(|&loc| scale_grid.iter()
+                .map(|&scale| GEV { loc, scale, shape }.log_likelihood(data))
+                .collect())
+            .collect()
+    }
+
+}
+
+/// Relative step (in units of the scale) used to move the random values that round to an end point
+/// of the support strictly inside it.
+pub const SAMPLE_SUPPORT_EPS: f64 = 1e-12;
+
+/// Sign conventions for the GEV shape parameter used by the different tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Convention {
+    /// The convention of the crate, shared with R (`ismev`, `extRemes`, `evd`): a positive shape
+    /// $\xi$ gives the heavy tailed (Fréchet type) distribution.
+    #[default]
+    Native,
+    /// The convention of `scipy.stats.genextreme`, whose shape is $c = -\xi$.
+    Scipy,
+}
+
+impl Convention {
+    /// Convert a shape parameter between this convention and the native one (the mapping is its
+    /// own inverse).
+    pub fn to_native(&self, shape: f64) -> f64 {
+        match self {
+            Convention::Native => shape,
+            Convention::Scipy => - shape,
+        }
+    }
+}
+
+/// Methods to pool the return levels of an ensemble of GEV distributions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolMethod {
+    /// Average of the return levels of the members.
+    QuantileAverage,
+    /// Return level of the equally weighted mixture, i.e. the level where the average of the
+    /// members' CDFs equals $1 - 1 / T$ (found numerically by bisection).
+    MixtureCdf,
+}
+
+/// CDF of the GEV extended to the whole real line (0 below and 1 above the support).
+fn cdf_extended(gev: &GEV, x: f64) -> f64 {
+    let (lower, upper) = gev.support();
+    if x <= lower {
+        0.0
+    } else if x >= upper {
+        1.0
+    } else {
+        gev.cdf(x)
+    }
+}
+
+/// Pooled return level of an ensemble of GEV distributions (e.g. multi-model climate ensembles),
+/// either by averaging the members' return levels or by inverting the CDF of the equally
+/// weighted mixture of the members (see `PoolMethod`).
+/// The mixture return level always lies between the smallest and largest member return levels,
+/// which are used as the initial bracket of the bisection.
+pub fn ensemble_return_level(dists: &[GEV], return_period: f64, method: PoolMethod) -> f64 {
+    domain!(!dists.is_empty() && return_period > 1.0);
+    let levels: Vec<f64> = dists.iter().map(|gev| gev.return_level(return_period)).collect();
+    match method {
+        PoolMethod::QuantileAverage => levels.iter().sum::<f64>() / levels.len() as f64,
+        PoolMethod::MixtureCdf => mixture_quantile(dists, 1.0 - 1.0 / return_period),
+    }
+}
+
+/// Quantile of the equally weighted mixture of the GEV distributions, by bisection between the
+/// smallest and largest quantiles of the members (which bracket the mixture quantile).
+fn mixture_quantile(dists: &[GEV], prob: f64) -> f64 {
+    let mixture_cdf = |x: f64| dists.iter().map(|gev| cdf_extended(gev, x)).sum::<f64>() / dists.len() as f64;
+    let mut lo: f64 = dists.iter().map(|gev| gev.quantile(prob)).fold(f64::INFINITY, f64::min);
+    let mut hi: f64 = dists.iter().map(|gev| gev.quantile(prob)).fold(f64::NEG_INFINITY, f64::max);
+    for _ in 0..200 {
+        let mid: f64 = 0.5 * (lo + hi);
+        if mixture_cdf(mid) < prob { lo = mid } else { hi = mid }
+    }
+    0.5 * (lo + hi)
+}
+
+/// CDF at `x` of the GEV distribution of every `(loc, scale, shape)` parameter set in `params`
+/// (e.g. the cells of a spatial grid), in the same order. The CDF is extended to the whole real
+/// line, so that `x` may lie outside the support of some of the parameter sets (0 below and 1
+/// above the support). The distributions are built on the stack and never stored.
+pub fn cdf_broadcast(params: &[(f64, f64, f64)], x: f64) -> Vec<f64> {
+    params.iter().map(|&(loc, scale, shape)| cdf_extended(&GEV::new(loc, scale, shape), x)).collect()
+}
+
+/// PDF at `x` of the GEV distribution of every `(loc, scale, shape)` parameter set in `params`,
+/// in the same order. The PDF is 0 for the parameter sets whose support does not contain `x`.
+pub fn pdf_broadcast(params: &[(f64, f64, f64)], x: f64) -> Vec<f64> {
+    params.iter().map(|&(loc, scale, shape)| {
+        let gev: GEV = GEV::new(loc, scale, shape);
+        if gev.is_in_support(x) { gev.pdf(x) } else { 0.0 }
+    }).collect()
+}
+
+/// Grid of GEV parameters for sensitivity studies, e.g. of the response of the return levels to
+/// the parameters: the product of equally spaced values of the location, scale and shape.
+/// Iterating over the grid (`iter`, or `for gev in &grid`) yields the GEV distributions with the
+/// location varying slowest and the shape fastest, skipping the combinations with a non-positive
+/// (or non-finite) scale and the non-finite locations and shapes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamGrid {
+    loc: Vec<f64>,
+    scale: Vec<f64>,
+    shape: Vec<f64>,
+}
+
+impl ParamGrid {
+    /// Grid over the `(lo, hi, n)` ranges of the location, scale and shape: each range gives `n`
+    /// equally spaced values from `lo` to `hi`, both included (only `lo` if `n = 1`, and none if `n = 0`).
+    pub fn new(loc: (f64, f64, usize), scale: (f64, f64, usize), shape: (f64, f64, usize)) -> Self {
+        ParamGrid { loc: ParamGrid::axis(loc), scale: ParamGrid::axis(scale), shape: ParamGrid::axis(shape) }
+    }
+
+    fn axis((lo, hi, n): (f64, f64, usize)) -> Vec<f64> {
+        match n {
+            0 => Vec::new(),
+            1 => vec![lo],
+            _ => (0..n).map(|i| lo + (hi - lo) * i as f64 / (n - 1) as f64).collect(),
+        }
+    }
+
+    /// Number of parameter combinations of the grid, including the invalid ones that are skipped.
+    pub fn n_combinations(&self) -> usize {
+        self.loc.len() * self.scale.len() * self.shape.len()
+    }
+
+    /// Iterator over the valid GEV distributions of the grid.
+    pub fn iter(&self) -> ParamGridIter<'_> {
+        ParamGridIter { grid: self, index: 0 }
+    }
+}
+
+impl<'a> IntoIterator for &'a ParamGrid {
+    type Item = GEV;
+    type IntoIter = ParamGridIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the valid GEV distributions of a `ParamGrid`.
+#[derive(Debug, Clone)]
+pub struct ParamGridIter<'a> {
+    grid: &'a ParamGrid,
+    index: usize,
+}
+
+impl Iterator for ParamGridIter<'_> {
+    type Item = GEV;
+
+    fn next(&mut self) -> Option<GEV> {
+        let (n_scale, n_shape) = (self.grid.scale.len(), self.grid.shape.len());
+        while self.index < self.grid.n_combinations() {
+            let i: usize = self.index;
+            self.index += 1;
+            let params: [f64; 3] = [self.grid.loc[i / (n_scale * n_shape)], self.grid.scale[(i / n_shape) % n_scale], self.grid.shape[i % n_shape]];
+            if let Ok(gev) = GEV::from_params(&params) {
+                return Some(gev);
+            }
+        }
+        None
+    }
+}
+
+/// Fit the GEV distribution to each column (sample) of `data` independently and in parallel with
+/// rayon (requires the `parallel` feature), e.g. for the thousands of cells of a spatial grid.
+/// Fitting is deterministic given the data, so the results do not depend on the number of threads
+/// and are identical to fitting each column in turn with `GEV::fit`.
+#[cfg(feature = "parallel")]
+pub fn fit_columns(data: &[Vec<f64>], method: FitMethod) -> Vec<Result<GEV, FitError>> {
+    use rayon::prelude::*;
+    data.par_iter().map(|column| GEV::fit(column, method)).collect()
+}
+
+/// Fitting methods available for the GEV Distribution: maximum likelihood and PWM.
+impl Fittable for GEV {
+    fn fit(data: &[f64], method: FitMethod) -> Result<Self, FitError> {
+        match method {
+            FitMethod::Mle => GEV::fit_mle(data),
+            FitMethod::Pwm => GEV::fit_pwm(data),
+            FitMethod::MomentMatching => Err(FitError::UnsupportedMethod),
+        }
+    }
+}
+
+impl fmt::Display for GEV {
+    /// Format as `GEV(loc=..., scale=..., shape=...)`, honoring the precision of the formatter
+    /// (e.g. `format!("{:.2}", dist)`), the parameters being printed in full by default.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_params(f, "GEV", &[("loc", self.loc), ("scale", self.scale), ("shape", self.shape)])
+    }
+}
+
+impl LocationScale for GEV {
+    #[inline(always)]
+    fn loc(&self) -> f64 {
+        self.loc
+    }
+
+    #[inline(always)]
+    fn scale(&self) -> f64 {
+        self.scale
+    }
 }
 
 impl DistQuant for GEV {
@@ -69,6 +1045,48 @@ impl DistQuant for GEV {
         let t_val: f64 = self.t_func(x);
         exp(- t_val)
     }
+
+    /// PDF and CDF computed together: with $b = 1 + shape \frac{x - loc}{scale}$ and $t = b^{-1 / shape}$,
+    /// $F(x) = e^{-t}$ and $f(x) = \frac{t}{b \cdot scale} F(x)$, so one `pow` and one `exp` are
+    /// saved compared to separate `pdf` and `cdf` calls.
+    fn evaluate(&self, x: f64) -> (f64, f64) {
+        domain!(1.0 + self.shape * ( (x - self.loc ) / self.scale ) > 0.0 && self.scale > 0.0);
+        let y: f64 = self.reduced(x);
+        let (t_val, base): (f64, f64) = if self.shape == 0.0 {
+            (exp(- y), 1.0)
+        } else {
+            let base: f64 = 1.0 + self.shape * y;
+            (pow(base, - 1.0 / self.shape), base)
+        };
+        let cdf: f64 = exp(- t_val);
+        (t_val / (base * self.scale) * cdf, cdf)
+    }
+
+    /// Survival function $S(x) = - \mathrm{expm1}(- t(x))$, accurate in the upper tail.
+    fn sf(&self, x: f64) -> f64 {
+        domain!(1.0 + self.shape * ( (x - self.loc ) / self.scale ) > 0.0 && self.scale > 0.0);
+        - expm1(- self.t_func(x))
+    }
+
+    /// Logarithm of the CDF, exactly $\log F(x) = - t(x)$, without underflow in the lower tail.
+    fn log_cdf(&self, x: f64) -> f64 {
+        domain!(1.0 + self.shape * ( (x - self.loc ) / self.scale ) > 0.0 && self.scale > 0.0);
+        - self.t_func(x)
+    }
+
+    /// Logarithm of the survival function, $\log(1 - e^{-t})$ with $\log t(x)$ computed directly
+    /// (see `gumbel_reduced`), without underflow in the upper tail.
+    fn log_sf(&self, x: f64) -> f64 {
+        log1mexp(- self.gumbel_reduced(x))
+    }
+
+    /// Reduced variate $- \log t(x)$, i.e. $\frac{\mathrm{log1p}(shape \cdot y)}{shape}$ with
+    /// $y = \frac{x - loc}{scale}$ ($y$ if $shape = 0$), without forming $t(x)$.
+    fn gumbel_reduced(&self, x: f64) -> f64 {
+        domain!(1.0 + self.shape * ( (x - self.loc ) / self.scale ) > 0.0 && self.scale > 0.0);
+        let y: f64 = self.reduced(x);
+        if self.shape == 0.0 { y } else { log1p(self.shape * y) / self.shape }
+    }
     
     /// PDF of the GEV distribution.
     /// $$ f(x) = \frac{1}{ scale } t_func(x)^{\zeta + 1} \cdot F(x)  $$
@@ -81,14 +1099,67 @@ impl DistQuant for GEV {
 
     /// Quantile (inverse CDF) function.
     /// If $shape = 0$, $F^{-1}(x) = loc - scale * \log(- \log x)$
-    /// o.w. we have $\frac{scale}{shape} * (- \log x)^{- shape} - \frac{scale}{shape} + loc$
+    /// o.w. we have $\frac{scale}{shape} * (- \log x)^{- shape} - \frac{scale}{shape} + loc$.
+    /// When $|shape \log(- \log x)|$ is small (e.g. a shape close to 0), $(- \log x)^{- shape} - 1$ suffers
+    /// from cancellation, so it is computed as $expm1(- shape \log(- \log x))$ instead, which continuously
+    /// approaches the $shape = 0$ formula.
     fn quantile(&self, x: f64) -> f64 {
-        domain!(x >= 0.0 && x <= 1.0);
+        domain!((0.0..=1.0).contains(&x));
         if self.shape == 0.0 {
             - self.scale * log( - log(x)) + self.loc
         } else {
+            let log_y: f64 = log(- log(x));
+            let mult_const: f64 = self.scale / self.shape;
+            if (self.shape * log_y).abs() < 0.5 {
+                mult_const * expm1(- self.shape * log_y) + self.loc
+            } else {
+                mult_const * pow(- log(x) , - self.shape) - mult_const + self.loc
+            }
+        }
+    }
+
+    /// Inverse survival function, the quantile formula with $- \log x$ replaced by
+    /// $- \mathrm{log1p}(- p)$, so that small exceedance probabilities keep all their digits.
+    fn isf(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        let t_val: f64 = - log1p(- p);
+        if self.shape == 0.0 {
+            - self.scale * log(t_val) + self.loc
+        } else {
+            let log_y: f64 = log(t_val);
             let mult_const: f64 = self.scale / self.shape;
-            mult_const * pow(- log(x) , - self.shape) - mult_const + self.loc
+            if (self.shape * log_y).abs() < 0.5 {
+                mult_const * expm1(- self.shape * log_y) + self.loc
+            } else {
+                mult_const * pow(t_val, - self.shape) - mult_const + self.loc
+            }
+        }
+    }
+
+    /// Density quantile function $\frac{dQ}{dp} = \frac{scale}{p} (- \log p)^{- shape - 1}$, for
+    /// every shape (the Gumbel form when $shape = 0$). It is infinite at $p = 0$ whatever the shape.
+    /// At $p = 1$ it is infinite for $shape > -1$, $scale$ for $shape = -1$ and 0 for $shape < -1$
+    /// (where the density is unbounded at the upper end point).
+    fn quantile_density(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        if p == 0.0 {
+            return f64::INFINITY;
+        }
+        if p == 1.0 {
+            return if self.shape > -1.0 { f64::INFINITY } else if self.shape == -1.0 { self.scale } else { 0.0 };
+        }
+        self.scale * pow(- log(p), - self.shape - 1.0) / p
+    }
+
+    /// Support: $(loc - scale / shape, \infty)$ if $shape > 0$, $(-\infty, loc - scale / shape)$ if
+    /// $shape < 0$ and the whole real line if $shape = 0$.
+    fn support(&self) -> (f64, f64) {
+        if self.shape > 0.0 {
+            (self.loc - self.scale / self.shape, f64::INFINITY)
+        } else if self.shape < 0.0 {
+            (f64::NEG_INFINITY, self.loc - self.scale / self.shape)
+        } else {
+            (f64::NEG_INFINITY, f64::INFINITY)
         }
     }
 
@@ -100,7 +1171,24 @@ impl DistQuant for GEV {
             RandomSeed::Seed(val) => ChaCha8Rng::seed_from_u64(val), // ChaCha8Rng implements the SeedableRng trait
         };
         let rand_quant: f64 = rng.gen::<f64>(); // generate randomly from U(0,1)
-        self.quantile(rand_quant) // then plug that random uniform into the quantile.
+        self.sample_from_uniform(rand_quant) // then plug that random uniform into the quantile.
+    }
+
+    /// Whether `x` lies inside the support, with the exact check $1 + shape \frac{x - loc}{scale} > 0$
+    /// used by `cdf` and `pdf`.
+    fn is_in_support(&self, x: f64) -> bool {
+        x.is_finite() && 1.0 + self.shape * ( (x - self.loc) / self.scale ) > 0.0
+    }
+
+    /// Generate `n` random values, nudged inside the support like `random`.
+    fn sample_n(&self, n: usize, seed: RandomSeed) -> Vec<f64> {
+        let mut rng: ChaCha8Rng = seed.rng();
+        (0..n).map(|_| self.sample_from_uniform(rng.gen::<f64>())).collect()
+    }
+
+    /// Generate a random value with the given random number generator, nudged inside the support like `random`.
+    fn random_with<R: Rng>(&self, mut rng: R) -> f64 where Self: Sized {
+        self.sample_from_uniform(rng.gen::<f64>())
     }
  
 }
@@ -109,6 +1197,8 @@ impl DistQuant for GEV {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dist::gof::cramer_von_mises;
+    use crate::dist::gpd::GeneralizedPareto;
 
     // quick macro to create the instance of the Gumbel Distribution
     macro_rules! new_gev(
@@ -164,4 +1254,692 @@ mod tests {
         assert_eq!(ans, quant_gev);
     }
 
+    #[test]
+    fn gev_quantile_shape_near_zero_test() {
+        let gumbel_quant: f64 = - log(- log(0.99));
+        // for a small shape the quantile is y + shape * y^2 / 2 to first order, y being the Gumbel quantile
+        let gev: GEV = new_gev!(0.0, 1.0, 1e-7);
+        let first_order: f64 = gumbel_quant + 1e-7 * gumbel_quant * gumbel_quant / 2.0;
+        assert!((gev.quantile(0.99) - first_order).abs() < 1e-12);
+        assert!((gev.quantile(0.99) - gumbel_quant).abs() < 2e-6);
+        // the pow based formula was off by ~1e-4 here because of the cancellation
+        let gev: GEV = new_gev!(0.0, 1.0, 1e-12);
+        assert!((gev.quantile(0.99) - gumbel_quant).abs() < 1e-10);
+        let gev: GEV = new_gev!(0.0, 1.0, -1e-12);
+        assert!((gev.quantile(0.99) - gumbel_quant).abs() < 1e-10);
+    }
+
+    #[test]
+    fn gev_random_in_support_test() {
+        for shape in [-0.5, -8.0, 8.0] {
+            let gev: GEV = new_gev!(2.0, 2.0, shape);
+            let samples: Vec<f64> = gev.sample_n(20000, RandomSeed::Seed(1));
+            assert!(samples.iter().all(|&x| gev.is_in_support(x)));
+            assert!(samples.iter().all(|&x| gev.pdf(x).is_finite()));
+            assert!(gev.is_in_support(gev.random(RandomSeed::Seed(2))));
+        }
+        // the upper end point itself is not in the support
+        let gev: GEV = new_gev!(2.0, 2.0, -8.0);
+        assert!(!gev.is_in_support(gev.support().1));
+        assert!(gev.is_in_support(gev.sample_from_uniform(1.0 - f64::EPSILON)));
+    }
+
+    #[test]
+    fn gev_from_validated_test() {
+        let gev: GEV = GEV::from_validated(2.0, Scale::new(2.0).unwrap(), Shape::new(2.0).unwrap());
+        let raw: GEV = new_gev!(2.0, 2.0, 2.0);
+        assert_eq!(raw.cdf(3.0), gev.cdf(3.0));
+        assert_eq!(raw.pdf(3.0), gev.pdf(3.0));
+        assert_eq!(raw.quantile(0.7), gev.quantile(0.7));
+    }
+
+    #[test]
+    fn gev_param_bounds_test() {
+        let bounds: Vec<(f64, f64)> = GEV::param_bounds();
+        assert_eq!(bounds.len(), 3);
+        // the lower bounds are valid parameters, and the values just outside are rejected
+        assert!(GEV::from_params(&[0.5, f64::EPSILON, -3.0]).is_ok());
+        assert!(GEV::from_params(&[0.5, 0.0, -3.0]).is_err());
+        assert!(bounds.iter().all(|(lo, hi)| lo < hi));
+    }
+
+    #[test]
+    fn gev_to_standard_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.2);
+        for x in [5.0, 10.0, 20.0, 100.0] {
+            let z: f64 = gev.to_standard(x);
+            assert!((gev.from_standard(z) - x).abs() < 1e-12);
+            assert!((Gumbel::new(0.0, 1.0).cdf(z) - gev.cdf(x)).abs() < 1e-14);
+        }
+        let gev0: GEV = new_gev!(10.0, 2.0, 0.0);
+        assert_eq!(gev0.to_standard(14.0), 2.0);
+        assert_eq!(gev0.from_standard(2.0), 14.0);
+
+        // the standardized data of a fitted GEV is close to the standard Gumbel distribution
+        let data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, -0.2), 500);
+        let fitted: GEV = GEV::fit_mle(&data).unwrap();
+        let standard: Vec<f64> = data.iter().map(|&x| fitted.to_standard(x)).collect();
+        assert!(cramer_von_mises(&Gumbel::new(0.0, 1.0), &standard) < 0.461);
+    }
+
+    #[test]
+    fn gev_residuals_test() {
+        let data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, 0.2), 500);
+        let fitted: GEV = GEV::fit_mle(&data).unwrap();
+        let residuals: Vec<f64> = fitted.residuals(&data);
+        for (r, &x) in residuals.iter().zip(data.iter()) {
+            assert!((r + log(fitted.cdf(x))).abs() < 1e-12);
+        }
+        let exponential: GeneralizedPareto = GeneralizedPareto::new(0.0, 1.0, 0.0);
+        assert!(cramer_von_mises(&exponential, &residuals) < 0.461);
+    }
+
+    #[test]
+    fn gev_from_r_mle_test() {
+        // `fevd(x, use.phi = TRUE)`: location = 10.2, log.scale = 0.9162907 (scale 2.5), shape = 0.12
+        let gev: GEV = GEV::from_r_mle(10.2, 0.9162907318741551, 0.12).unwrap();
+        assert_eq!(gev.loc, 10.2);
+        assert!((gev.scale - 2.5).abs() < 1e-14);
+        assert_eq!(gev.shape, 0.12);
+        assert_eq!(GEV::from_r_mle(10.2, -800.0, 0.12), Err(ParamError::NonPositiveScale));
+        assert_eq!(GEV::from_r_mle(10.2, f64::NAN, 0.12), Err(ParamError::NonFinite));
+    }
+
+    #[test]
+    fn gev_unconstrained_test() {
+        let gev: GEV = GEV::new(10.2, 1.0, -0.12);
+        assert_eq!(gev.to_unconstrained(), [10.2, 0.0, -0.12]);
+        assert_eq!(GEV::from_unconstrained(&gev.to_unconstrained()), gev);
+        for &scale in [1e-10, 0.3, 2.5, 7.0, 1e12].iter() {
+            let gev: GEV = GEV::new(10.2, scale, 0.12);
+            let back: GEV = GEV::from_unconstrained(&gev.to_unconstrained());
+            assert_eq!((back.loc, back.shape), (gev.loc, gev.shape));
+            // exact up to the rounding of log(scale), amplified by |log(scale)| through exp
+            assert!((back.scale / scale - 1.0).abs() < 1e-14);
+        }
+        for &log_scale in [-1e6, -800.0, -5.0, 0.0, 5.0, 800.0, 1e6].iter() {
+            let gev: GEV = GEV::from_unconstrained(&[0.0, log_scale, 0.1]);
+            assert!(gev.scale > 0.0 && gev.scale.is_finite());
+        }
+        // the unconstrained objective
+        let data: [f64; 5] = [9.1, 10.4, 11.0, 12.7, 15.3];
+        let nll = |p: &[f64]| GEV::from_unconstrained(p).negative_log_likelihood(&data);
+        assert_eq!(nll(&gev.to_unconstrained()), - gev.log_likelihood(&data));
+        assert_eq!(nll(&[20.0, 0.0, 0.5]), f64::INFINITY);
+    }
+
+    #[test]
+    fn gev_convention_test() {
+        let scipy: GEV = GEV::new_with_convention(1.0, 2.0, 0.3, Convention::Scipy);
+        let native: GEV = new_gev!(1.0, 2.0, -0.3);
+        assert_eq!(scipy, native);
+        for x in [-2.0, 0.5, 3.0, 6.0] {
+            assert_eq!(scipy.cdf(x), native.cdf(x));
+        }
+        assert_eq!(scipy.shape_in(Convention::Scipy), 0.3);
+        assert_eq!(scipy.shape_in(Convention::Native), -0.3);
+        assert_eq!(GEV::new_with_convention(1.0, 2.0, 0.3, Convention::default()), new_gev!(1.0, 2.0, 0.3));
+    }
+
+    #[test]
+    fn gev_display_test() {
+        let gev: GEV = new_gev!(1.23456, 2.0, -0.105);
+        assert_eq!(format!("{}", gev), "GEV(loc=1.23456, scale=2, shape=-0.105)");
+        assert_eq!(format!("{:.2}", gev), "GEV(loc=1.23, scale=2.00, shape=-0.10)");
+        assert_eq!(format!("{:.0}", gev), "GEV(loc=1, scale=2, shape=-0)");
+    }
+
+    #[test]
+    fn gev_params_test() {
+        let gev: GEV = new_gev!(2.0, 2.0, -0.5);
+        assert_eq!(gev.as_params(), vec![2.0, 2.0, -0.5]);
+        assert_eq!(GEV::from_params(&gev.as_params()), Ok(gev));
+        assert_eq!(GEV::from_params(&[2.0, 2.0, -0.5, 1.0]), Err(ParamError::WrongLength { expected: 3, found: 4 }));
+        assert_eq!(GEV::from_params(&[2.0, 2.0, f64::INFINITY]), Err(ParamError::NonFinite));
+    }
+
+    #[test]
+    fn gev_affine_test() {
+        let gev: GEV = new_gev!(2.0, 2.0, -0.5);
+        // the shape is unchanged, so are the probabilities of the transformed values
+        let moved: GEV = gev.affine(0.5, 1.0).with_scale(3.0);
+        assert_eq!(moved, new_gev!(2.0, 3.0, -0.5));
+        assert!((gev.affine(0.5, 1.0).cdf(0.5 * 2.5 + 1.0) - gev.cdf(2.5)).abs() < 1e-14);
+        assert_eq!(new_gev!(2.0, 1e10, 0.1).try_affine(1e308, 0.0), Err(ParamError::NonFinite));
+        assert_eq!(gev.try_with_scale(0.0), Err(ParamError::NonPositiveScale));
+    }
+
+    #[test]
+    fn gev_le_bytes_test() {
+        let gev: GEV = new_gev!(2.0, 2.0, -0.5);
+        let bytes: Vec<u8> = gev.to_le_bytes();
+        assert_eq!(bytes.len(), 25);
+        assert_eq!(GEV::from_le_bytes(&bytes), Ok(gev));
+        assert_eq!(GEV::from_le_bytes(&bytes[..24]), Err(DecodeError::WrongLength { expected: 25, found: 24 }));
+        assert_eq!(GEV::from_le_bytes(&[]), Err(DecodeError::WrongLength { expected: 25, found: 0 }));
+        // a Weibull has the same layout but another tag
+        let mut other: Vec<u8> = bytes.clone();
+        other[0] = 2;
+        assert_eq!(GEV::from_le_bytes(&other), Err(DecodeError::WrongTag { expected: GEV::TAG, found: 2 }));
+    }
+
+    #[test]
+    fn gev_from_quantiles_test() {
+        let points: [(f64, f64); 3] = [(0.5, 10.0), (0.9, 20.0), (0.99, 50.0)];
+        let gev: GEV = GEV::from_quantiles(&points).unwrap();
+        for (p, q) in points {
+            assert!((gev.quantile(p) - q).abs() < 1e-8);
+        }
+        // the quantiles of a GEV recover its parameters
+        let orig: GEV = new_gev!(2.0, 2.0, -0.2);
+        let points: [(f64, f64); 3] = [(0.99, orig.quantile(0.99)), (0.1, orig.quantile(0.1)), (0.5, orig.quantile(0.5))];
+        let gev: GEV = GEV::from_quantiles(&points).unwrap();
+        assert!((gev.loc() - 2.0).abs() < 1e-8);
+        assert!((gev.scale() - 2.0).abs() < 1e-8);
+        assert!((gev.shape() + 0.2).abs() < 1e-8);
+        assert_eq!(GEV::from_quantiles(&[(0.5, 10.0), (0.5, 20.0), (0.9, 30.0)]).err(), Some(FitError::InvalidInput));
+    }
+
+    #[test]
+    fn gev_from_return_levels_test() {
+        let orig: GEV = new_gev!(10.0, 2.0, 0.1);
+        let design = |periods: &[f64]| -> Vec<(f64, f64)> { periods.iter().map(|&t| (t, orig.return_level(t))).collect() };
+        // exactly determined
+        let gev: GEV = GEV::from_return_levels(&design(&[1000.0, 10.0, 100.0])).unwrap();
+        assert!((gev.loc() - 10.0).abs() < 1e-8);
+        assert!((gev.scale() - 2.0).abs() < 1e-8);
+        assert!((gev.shape() - 0.1).abs() < 1e-8);
+        // overdetermined, consistent levels: the least squares solution is exact
+        let gev: GEV = GEV::from_return_levels(&design(&[2.0, 5.0, 10.0, 50.0, 100.0])).unwrap();
+        assert!((gev.loc() - 10.0).abs() < 1e-4);
+        assert!((gev.scale() - 2.0).abs() < 1e-4);
+        assert!((gev.shape() - 0.1).abs() < 1e-4);
+        // inconsistent levels: closer overall than the exact fit of three of them
+        let mut noisy: Vec<(f64, f64)> = design(&[2.0, 5.0, 10.0, 50.0, 100.0]);
+        noisy[1].1 += 0.5;
+        noisy[3].1 -= 0.5;
+        let sse = |gev: &GEV| noisy.iter().map(|&(t, level)| (gev.return_level(t) - level).powi(2)).sum::<f64>();
+        let exact: GEV = GEV::from_return_levels(&[noisy[0], noisy[2], noisy[4]]).unwrap();
+        assert!(sse(&GEV::from_return_levels(&noisy).unwrap()) < 0.9 * sse(&exact));
+
+        assert_eq!(GEV::from_return_levels(&design(&[10.0, 100.0])).err(), Some(FitError::InsufficientData));
+        assert_eq!(GEV::from_return_levels(&[(0.5, 1.0), (10.0, 2.0), (100.0, 3.0)]).err(), Some(FitError::InvalidInput));
+        assert_eq!(GEV::from_return_levels(&[(2.0, 3.0), (10.0, 2.0), (100.0, 1.0)]).err(), Some(FitError::InvalidInput));
+    }
+
+    #[test]
+    fn gev_pdf_max_test() {
+        for shape in [-0.5, 0.0, 0.3] {
+            let gev: GEV = new_gev!(2.0, 2.0, shape);
+            let (lo, hi) = (gev.quantile(1e-6), gev.quantile(1.0 - 1e-6));
+            let grid_max: f64 = (0..20001).map(|i| gev.pdf(lo + (hi - lo) * i as f64 / 20000.0)).fold(0.0, f64::max);
+            assert!((gev.pdf_max() - grid_max).abs() < 1e-6);
+            assert!(gev.pdf_max() >= grid_max);
+        }
+        assert_eq!(new_gev!(2.0, 2.0, -1.0).pdf_max(), 0.5);
+        assert_eq!(new_gev!(2.0, 2.0, -1.5).pdf_max(), f64::INFINITY);
+    }
+
+    // simulate n values from the distribution, one seed per draw
+    fn simulate(gev: &GEV, n: u64) -> Vec<f64> {
+        (0..n).map(|i| gev.random(RandomSeed::Seed(i))).collect()
+    }
+
+    #[test]
+    fn gev_random_fast_test() {
+        for &shape in [-0.3, 0.0, 0.3].iter() {
+            let gev: GEV = new_gev!(1.0, 2.0, shape);
+            let fast: Vec<f64> = (0..5000).map(|i| gev.random_fast(RandomSeed::Seed(i))).collect();
+            // the same draws as the inverse transform, up to rounding
+            for (i, x) in fast.iter().enumerate().take(100) {
+                assert!((x - gev.random(RandomSeed::Seed(i as u64))).abs() < 1e-10);
+            }
+            // 1% critical value of the Cramér-von Mises statistic
+            assert!(cramer_von_mises(&gev, &fast) < 0.743);
+        }
+        assert_eq!(new_gev!(1.0, 2.0, 0.3).random_fast(RandomSeed::Seed(3)), new_gev!(1.0, 2.0, 0.3).random_fast(RandomSeed::Seed(3)));
+    }
+
+    #[test]
+    fn gev_broadcast_test() {
+        let params: Vec<(f64, f64, f64)> = vec![(0.0, 1.0, 0.0), (1.0, 2.0, 0.3), (-1.0, 0.5, -0.1), (5.0, 1.0, 0.5), (0.0, 1.0, -1.0)];
+        let x: f64 = 1.5;
+        let cdfs: Vec<f64> = cdf_broadcast(&params, x);
+        let pdfs: Vec<f64> = pdf_broadcast(&params, x);
+        assert_eq!((cdfs.len(), pdfs.len()), (params.len(), params.len()));
+        for (i, &(loc, scale, shape)) in params.iter().enumerate().take(3) {
+            let gev: GEV = new_gev!(loc, scale, shape);
+            assert_eq!(cdfs[i], gev.cdf(x));
+            assert_eq!(pdfs[i], gev.pdf(x));
+        }
+        // below the lower end point (3.0) and above the upper end point (1.0)
+        assert_eq!((cdfs[3], pdfs[3]), (0.0, 0.0));
+        assert_eq!((cdfs[4], pdfs[4]), (1.0, 0.0));
+        assert!(cdf_broadcast(&[], x).is_empty());
+    }
+
+    #[test]
+    fn gev_fit_penalized_test() {
+        let data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, 0.2), 30);
+        let mle: GEV = GEV::fit_mle(&data).unwrap();
+        assert_eq!(GEV::fit_penalized(&data, -0.3, 0.0).unwrap(), mle);
+        let shrunk: GEV = GEV::fit_penalized(&data, -0.3, 1e6).unwrap();
+        assert!((shrunk.shape - (-0.3)).abs() < 1e-3);
+        // a moderate penalty lands in between
+        let between: GEV = GEV::fit_penalized(&data, -0.3, 10.0).unwrap();
+        assert!(between.shape > shrunk.shape && between.shape < mle.shape);
+        assert_eq!(GEV::fit_penalized(&data, 0.0, -1.0), Err(FitError::InvalidInput));
+        assert_eq!(GEV::fit_penalized(&data, f64::NAN, 1.0), Err(FitError::InvalidInput));
+    }
+
+    #[test]
+    fn gev_dominates_test() {
+        // a shift of the location moves the whole distribution up
+        let low: GEV = new_gev!(10.0, 2.0, 0.1);
+        let high: GEV = new_gev!(12.0, 2.0, 0.1);
+        assert_eq!(high.dominates(&low, 0.0, 60.0, 500), Some(true));
+        assert_eq!(low.dominates(&high, 0.0, 60.0, 500), Some(false));
+        assert_eq!(low.dominates(&low, 0.0, 60.0, 500), Some(true));
+        // a larger scale puts more mass in both tails: the CDFs cross
+        let wide: GEV = new_gev!(10.0, 4.0, 0.1);
+        assert_eq!(wide.dominates(&low, 0.0, 60.0, 500), None);
+        // but the crossing is missed by a grid restricted to the upper tail
+        assert_eq!(wide.dominates(&low, 15.0, 60.0, 500), Some(true));
+    }
+
+    #[test]
+    fn param_grid_test() {
+        // scales -1, 0, 1, 2, 3: the first two are skipped
+        let grid: ParamGrid = ParamGrid::new((0.0, 10.0, 3), (-1.0, 3.0, 5), (-0.2, 0.2, 4));
+        assert_eq!(grid.n_combinations(), 60);
+        let gevs: Vec<GEV> = grid.iter().collect();
+        assert_eq!(gevs.len(), 3 * 3 * 4);
+        assert!(gevs.iter().all(|gev| gev.scale > 0.0));
+        assert_eq!(gevs[0], new_gev!(0.0, 1.0, -0.2));
+        assert_eq!(gevs[1], new_gev!(0.0, 1.0, -0.2 + 0.4 / 3.0));
+        assert_eq!((gevs[35].loc, gevs[35].scale), (10.0, 3.0));
+        assert!((gevs[35].shape - 0.2).abs() < 1e-15);
+        let mut count: usize = 0;
+        for gev in &grid {
+            assert!(gev.return_level(100.0).is_finite());
+            count += 1;
+        }
+        assert_eq!(count, 36);
+        // a single value per axis, an empty axis and a non-finite location range
+        assert_eq!(ParamGrid::new((1.0, 5.0, 1), (2.0, 9.0, 1), (0.1, 0.0, 1)).iter().collect::<Vec<GEV>>(), vec![new_gev!(1.0, 2.0, 0.1)]);
+        assert_eq!(ParamGrid::new((1.0, 5.0, 2), (2.0, 9.0, 0), (0.1, 0.0, 1)).iter().count(), 0);
+        assert_eq!(ParamGrid::new((f64::NAN, 5.0, 2), (2.0, 9.0, 2), (0.1, 0.0, 1)).iter().count(), 0);
+    }
+
+    #[test]
+    fn gev_pdf_scaled_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.2);
+        let mut xs: Vec<f64> = (0..50).map(|i| 5.0 + 0.5 * i as f64).collect();
+        xs.push(gev.mode());
+        let scaled: Vec<f64> = gev.pdf_scaled(&xs, 120.0);
+        assert!((scaled.iter().cloned().fold(0.0, f64::max) - 120.0).abs() < 1e-12);
+        // the shape of the density is kept
+        let pdf: Vec<f64> = gev.pdf_over(&xs);
+        assert!((scaled[3] / scaled[10] - pdf[3] / pdf[10]).abs() < 1e-12);
+        // an unbounded density is scaled by its largest value over the points
+        let spiky: GEV = new_gev!(0.0, 1.0, -2.0);
+        let scaled: Vec<f64> = spiky.pdf_scaled(&[-1.0, 0.0, 0.4, 0.49], 1.0);
+        assert_eq!(scaled[3], 1.0);
+    }
+
+    #[test]
+    fn gev_fit_mle_report_test() {
+        let data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, 0.1), 500);
+        let report: FitReport<GEV> = GEV::fit_mle_report(&data).unwrap();
+        assert_eq!(report.dist, GEV::fit_mle(&data).unwrap());
+        assert_eq!(report.log_likelihood, report.dist.log_likelihood(&data));
+        // a well-conditioned fit
+        assert!(report.hessian_condition_number > 1.0 && report.hessian_condition_number < 100.0);
+        let info: &Vec<Vec<f64>> = &report.observed_information;
+        assert!((0..3).all(|i| info[i][i] > 0.0 && (0..3).all(|j| info[i][j] == info[j][i])));
+        // a shape below -1, where the likelihood is unbounded at the sample maximum
+        let data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, -1.2), 500);
+        let report: FitReport<GEV> = GEV::fit_mle_report(&data).unwrap();
+        assert!(report.hessian_condition_number > 1e8);
+    }
+
+    #[test]
+    fn gev_summary_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.2);
+        let summary: DistSummary = gev.summary();
+        assert_eq!(summary.mean, gev.mean());
+        assert_eq!(summary.variance, gev.variance());
+        assert_eq!(summary.skewness, gev.skewness());
+        assert_eq!(summary.median, gev.median());
+        assert_eq!(summary.mode, gev.mode());
+        assert_eq!(summary.support, gev.support());
+        assert_eq!(summary.entropy, gev.entropy());
+        // checked against numerical integration
+        let config: Integration = Integration::default();
+        let mean: f64 = gev.expect(|x| x, config);
+        assert!((summary.mean.unwrap() - mean).abs() < 1e-6);
+        assert!((summary.variance.unwrap() - gev.expect(|x| (x - mean) * (x - mean), config)).abs() < 1e-3);
+        assert!((summary.entropy - gev.expect(|x| - log(gev.pdf(x)), config)).abs() < 1e-6);
+        assert!((gev.cdf(summary.median) - 0.5).abs() < 1e-14);
+        // the Gumbel limit
+        let gumb: Gumbel = Gumbel::new(10.0, 2.0);
+        let gev0: GEV = new_gev!(10.0, 2.0, 0.0);
+        assert_eq!(gev0.summary().mean, Some(gumb.mean()));
+        assert!((gev0.summary().skewness.unwrap() - new_gev!(10.0, 2.0, 1e-3).skewness().unwrap()).abs() < 1e-2);
+        assert!((gev0.entropy() - gumb.entropy()).abs() < 1e-15);
+
+        // moments that do not exist
+        let heavy: DistSummary = new_gev!(10.0, 2.0, 0.4).summary();
+        assert!(heavy.mean.is_some() && heavy.variance.is_some());
+        assert_eq!(heavy.skewness, None);
+        let heavier: DistSummary = new_gev!(10.0, 2.0, 1.5).summary();
+        assert_eq!((heavier.mean, heavier.variance, heavier.skewness), (None, None, None));
+    }
+
+    #[test]
+    fn gev_moment_exists_test() {
+        let gev: GEV = new_gev!(1.0, 2.0, 0.5);
+        assert!(gev.moment_exists(0));
+        assert!(gev.moment_exists(1));
+        // boundary: shape = 1/2 has no variance
+        assert!(!gev.moment_exists(2));
+        assert!(!new_gev!(1.0, 2.0, 1.0).moment_exists(1));
+        assert!(new_gev!(1.0, 2.0, 0.3).moment_exists(3));
+        assert!(!new_gev!(1.0, 2.0, 0.3).moment_exists(4));
+        // no restriction for the Gumbel and reversed Weibull types
+        assert!((0..10).all(|order| new_gev!(1.0, 2.0, 0.0).moment_exists(order)));
+        assert!((0..10).all(|order| new_gev!(1.0, 2.0, -0.4).moment_exists(order)));
+    }
+
+    #[test]
+    fn gev_fit_mle_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 2000);
+        let fitted: GEV = GEV::fit_mle(&data).unwrap();
+        assert!((fitted.loc() - 10.0).abs() < 0.2);
+        assert!((fitted.scale() - 2.0).abs() < 0.2);
+        assert!((fitted.shape() - 0.1).abs() < 0.05);
+        assert!(fitted.log_likelihood(&data) >= gev.log_likelihood(&data));
+    }
+
+    #[test]
+    fn gev_conditional_return_level_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.2);
+        let u: f64 = gev.quantile(0.5);
+        let level: f64 = gev.conditional_return_level(u, 10.0);
+        assert!((gev.conditional_cdf(level, u) - 0.9).abs() < 1e-12);
+        // a very low threshold gives (nearly) the unconditional return level
+        assert!((gev.conditional_return_level(gev.quantile(1e-12), 100.0) - gev.return_level(100.0)).abs() < 1e-9);
+
+        // exceedances of a high threshold are approximately GPD with the same shape and
+        // scale + shape (u - loc)
+        let u: f64 = gev.quantile(0.999);
+        let gpd: GeneralizedPareto = GeneralizedPareto::new(u, 2.0 + 0.2 * (u - 10.0), 0.2);
+        for period in [10.0, 100.0] {
+            let approx: f64 = gpd.isf(1.0 / period);
+            assert!((gev.conditional_return_level(u, period) - approx).abs() < 1e-3 * approx);
+        }
+    }
+
+    #[test]
+    fn gev_prediction_interval_test() {
+        let data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, 0.1), 40);
+        let fitted: GEV = GEV::fit_mle(&data).unwrap();
+        let (lower, upper) = fitted.prediction_interval(&data, 0.9, 200, RandomSeed::Seed(1));
+        assert_eq!((lower, upper), fitted.prediction_interval(&data, 0.9, 200, RandomSeed::Seed(1)));
+        // wider than the plug-in interval, which ignores the estimation error
+        let (plug_lower, plug_upper) = (fitted.quantile(0.05), fitted.quantile(0.95));
+        assert!(lower < upper);
+        assert!(upper - lower > plug_upper - plug_lower);
+        assert!(upper > plug_upper);
+        // no refit at all: the plug-in interval
+        assert_eq!(fitted.prediction_interval(&data, 0.9, 0, RandomSeed::Seed(1)), (plug_lower, plug_upper));
+    }
+
+    #[test]
+    fn gev_lr_test_gumbel_test() {
+        let gumbel_data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, 0.0), 500);
+        let (stat, p_value) = GEV::lr_test_gumbel(&gumbel_data).unwrap();
+        assert!(stat >= 0.0);
+        assert!(p_value > 0.05);
+        let frechet_data: Vec<f64> = simulate(&new_gev!(10.0, 2.0, 0.4), 500);
+        let (stat, p_value) = GEV::lr_test_gumbel(&frechet_data).unwrap();
+        assert!(stat > 20.0);
+        assert!(p_value < 1e-4);
+        // the chi-squared(1) upper tail: P(X > 3.841459) = 0.05
+        assert!((erfc(sqrt(3.841458820694124 / 2.0)) - 0.05).abs() < 1e-12);
+        assert_eq!(GEV::lr_test_gumbel(&[1.0]), Err(FitError::InsufficientData));
+    }
+
+    #[test]
+    fn gev_observed_return_periods_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 200);
+        let periods: Vec<f64> = gev.observed_return_periods(&data);
+        let argmax = |v: &[f64]| (0..v.len()).max_by(|&a, &b| v[a].total_cmp(&v[b])).unwrap();
+        assert_eq!(argmax(&periods), argmax(&data));
+        assert!((gev.observed_return_periods(&[gev.quantile(0.5)])[0] - 2.0).abs() < 1e-12);
+        // consistent with the return levels
+        assert!((gev.observed_return_periods(&[gev.return_level(100.0)])[0] - 100.0).abs() < 1e-9);
+        // outside of the support
+        assert_eq!(gev.observed_return_periods(&[-100.0]), vec![1.0]);
+        assert_eq!(new_gev!(10.0, 2.0, -0.5).observed_return_periods(&[100.0]), vec![f64::INFINITY]);
+    }
+
+    #[test]
+    fn gev_sf_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        assert!((gev.sf(12.0) - (1.0 - gev.cdf(12.0))).abs() < 1e-15);
+        // far in the upper tail 1 - cdf is 0 while the survival function is still accurate
+        // t(x) = (1 + 0.1 (x - 10) / 2)^(-10) = 1e-20
+        let x: f64 = 10.0 + 20.0 * (100.0 - 1.0);
+        assert_eq!(1.0 - gev.cdf(x), 0.0);
+        assert!((gev.sf(x) * 1e20 - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gev_return_level_distribution_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let mut levels: Vec<f64> = gev.return_level_distribution(100, 50.0, 200, RandomSeed::Seed(4));
+        assert!(levels.len() > 190);
+        assert_eq!(levels, gev.return_level_distribution(100, 50.0, 200, RandomSeed::Seed(4)));
+        levels.sort_by(|a, b| a.total_cmp(b));
+        let median: f64 = levels[levels.len() / 2];
+        let plug_in: f64 = gev.return_level(50.0);
+        assert!((median - plug_in).abs() < 0.05 * plug_in);
+        // the sampling distribution has a real spread
+        assert!(levels[levels.len() * 9 / 10] - levels[levels.len() / 10] > 1.0);
+    }
+
+    #[test]
+    fn gev_fit_mle_from_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 500);
+        // all the observations lie outside of the support of this start
+        let bad: GEV = new_gev!(1000.0, 0.01, 0.5);
+        assert_eq!(GEV::fit_mle_from(&data, bad).err(), Some(FitError::NoConvergence));
+        let fitted: GEV = GEV::fit_mle_from(&data, GEV::fit_pwm(&data).unwrap()).unwrap();
+        let mle: GEV = GEV::fit_mle(&data).unwrap();
+        assert!((fitted.log_likelihood(&data) - mle.log_likelihood(&data)).abs() < 1e-6);
+        assert!((fitted.shape() - mle.shape()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn gev_fit_mle_insufficient_data_test() {
+        assert_eq!(GEV::fit_mle(&[1.0, 2.0]).err(), Some(FitError::InsufficientData));
+    }
+
+    #[test]
+    fn gev_fit_degenerate_test() {
+        let constant: Vec<f64> = vec![5.0; 100];
+        let two_values: Vec<f64> = (0..100).map(|i| (i % 2) as f64).collect();
+        let single_point: Vec<f64> = vec![5.0; 3];
+        for data in [&constant, &two_values, &single_point] {
+            for method in [FitMethod::Mle, FitMethod::Pwm] {
+                assert_eq!(GEV::fit(data, method), Err(FitError::Degenerate));
+            }
+            assert_eq!(GEV::fit_mps(data), Err(FitError::Degenerate));
+            assert_eq!(GEV::fit_mle_weighted(data, &vec![1.0; data.len()]), Err(FitError::Degenerate));
+        }
+        // the other families are started from the PWM fit
+        assert_eq!(crate::dist::frechet::Frechet::fit_mle(&constant).err(), Some(FitError::Degenerate));
+        assert_eq!(Gumbel::fit_mle(&constant).err(), Some(FitError::Degenerate));
+    }
+
+    #[test]
+    fn gev_fittable_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 500);
+        assert_eq!(GEV::fit(&data, FitMethod::Mle), GEV::fit_mle(&data));
+        assert_eq!(GEV::fit(&data, FitMethod::Pwm), GEV::fit_pwm(&data));
+        assert_eq!(GEV::fit(&data, FitMethod::MomentMatching), Err(FitError::UnsupportedMethod));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn fit_columns_test() {
+        let columns: Vec<Vec<f64>> = (0..8)
+            .map(|i| new_gev!(i as f64, 1.0 + 0.1 * i as f64, 0.1).sample_n(200, RandomSeed::Seed(i)))
+            .collect();
+        for method in [FitMethod::Mle, FitMethod::Pwm] {
+            let serial: Vec<Result<GEV, FitError>> = columns.iter().map(|column| GEV::fit(column, method)).collect();
+            assert_eq!(fit_columns(&columns, method), serial);
+        }
+    }
+
+    #[test]
+    fn gev_fit_mle_weighted_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 500);
+        let mle: GEV = GEV::fit_mle(&data).unwrap();
+        let uniform: GEV = GEV::fit_mle_weighted(&data, &vec![2.5; 500]).unwrap();
+        assert!((uniform.loc() - mle.loc()).abs() < 1e-4);
+        assert!((uniform.scale() - mle.scale()).abs() < 1e-4);
+        assert!((uniform.shape() - mle.shape()).abs() < 1e-4);
+
+        // upweighting a sample from a shifted distribution pulls the location towards it
+        let mut mixed: Vec<f64> = data.clone();
+        mixed.extend(simulate(&new_gev!(20.0, 2.0, 0.1), 100));
+        let mut weights: Vec<f64> = vec![1.0; 600];
+        let unweighted: GEV = GEV::fit_mle_weighted(&mixed, &weights).unwrap();
+        weights[500..].iter_mut().for_each(|w| *w = 5.0);
+        let upweighted: GEV = GEV::fit_mle_weighted(&mixed, &weights).unwrap();
+        assert!(upweighted.loc() > unweighted.loc() + 1.0);
+
+        assert_eq!(GEV::fit_mle_weighted(&data, &[1.0; 3]).err(), Some(FitError::InvalidInput));
+        let mut negative: Vec<f64> = vec![1.0; 500];
+        negative[0] = -1.0;
+        assert_eq!(GEV::fit_mle_weighted(&data, &negative).err(), Some(FitError::InvalidInput));
+        assert_eq!(GEV::fit_mle_weighted(&data[..3], &[1.0, 0.0, 1.0]).err(), Some(FitError::InsufficientData));
+    }
+
+    #[test]
+    fn gev_fit_mps_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 1000);
+        let fitted: GEV = GEV::fit_mps(&data).unwrap();
+        assert!((fitted.loc() - 10.0).abs() < 0.2);
+        assert!((fitted.scale() - 2.0).abs() < 0.2);
+        assert!((fitted.shape() - 0.1).abs() < 0.05);
+    }
+
+    #[test]
+    fn gev_fit_mps_boundary_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, -1.2);
+        let data: Vec<f64> = simulate(&gev, 300);
+        let mps: GEV = GEV::fit_mps(&data).unwrap();
+        assert!((mps.shape() + 1.2).abs() < 0.1);
+        assert!((mps.scale() - 2.0).abs() < 0.2);
+        // the likelihood is unbounded at the end point for shape < -1, the MLE drifts away
+        let mle: GEV = GEV::fit_mle(&data).unwrap();
+        assert!((mps.shape() + 1.2).abs() < (mle.shape() + 1.2).abs());
+        assert!((mps.scale() - 2.0).abs() < (mle.scale() - 2.0).abs());
+    }
+
+    #[test]
+    fn gev_fit_pwm_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 2000);
+        let fitted: GEV = GEV::fit_pwm(&data).unwrap();
+        assert!((fitted.loc() - 10.0).abs() < 0.2);
+        assert!((fitted.scale() - 2.0).abs() < 0.2);
+        assert!((fitted.shape() - 0.1).abs() < 0.05);
+    }
+
+    #[test]
+    fn gev_return_level_test() {
+        let gev: GEV = new_gev!(2.0, 2.0, 0.0);
+        assert_eq!(gev.return_level(100.0), gev.quantile(0.99));
+    }
+
+    #[test]
+    fn gev_return_level_log10_test() {
+        let gev: GEV = new_gev!(2.0, 2.0, 0.1);
+        assert!((gev.return_level_log10(2.0) - gev.return_level(100.0)).abs() < 1e-12);
+        let level: f64 = gev.return_level_log10(3.0);
+        assert!((gev.return_period_log10(level) - 3.0).abs() < 1e-9);
+        // a return period of 1e20, where the CDF rounds to 1
+        let level: f64 = gev.isf(1e-20);
+        assert_eq!(gev.cdf(level), 1.0);
+        let log10_period: f64 = gev.return_period_log10(level);
+        assert!((log10_period - 20.0).abs() < 1e-9);
+        assert!((gev.isf(pow(10.0, - log10_period)) / level - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gev_return_level_bias_corrected_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 25);
+        let fitted: GEV = GEV::fit_mle(&data).unwrap();
+        let plug_in: f64 = fitted.return_level_bias_corrected(&data, 100.0, BiasCorrection::None);
+        assert_eq!(plug_in, fitted.return_level(100.0));
+        let corrected: f64 = fitted.return_level_bias_corrected(&data, 100.0, BiasCorrection::Bootstrap { reps: 200, seed: 3 });
+        // the bootstrap return levels are right-skewed, so the correction lowers the estimate
+        assert!(corrected < plug_in);
+    }
+
+    #[test]
+    fn ensemble_return_level_test() {
+        let ensemble: [GEV; 3] = [new_gev!(10.0, 2.0, 0.1), new_gev!(12.0, 1.5, -0.1), new_gev!(9.0, 3.0, 0.2)];
+        let levels: Vec<f64> = ensemble.iter().map(|gev| gev.return_level(100.0)).collect();
+        let average: f64 = ensemble_return_level(&ensemble, 100.0, PoolMethod::QuantileAverage);
+        assert!((average - levels.iter().sum::<f64>() / 3.0).abs() < 1e-12);
+
+        let mixture: f64 = ensemble_return_level(&ensemble, 100.0, PoolMethod::MixtureCdf);
+        let mixture_cdf: f64 = ensemble.iter().map(|gev| cdf_extended(gev, mixture)).sum::<f64>() / 3.0;
+        assert!((mixture_cdf - 0.99).abs() < 1e-10);
+        // the mixture is driven by the heaviest tail, so it is above the average here
+        assert!(mixture > average);
+        assert!(mixture < levels.iter().cloned().fold(f64::NEG_INFINITY, f64::max));
+
+        // both methods agree for identical members
+        let same: [GEV; 2] = [new_gev!(10.0, 2.0, 0.1); 2];
+        let diff: f64 = ensemble_return_level(&same, 50.0, PoolMethod::QuantileAverage) - ensemble_return_level(&same, 50.0, PoolMethod::MixtureCdf);
+        assert!(diff.abs() < 1e-9);
+    }
+
+    #[test]
+    fn gev_loglik_surface_test() {
+        let gev: GEV = new_gev!(10.0, 2.0, 0.1);
+        let data: Vec<f64> = simulate(&gev, 500);
+        let mle: GEV = GEV::fit_mle(&data).unwrap();
+
+        let loc_grid: Vec<f64> = (0..41).map(|i| mle.loc() - 1.0 + 0.05 * i as f64).collect();
+        let scale_grid: Vec<f64> = (0..41).map(|j| mle.scale() - 1.0 + 0.05 * j as f64).collect();
+        let surface: Vec<Vec<f64>> = GEV::loglik_surface(&data, &loc_grid, &scale_grid, mle.shape());
+        assert_eq!(surface.len(), loc_grid.len());
+        assert_eq!(surface[0].len(), scale_grid.len());
+
+        let mut best: (usize, usize) = (0, 0);
+        for (i, row) in surface.iter().enumerate() {
+            for (j, val) in row.iter().enumerate() {
+                if *val > surface[best.0][best.1] {
+                    best = (i, j);
+                }
+            }
+        }
+        assert!((loc_grid[best.0] - mle.loc()).abs() <= 0.05);
+        assert!((scale_grid[best.1] - mle.scale()).abs() <= 0.05);
+    }
+
 }
@@ -0,0 +1,106 @@
+//! Goodness-of-fit statistics, to check how well a (fitted) distribution describes a sample.
+use crate::dist::distutils::DistQuant;
+
+/// CDF of the distribution extended to the whole real line (0 below and 1 above the support),
+/// so the statistics can be evaluated at arbitrary points.
+pub(crate) fn cdf_extended<D: DistQuant + ?Sized>(dist: &D, x: f64) -> f64 {
+    let (lower, upper) = dist.support();
+    if x <= lower {
+        0.0
+    } else if x >= upper {
+        1.0
+    } else {
+        dist.cdf(x)
+    }
+}
+
+/// Pearson's $\chi^2$ goodness-of-fit statistic for binned data,
+/// $\chi^2 = \sum_i \frac{(O_i - E_i)^2}{E_i}$, where $O_i$ are the `observed_counts` of the bins
+/// $[e_i, e_{i+1})$ given by the increasing `bin_edges` (the edges may be infinite) and $E_i$ the
+/// expected counts under `dist`, from the differences of its CDF at the edges.
+/// The expected bin probabilities are normalized over the range of the edges, so the bins do not
+/// need to cover the whole support.
+/// Returns the statistic and the number of degrees of freedom, the number of bins minus one;
+/// subtract the number of parameters if `dist` was fitted to the same data.
+///
+/// The $\chi^2$ approximation of the statistic is only reliable when the expected counts are not
+/// too small (a common rule is $E_i \geq 5$ in every bin): merge the sparse (tail) bins otherwise.
+pub fn chi_squared_gof<D: DistQuant + ?Sized>(dist: &D, bin_edges: &[f64], observed_counts: &[usize]) -> (f64, usize) {
+    domain!(bin_edges.len() >= 2 && observed_counts.len() == bin_edges.len() - 1);
+    domain!(bin_edges.windows(2).all(|w| w[0] < w[1]));
+    let total: f64 = observed_counts.iter().sum::<usize>() as f64;
+    let probs: Vec<f64> = bin_edges
+        .windows(2)
+        .map(|w| cdf_extended(dist, w[1]) - cdf_extended(dist, w[0]))
+        .collect();
+    let covered: f64 = probs.iter().sum::<f64>();
+
+    let mut stat: f64 = 0.0;
+    for (prob, &count) in probs.iter().zip(observed_counts) {
+        let expected: f64 = total * prob / covered;
+        let diff: f64 = count as f64 - expected;
+        stat += diff * diff / expected;
+    }
+    (stat, observed_counts.len() - 1)
+}
+
+/// Cramér-von Mises statistic of the data under `dist`,
+/// $$ W^2 = \frac{1}{12 n} + \sum_{i=1}^{n} \left( F(x_{(i)}) - \frac{2 i - 1}{2 n} \right)^2 $$
+/// on the sorted sample $x_{(1)} \leq \cdots \leq x_{(n)}$. It measures the squared distance between
+/// the empirical and fitted CDFs over the whole range, while the Anderson-Darling statistic puts
+/// more weight on the tails. Small values indicate a good fit.
+pub fn cramer_von_mises<D: DistQuant + ?Sized>(dist: &D, data: &[f64]) -> f64 {
+    domain!(!data.is_empty());
+    let mut sorted: Vec<f64> = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let n: f64 = sorted.len() as f64;
+    let mut stat: f64 = 1.0 / (12.0 * n);
+    for (i, &x) in sorted.iter().enumerate() {
+        let diff: f64 = cdf_extended(dist, x) - (2.0 * i as f64 + 1.0) / (2.0 * n);
+        stat += diff * diff;
+    }
+    stat
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::distutils::RandomSeed;
+    use crate::dist::gumbel::Gumbel;
+
+    // counts of the sample in the bins given by the edges
+    fn bin_counts(data: &[f64], edges: &[f64]) -> Vec<usize> {
+        edges.windows(2).map(|w| data.iter().filter(|&&x| w[0] <= x && x < w[1]).count()).collect()
+    }
+
+    #[test]
+    fn cramer_von_mises_test() {
+        let gumbel: Gumbel = Gumbel::new(0.0, 1.0);
+        // a single observation at the median only leaves the offset
+        assert!((cramer_von_mises(&gumbel, &[gumbel.quantile(0.5)]) - 1.0 / 12.0).abs() < 1e-15);
+
+        let data: Vec<f64> = gumbel.sample_n(500, RandomSeed::Seed(3));
+        let good: f64 = cramer_von_mises(&gumbel, &data);
+        let bad: f64 = cramer_von_mises(&Gumbel::new(0.5, 1.5), &data);
+        // below the 99% critical value (0.743) for the good fit
+        assert!(good < 0.743);
+        assert!(bad > 10.0 * good);
+    }
+
+    #[test]
+    fn chi_squared_gof_test() {
+        let gumbel: Gumbel = Gumbel::new(0.0, 1.0);
+        let data: Vec<f64> = gumbel.sample_n(2000, RandomSeed::Seed(3));
+        let edges: Vec<f64> = vec![f64::NEG_INFINITY, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0, 3.0, f64::INFINITY];
+        let counts: Vec<usize> = bin_counts(&data, &edges);
+
+        let (matched, dof) = chi_squared_gof(&gumbel, &edges, &counts);
+        assert_eq!(dof, 8);
+        // well below the 99.9% quantile of the chi-squared distribution with 8 dof (26.1)
+        assert!(matched < 26.1);
+        let (mismatched, _) = chi_squared_gof(&Gumbel::new(0.5, 1.0), &edges, &counts);
+        assert!(mismatched > 100.0);
+        assert!(mismatched > 10.0 * matched);
+    }
+}
@@ -0,0 +1,487 @@
+//! The Generalized Pareto Distribution (GPD), the limit distribution of the exceedances over a
+//! high threshold (peaks-over-threshold approach).
+use std::fmt;
+
+use libm::{exp, expm1, log, log1p, pow};
+
+use crate::dist::distutils::*;
+use crate::dist::fit::{check_data, check_distinct, FitError};
+use crate::dist::lmoments::{sample_lmoments, LMoments};
+
+use rand_chacha::ChaCha8Rng;
+use rand::SeedableRng;
+use rand::Rng;
+
+/// Generalized Pareto Dist. struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneralizedPareto {
+    /// location parameter (the threshold)
+    pub loc:   f64,
+    /// scale parameter, must be positive
+    pub scale: f64,
+    /// shape parameter
+    pub shape: f64,
+}
+
+impl GeneralizedPareto {
+    /// Create an instance of the Generalized Pareto Distribution given location (loc), scale and shape parameter.
+    /// The scale parameter must be larger than 0.
+    #[inline]
+    pub fn new(loc: f64, scale: f64, shape: f64) -> Self {
+        domain!(scale > 0.0);
+        GeneralizedPareto{loc, scale, shape}
+    }
+
+    /// Bounds `(lo, hi)` of the parameters (loc, scale, shape), for bounded optimizers.
+    /// The strictly positive scale is bounded below by `f64::EPSILON`.
+    pub fn param_bounds() -> Vec<(f64, f64)> {
+        vec![(f64::NEG_INFINITY, f64::INFINITY), (f64::EPSILON, f64::INFINITY), (f64::NEG_INFINITY, f64::INFINITY)]
+    }
+
+    /// Obtain the shape parameter
+    #[inline(always)]
+    pub fn shape(&self) -> f64 {
+        self.shape
+    }
+
+    /// Mean of the GPD, $loc + \frac{scale}{1 - shape}$.
+    /// Only exists for $shape < 1$, otherwise `None` is returned.
+    pub fn mean(&self) -> Option<f64> {
+        if self.shape < 1.0 {
+            Some(self.loc + self.scale / (1.0 - self.shape))
+        } else {
+            None
+        }
+    }
+
+    /// Theoretical mean excess (mean residual life) $e(u) = E[X - u \mid X > u]$ over the level
+    /// $u \geq loc$ inside the support,
+    /// $$ e(u) = \frac{scale + shape (u - loc)}{1 - shape}, $$
+    /// since the excesses over $u$ are again GPD with the shape unchanged and the scale
+    /// $scale + shape (u - loc)$. It is infinite if $shape \geq 1$.
+    ///
+    /// This linearity in $u$ underlies the mean residual life plot used to choose a threshold: above
+    /// the level where the GPD approximation holds, the empirical mean excess of the data is
+    /// approximately linear in $u$ with the slope `mean_excess_slope`.
+    pub fn mean_excess(&self, u: f64) -> f64 {
+        domain!(u >= self.loc && self.scale + self.shape * (u - self.loc) >= 0.0);
+        if self.shape >= 1.0 {
+            return f64::INFINITY;
+        }
+        (self.scale + self.shape * (u - self.loc)) / (1.0 - self.shape)
+    }
+
+    /// Slope $\frac{shape}{1 - shape}$ of the mean excess function `mean_excess` in the level $u$:
+    /// positive for heavy tails, zero for the exponential distribution and negative for bounded tails.
+    /// Only meaningful for $shape < 1$ (infinite mean excess otherwise).
+    pub fn mean_excess_slope(&self) -> f64 {
+        self.shape / (1.0 - self.shape)
+    }
+
+    /// Variance of the GPD, $\frac{scale^2}{(1 - shape)^2 (1 - 2 shape)}$.
+    /// Only exists for $shape < 1/2$, otherwise `None` is returned.
+    pub fn variance(&self) -> Option<f64> {
+        if self.shape < 0.5 {
+            Some(self.scale * self.scale / (pow(1.0 - self.shape, 2.0) * (1.0 - 2.0 * self.shape)))
+        } else {
+            None
+        }
+    }
+
+    /// Median of the GPD, $loc + scale \frac{2^{shape} - 1}{shape}$ ($loc + scale \log 2$ if $shape = 0$).
+    pub fn median(&self) -> f64 {
+        self.quantile(0.5)
+    }
+
+    /// Skewness of the GPD, $\frac{2 (1 + shape) \sqrt{1 - 2 shape}}{1 - 3 shape}$ (2 for the
+    /// exponential distribution, $shape = 0$). Only exists for $shape < 1/3$, otherwise `None` is returned.
+    pub fn skewness(&self) -> Option<f64> {
+        if self.shape < 1.0 / 3.0 {
+            Some(2.0 * (1.0 + self.shape) * (1.0 - 2.0 * self.shape).sqrt() / (1.0 - 3.0 * self.shape))
+        } else {
+            None
+        }
+    }
+
+    /// Differential entropy of the GPD, $\log scale + shape + 1$.
+    pub fn entropy(&self) -> f64 {
+        log(self.scale) + self.shape + 1.0
+    }
+
+    /// Summary of the distribution, the moments that do not exist being `None`.
+    pub fn summary(&self) -> DistSummary {
+        DistSummary {
+            mean: self.mean(),
+            variance: self.variance(),
+            median: self.median(),
+            mode: self.mode(),
+            skewness: self.skewness(),
+            support: self.support(),
+            entropy: self.entropy(),
+        }
+    }
+
+    /// The first three L-moments of the GPD (Hosking),
+    /// $$ \lambda_1 = loc + \frac{scale}{1 - shape}, \quad \lambda_2 = \frac{scale}{(1 - shape)(2 - shape)},
+    /// \quad \tau_3 = \frac{\lambda_3}{\lambda_2} = \frac{1 + shape}{3 - shape}. $$
+    /// Only exist for $shape < 1$, otherwise `None` is returned.
+    pub fn lmoments(&self) -> Option<LMoments> {
+        if self.shape < 1.0 {
+            let l2: f64 = self.scale / ((1.0 - self.shape) * (2.0 - self.shape));
+            Some(LMoments {
+                l1: self.loc + self.scale / (1.0 - self.shape),
+                l2,
+                l3: l2 * (1.0 + self.shape) / (3.0 - self.shape),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Fit the GPD to the exceedances $y_i = x_i - u \geq 0$ of a threshold $u$ by probability
+    /// weighted moments (equivalently L-moments, Hosking & Wallis 1987). With the threshold known,
+    /// the first two L-moments $\lambda_1 = \frac{scale}{1 - shape}$ and
+    /// $\lambda_2 = \frac{scale}{(1 - shape)(2 - shape)}$ of the exceedances give
+    /// $$ shape = 2 - \frac{\lambda_1}{\lambda_2}, \quad scale = \lambda_1 \left( \frac{\lambda_1}{\lambda_2} - 1 \right), $$
+    /// with the sample L-moments (see `lmoments::sample_lmoments`) in place of $\lambda_1, \lambda_2$.
+    /// The fitted GPD has $loc = 0$, i.e. it is the distribution of the exceedances (shift it by
+    /// $u$ for the distribution of the observations). The estimator exists for $shape < 1$ and is
+    /// reliable for $shape < 1/2$.
+    /// Exceedances equal to 0 (observations exactly at the threshold) are valid observations of
+    /// the GPD and are kept; the sample L-moments handle them like any other value.
+    /// Returns `FitError::InvalidInput` if an exceedance is negative, and otherwise requires at
+    /// least 3 finite exceedances taking at least 2 distinct values.
+    pub fn fit_pwm(exceedances: &[f64]) -> Result<GeneralizedPareto, FitError> {
+        check_data(exceedances, 3)?;
+        if exceedances.iter().any(|y| *y < 0.0) {
+            return Err(FitError::InvalidInput);
+        }
+        check_distinct(exceedances, 2)?;
+        let lmom: LMoments = sample_lmoments(exceedances);
+        let ratio: f64 = lmom.l1 / lmom.l2;
+        let scale: f64 = lmom.l1 * (ratio - 1.0);
+        if scale.is_finite() && scale > 0.0 {
+            Ok(GeneralizedPareto { loc: 0.0, scale, shape: 2.0 - ratio })
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+    /// L-skewness diagnostic of the GPD as a model of the exceedances: the sample L-skewness of
+    /// `exceedances` minus the L-skewness $\frac{1 + shape}{3 - shape}$ of the distribution.
+    /// The PWM fit `GeneralizedPareto::fit_pwm` matches the first two L-moments exactly, so the
+    /// third one is a free check of the GPD assumption (e.g. of the choice of the threshold):
+    /// values far from 0 indicate that the exceedances are not GPD distributed. Needs at least
+    /// 3 exceedances and $shape < 1$.
+    pub fn t3_discrepancy(&self, exceedances: &[f64]) -> f64 {
+        domain!(self.shape < 1.0);
+        sample_lmoments(exceedances).t3() - (1.0 + self.shape) / (3.0 - self.shape)
+    }
+
+    /// Peaks-over-threshold return level, the level exceeded on average once every
+    /// `return_period` blocks (e.g. years) when the exceedances of `threshold` occur at the rate
+    /// `lambda`, the mean number of exceedances per block (e.g. per year, estimated by the number of
+    /// exceedances divided by the length of the record in blocks):
+    /// $$ x_T = u + \frac{scale}{shape} \left( (T \lambda)^{shape} - 1 \right), $$
+    /// or $u + scale \log(T \lambda)$ if $shape = 0$. The threshold $u$ is normally the location
+    /// parameter of the fitted GPD. $T \lambda$ must be larger than 1.
+    pub fn return_level(&self, return_period: f64, lambda: f64, threshold: f64) -> f64 {
+        let m: f64 = return_period * lambda;
+        domain!(m > 1.0);
+        if self.shape == 0.0 {
+            threshold + self.scale * log(m)
+        } else {
+            threshold + self.scale / self.shape * (pow(m, self.shape) - 1.0)
+        }
+    }
+
+    /// Mode of the GPD: the location parameter for $shape \geq -1$ (the density decreases from
+    /// $loc$, or is flat for $shape = -1$), and the upper end point $loc - scale / shape$ for $shape < -1$.
+    pub fn mode(&self) -> f64 {
+        if self.shape >= -1.0 {
+            self.loc
+        } else {
+            self.loc - self.scale / self.shape
+        }
+    }
+
+}
+
+impl fmt::Display for GeneralizedPareto {
+    /// Format as `GeneralizedPareto(loc=..., scale=..., shape=...)`, honoring the precision of the formatter
+    /// (e.g. `format!("{:.2}", dist)`), the parameters being printed in full by default.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_params(f, "GeneralizedPareto", &[("loc", self.loc), ("scale", self.scale), ("shape", self.shape)])
+    }
+}
+
+impl LocationScale for GeneralizedPareto {
+    #[inline(always)]
+    fn loc(&self) -> f64 {
+        self.loc
+    }
+
+    #[inline(always)]
+    fn scale(&self) -> f64 {
+        self.scale
+    }
+}
+
+impl DistQuant for GeneralizedPareto {
+    /// CDF: $F(x) = 1 - \left( 1 + shape \frac{x - loc}{scale} \right)^{- \frac{1}{shape}}$,
+    /// or $F(x) = 1 - \exp \left \{ - \frac{x - loc}{scale} \right \}$ if $shape = 0$,
+    /// for $x \geq loc$ (and $x \leq loc - scale / shape$ if $shape < 0$).
+    /// At the threshold $x = loc$ it is exactly 0 for every shape, as $1 + shape \cdot 0 = 1$ exactly.
+    fn cdf(&self, x: f64) -> f64 {
+        let y: f64 = self.reduced(x);
+        domain!(y >= 0.0 && 1.0 + self.shape * y >= 0.0);
+        if self.shape == 0.0 {
+            - expm1(- y)
+        } else {
+            1.0 - pow(1.0 + self.shape * y, - 1.0 / self.shape)
+        }
+    }
+
+    /// PDF of the GPD.
+    /// $$ f(x) = \frac{1}{scale} \left( 1 + shape \frac{x - loc}{scale} \right)^{- \frac{1}{shape} - 1} $$
+    /// or $f(x) = \frac{1}{scale} \exp \left \{ - \frac{x - loc}{scale} \right \}$ if $shape = 0$.
+    /// At the threshold $x = loc$ it is exactly $\frac{1}{scale}$ for every shape (including
+    /// $shape < -1$, where the density increases toward the upper end point).
+    fn pdf(&self, x: f64) -> f64 {
+        let y: f64 = self.reduced(x);
+        domain!(y >= 0.0 && 1.0 + self.shape * y >= 0.0);
+        let mult_const: f64 = 1.0 / self.scale;
+        if self.shape == 0.0 {
+            mult_const * exp(- y)
+        } else {
+            mult_const * pow(1.0 + self.shape * y, - 1.0 / self.shape - 1.0)
+        }
+    }
+
+    /// Quantile (inverse CDF) function.
+    /// $F^{-1}(x) = loc + scale \frac{(1 - x)^{- shape} - 1}{shape}$, or $loc - scale \log(1 - x)$ if
+    /// $shape = 0$, computed with `expm1`/`log1p` for accuracy in both tails.
+    fn quantile(&self, x: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&x));
+        if self.shape == 0.0 {
+            self.loc - self.scale * log1p(- x)
+        } else {
+            self.loc + self.scale * expm1(- self.shape * log1p(- x)) / self.shape
+        }
+    }
+
+    /// Inverse survival function $S^{-1}(p) = loc + scale \frac{p^{- shape} - 1}{shape}$, or
+    /// $loc - scale \log p$ if $shape = 0$.
+    fn isf(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        if self.shape == 0.0 {
+            self.loc - self.scale * log(p)
+        } else {
+            self.loc + self.scale * expm1(- self.shape * log(p)) / self.shape
+        }
+    }
+
+    /// Density quantile function $\frac{dQ}{dp} = scale (1 - p)^{- shape - 1}$, for every shape:
+    /// $scale$ at $p = 0$ (the threshold). At $p = 1$ it is infinite for $shape > -1$, $scale$ for
+    /// $shape = -1$ (the uniform distribution) and 0 for $shape < -1$ (where the density is unbounded).
+    fn quantile_density(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        if p == 1.0 {
+            return if self.shape > -1.0 { f64::INFINITY } else if self.shape == -1.0 { self.scale } else { 0.0 };
+        }
+        self.scale * exp(- (self.shape + 1.0) * log1p(- p))
+    }
+
+    /// Logarithm of the survival function, $- \frac{\mathrm{log1p}(shape \cdot y)}{shape}$ with
+    /// $y = \frac{x - loc}{scale}$ ($- y$ if $shape = 0$), without underflow in the upper tail.
+    fn log_sf(&self, x: f64) -> f64 {
+        let y: f64 = self.reduced(x);
+        domain!(y >= 0.0 && 1.0 + self.shape * y >= 0.0);
+        if self.shape == 0.0 { - y } else { - log1p(self.shape * y) / self.shape }
+    }
+
+    /// Support: $[loc, \infty)$ if $shape \geq 0$ and $[loc, loc - scale / shape]$ if $shape < 0$.
+    fn support(&self) -> (f64, f64) {
+        if self.shape < 0.0 {
+            (self.loc, self.loc - self.scale / self.shape)
+        } else {
+            (self.loc, f64::INFINITY)
+        }
+    }
+
+    /// Whether `x` lies in the support, which includes the threshold $loc$.
+    fn is_in_support(&self, x: f64) -> bool {
+        let (lower, upper) = self.support();
+        lower <= x && x <= upper
+    }
+
+    /// Return a randomly generated value from the GPD.
+    fn random(&self, seed: RandomSeed) -> f64 {
+
+        let mut rng = match seed {
+            RandomSeed::Empty => ChaCha8Rng::from_entropy(),
+            RandomSeed::Seed(val) => ChaCha8Rng::seed_from_u64(val), // ChaCha8Rng implements the SeedableRng trait
+        };
+        let rand_quant: f64 = rng.gen::<f64>(); // generate randomly from U(0,1)
+        self.quantile(rand_quant) // then plug that random uniform into the quantile.
+    }
+
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::gev::GEV;
+
+    // quick macro to create the instance of the Generalized Pareto Distribution
+    macro_rules! new_gpd(
+        ($loc:expr, $scale:expr, $shape:expr) => (GeneralizedPareto::new($loc, $scale, $shape));
+    );
+
+    #[test]
+    fn gpd_cdf_test() {
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.2);
+        assert!((gpd.cdf(3.0) - 0.5981224279835391).abs() < 1e-14);
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.0);
+        assert!((gpd.cdf(3.0) - 0.6321205588285577).abs() < 1e-14);
+    }
+
+    #[test]
+    fn gpd_pdf_test() {
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.2);
+        assert!((gpd.pdf(3.0) - 0.1674489883401921).abs() < 1e-14);
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.0);
+        assert!((gpd.pdf(3.0) - 0.18393972058572117).abs() < 1e-14);
+    }
+
+    #[test]
+    fn gpd_quantile_test() {
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.2);
+        assert!((gpd.quantile(0.7) - 3.722596365393921).abs() < 1e-13);
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.0);
+        assert!((gpd.quantile(0.7) - 3.4079456086518722).abs() < 1e-13);
+    }
+
+    #[test]
+    fn gpd_moments_test() {
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.2);
+        assert!((gpd.mean().unwrap() - 3.5).abs() < 1e-14);
+        assert!((gpd.variance().unwrap() - 4.0 / (0.64 * 0.6)).abs() < 1e-12);
+        // shape = 0 is the exponential distribution
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.0);
+        assert_eq!(gpd.mean(), Some(3.0));
+        assert_eq!(gpd.variance(), Some(4.0));
+        assert!((gpd.median() - (1.0 + 2.0 * log(2.0))).abs() < 1e-14);
+        // the variance requires shape < 1/2 and the mean shape < 1
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.5);
+        assert!(gpd.mean().is_some());
+        assert!(gpd.variance().is_none());
+        assert!(new_gpd!(1.0, 2.0, 1.0).mean().is_none());
+    }
+
+    #[test]
+    fn gpd_return_level_test() {
+        // 100-year level with 5 exceedances per year
+        let gpd: GeneralizedPareto = new_gpd!(10.0, 2.0, 0.1);
+        assert!((gpd.return_level(100.0, 5.0, 10.0) - 27.232911332721386).abs() < 1e-12);
+        let gpd0: GeneralizedPareto = new_gpd!(10.0, 2.0, 0.0);
+        assert!((gpd0.return_level(100.0, 5.0, 10.0) - (10.0 + 2.0 * log(500.0))).abs() < 1e-12);
+        // the level exceeded with probability 1 / (T lambda) by an exceedance
+        assert!((gpd.cdf(gpd.return_level(100.0, 5.0, 10.0)) - (1.0 - 1.0 / 500.0)).abs() < 1e-12);
+
+        // annual maxima of the Poisson(lambda) exceedances are GEV distributed, with the same
+        // shape, scale * lambda^shape and location u + scale (lambda^shape - 1) / shape
+        let gev: GEV = GEV::new(10.0 + 2.0 * (pow(5.0, 0.1) - 1.0) / 0.1, 2.0 * pow(5.0, 0.1), 0.1);
+        assert!((gev.return_level(100.0) - gpd.return_level(100.0, 5.0, 10.0)).abs() < 0.05);
+    }
+
+    #[test]
+    fn gpd_median_mode_test() {
+        let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, 0.2);
+        assert!((gpd.cdf(gpd.median()) - 0.5).abs() < 1e-14);
+        assert_eq!(gpd.mode(), 1.0);
+        assert_eq!(new_gpd!(1.0, 2.0, -2.0).mode(), 2.0);
+    }
+
+    #[test]
+    fn gpd_threshold_test() {
+        for &shape in [-2.0, -1.0, -0.5, 0.0, 0.2, 1.0, 3.0].iter() {
+            let gpd: GeneralizedPareto = new_gpd!(1.5, 2.0, shape);
+            assert!(gpd.is_in_support(1.5));
+            assert_eq!(gpd.cdf(1.5), 0.0);
+            assert_eq!(gpd.pdf(1.5), 0.5);
+            assert_eq!(gpd.evaluate(1.5), (0.5, 0.0));
+            assert_eq!(gpd.sf(1.5), 1.0);
+            assert_eq!(gpd.quantile(0.0), 1.5);
+        }
+        // exceedances exactly at the threshold are kept by the fit
+        let mut exceedances: Vec<f64> = new_gpd!(0.0, 2.0, 0.1).sample_n(1000, RandomSeed::Seed(3));
+        exceedances.extend_from_slice(&[0.0; 10]);
+        let fitted: GeneralizedPareto = GeneralizedPareto::fit_pwm(&exceedances).unwrap();
+        assert!(fitted.scale.is_finite() && fitted.shape.is_finite());
+        assert!(exceedances.iter().all(|&y| fitted.is_in_support(y) && fitted.pdf(y).is_finite()));
+        assert_eq!(GeneralizedPareto::fit_pwm(&[0.0, 0.0, 1.0, 2.0]).map(|gpd| gpd.loc), Ok(0.0));
+    }
+
+    #[test]
+    fn gpd_entropy_summary_test() {
+        for &shape in [-0.5, 0.0, 0.2].iter() {
+            let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, shape);
+            assert!((gpd.entropy() - (log(2.0) + shape + 1.0)).abs() < 1e-15);
+            let config: Integration = Integration::default();
+            assert!((gpd.entropy() - gpd.expect(|x| - log(gpd.pdf(x)), config)).abs() < 1e-6);
+            let summary: DistSummary = gpd.summary();
+            assert_eq!((summary.mean, summary.variance, summary.skewness), (gpd.mean(), gpd.variance(), gpd.skewness()));
+            assert_eq!((summary.median, summary.mode, summary.support), (gpd.median(), gpd.mode(), gpd.support()));
+        }
+        // the exponential distribution
+        assert_eq!(new_gpd!(0.0, 1.0, 0.0).entropy(), 1.0);
+        assert_eq!(new_gpd!(0.0, 1.0, 0.0).skewness(), Some(2.0));
+        assert!(new_gpd!(0.0, 1.0, 0.4).skewness().is_none());
+    }
+
+    #[test]
+    fn gpd_mean_excess_test() {
+        for &shape in [-0.4, 0.0, 0.25, 0.6].iter() {
+            let gpd: GeneralizedPareto = new_gpd!(1.0, 2.0, shape);
+            assert!((gpd.mean_excess(1.0) - (gpd.mean().unwrap() - 1.0)).abs() < 1e-14);
+            let levels: [f64; 4] = [1.0, 1.5, 2.5, 4.0];
+            for pair in levels.windows(2) {
+                let slope: f64 = (gpd.mean_excess(pair[1]) - gpd.mean_excess(pair[0])) / (pair[1] - pair[0]);
+                assert!((slope - gpd.mean_excess_slope()).abs() < 1e-13);
+            }
+            // E[X - u | X > u] = int_u^hi sf(x) dx / sf(u)
+            let u: f64 = 2.5;
+            let hi: f64 = gpd.quantile(1.0 - 1e-13);
+            let (integral, _) = adaptive_simpson(|x| gpd.sf(x), u, hi, Integration::default());
+            assert!((integral / gpd.sf(u) - gpd.mean_excess(u)).abs() < 1e-3 * gpd.mean_excess(u));
+        }
+        assert_eq!(new_gpd!(0.0, 2.0, 0.0).mean_excess_slope(), 0.0);
+        assert_eq!(new_gpd!(0.0, 2.0, 0.0).mean_excess(10.0), 2.0);
+        assert_eq!(new_gpd!(0.0, 2.0, 1.5).mean_excess(3.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn gpd_fit_pwm_test() {
+        let gpd: GeneralizedPareto = new_gpd!(0.0, 2.0, 0.2);
+        let exceedances: Vec<f64> = gpd.sample_n(5000, RandomSeed::Seed(1));
+        let fitted: GeneralizedPareto = GeneralizedPareto::fit_pwm(&exceedances).unwrap();
+        assert_eq!(fitted.loc, 0.0);
+        assert!((fitted.scale - 2.0).abs() < 0.1);
+        assert!((fitted.shape - 0.2).abs() < 0.05);
+        // the first two L-moments are matched exactly, the third one is a diagnostic
+        let lmom: LMoments = sample_lmoments(&exceedances);
+        let theory: LMoments = fitted.lmoments().unwrap();
+        assert!((theory.l1 - lmom.l1).abs() < 1e-12 && (theory.l2 - lmom.l2).abs() < 1e-12);
+        assert!(fitted.t3_discrepancy(&exceedances).abs() < 0.03);
+        // exceedances of a threshold chosen far below the data: uniform on [1, 2] is not GPD
+        let uniform: Vec<f64> = (0..1000).map(|i| 1.0 + i as f64 / 999.0).collect();
+        let fitted: GeneralizedPareto = GeneralizedPareto::fit_pwm(&uniform).unwrap();
+        assert!(fitted.t3_discrepancy(&uniform) > 0.5);
+
+        assert_eq!(GeneralizedPareto::fit_pwm(&[1.0, -1.0, 2.0]), Err(FitError::InvalidInput));
+        assert_eq!(GeneralizedPareto::fit_pwm(&[1.0, 2.0]), Err(FitError::InsufficientData));
+        assert_eq!(GeneralizedPareto::fit_pwm(&[1.0, 1.0, 1.0]), Err(FitError::Degenerate));
+    }
+}
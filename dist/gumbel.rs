@@ -1,7 +1,15 @@
 //! The Gumbel Distribution.
-use libm::{exp, log};
+use std::fmt;
+
+use libm::{exp, expm1, log, log10, log1p, pow, sqrt};
 
 use crate::dist::distutils::*;
+use crate::dist::viz::scale_to_peak;
+use crate::dist::empirical::PlottingPosition;
+use crate::dist::constants::{EULER_GAMMA, PI_SQUARED_OVER_6, ZETA_3};
+use crate::dist::fit::{check_data, check_distinct, mean_sd, nelder_mead, FitError, FitMethod, Fittable};
+use crate::dist::lmoments::{sample_lmoments, LMoments};
+use crate::dist::params::{check_params, decode, encode, DecodeError, Scale, ParamError};
 
 use rand_chacha::ChaCha8Rng;
 use rand::SeedableRng;
@@ -10,7 +18,7 @@ use rand::Rng;
 //extern crate libm::exp;
 
 /// Gumbel Dist. struct
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Gumbel {
     /// location parameter
     pub loc:   f64,
@@ -27,18 +35,335 @@ impl Gumbel {
         Gumbel{loc, scale}
     }
 
-    /// Obtain the location parameter
+    /// Parameters packed into a vector, in the order (loc, scale).
+    pub fn as_params(&self) -> Vec<f64> {
+        vec![self.loc, self.scale]
+    }
+
+    /// Bounds `(lo, hi)` of each parameter, in the order (loc, scale) of `as_params`, for bounded
+    /// optimizers. The strictly positive scale is bounded below by `f64::EPSILON`.
+    pub fn param_bounds() -> Vec<(f64, f64)> {
+        vec![(f64::NEG_INFINITY, f64::INFINITY), (f64::EPSILON, f64::INFINITY)]
+    }
+
+    /// Create the distribution from parameters packed in the order (loc, scale), e.g. by an optimizer.
+    /// Exactly 2 finite parameters are required and the scale must be positive.
+    pub fn from_params(params: &[f64]) -> Result<Self, ParamError> {
+        check_params(params, 2)?;
+        if params[1] <= 0.0 {
+            return Err(ParamError::NonPositiveScale);
+        }
+        Ok(Gumbel{ loc: params[0], scale: params[1] })
+    }
+
+    /// Distribution of $a X + b$ for $X$ following this distribution, $a > 0$: the location becomes
+    /// $a \cdot loc + b$ and the scale $a \cdot scale$. The resulting scale must be finite and
+    /// positive (checked by `domain!`, see `try_affine` for the checked version).
+    pub fn affine(&self, a: f64, b: f64) -> Self {
+        let dist: Gumbel = Gumbel { loc: a * self.loc + b, scale: a * self.scale };
+        domain!(dist.loc.is_finite() && dist.scale.is_finite() && dist.scale > 0.0);
+        dist
+    }
+
+    /// Checked version of `affine`, see `ParamError`.
+    pub fn try_affine(&self, a: f64, b: f64) -> Result<Self, ParamError> {
+        Gumbel::from_params(&[a * self.loc + b, a * self.scale])
+    }
+
+    /// The same distribution with the given scale parameter, which must be finite and positive
+    /// (checked by `domain!`, see `try_with_scale` for the checked version).
+    pub fn with_scale(&self, scale: f64) -> Self {
+        domain!(scale.is_finite() && scale > 0.0);
+        Gumbel { loc: self.loc, scale }
+    }
+
+    /// Checked version of `with_scale`, see `ParamError`.
+    pub fn try_with_scale(&self, scale: f64) -> Result<Self, ParamError> {
+        Gumbel::from_params(&[self.loc, scale])
+    }
+
+    /// Create an instance of the Gumbel Distribution from an already validated scale parameter,
+    /// so no domain check is needed.
+    #[inline]
+    pub fn from_validated(loc: f64, scale: Scale) -> Self {
+        Gumbel{loc, scale: scale.get()}
+    }
+
+    /// Tag byte identifying the Gumbel distribution in its binary form.
+    pub const TAG: u8 = 0;
+
+    /// Compact binary form: the tag byte `Gumbel::TAG` followed by the 2 parameters (see `as_params`)
+    /// as little-endian `f64`s.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        encode(Gumbel::TAG, &self.as_params())
+    }
+
+    /// Decode the distribution from its binary form (see `to_le_bytes`), validating the length,
+    /// the tag and the parameters.
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let params: Vec<f64> = decode(bytes, Gumbel::TAG, 2)?;
+        Gumbel::from_params(&params).map_err(DecodeError::InvalidParams)
+    }
+
+    /// Create the Gumbel distribution with quantiles `q1` and `q2` at probabilities `p1` and `p2`.
+    /// Since $q_i = loc + scale \cdot y_i$ with $y_i = - \log(- \log p_i)$, the two equations are solved
+    /// analytically: $scale = \frac{q_2 - q_1}{y_2 - y_1}$ and $loc = q_1 - scale \cdot y_1$.
+    /// Returns `FitError::InvalidInput` if a probability is not in $(0, 1)$, the probabilities are equal,
+    /// or the quantiles are not increasing with the probabilities.
+    pub fn from_quantiles(p1: f64, q1: f64, p2: f64, q2: f64) -> Result<Gumbel, FitError> {
+        let valid_p = |p: f64| p > 0.0 && p < 1.0;
+        if !valid_p(p1) || !valid_p(p2) || p1 == p2 || !q1.is_finite() || !q2.is_finite() {
+            return Err(FitError::InvalidInput);
+        }
+        let (y1, y2) = (- log(- log(p1)), - log(- log(p2)));
+        let scale: f64 = (q2 - q1) / (y2 - y1);
+        if scale > 0.0 && scale.is_finite() {
+            Ok(Gumbel { loc: q1 - scale * y1, scale })
+        } else {
+            Err(FitError::InvalidInput)
+        }
+    }
+
+    /// Standardized residuals $r_i = - \log F(x_i) = e^{- y_i}$ of the data, which follow the unit
+    /// exponential distribution when the data follows this Gumbel distribution (as $F(X)$ is uniform).
+    /// Used for QQ plots against the exponential quantiles $- \log(1 - p)$ to check a fit.
+    pub fn residuals(&self, data: &[f64]) -> Vec<f64> {
+        data.iter().map(|&x| exp(- self.reduced(x))).collect()
+    }
+
+    /// Log-likelihood of the data under the Gumbel distribution,
+    /// $\ell = \sum_i \left( - \log scale - y_i - e^{- y_i} \right)$ with $y_i = \frac{x_i - loc}{scale}$.
+    /// Returns $-\infty$ if the scale is not positive.
+    pub fn log_likelihood(&self, data: &[f64]) -> f64 {
+        if self.scale <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let log_scale: f64 = log(self.scale);
+        data.iter()
+            .map(|x| {
+                let y: f64 = self.reduced(*x);
+                - log_scale - y - exp(- y)
+            })
+            .sum()
+    }
+
+    /// Fit the Gumbel distribution to the data by matching the mean and variance:
+    /// $scale = \frac{\sqrt{6} s}{\pi}$ and $loc = \bar{x} - \gamma \cdot scale$, $\gamma$ being the Euler-Mascheroni constant.
+    /// At least 2 finite observations taking at least 2 distinct values are required.
+    pub fn fit_moments(data: &[f64]) -> Result<Gumbel, FitError> {
+        check_data(data, 2)?;
+        check_distinct(data, 2)?;
+        let (mean, sd) = mean_sd(data);
+        let scale: f64 = sqrt(6.0) * sd / std::f64::consts::PI;
+        if scale > 0.0 {
+            Ok(Gumbel { loc: mean - EULER_GAMMA * scale, scale })
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+    /// Fit the Gumbel distribution to the data by probability weighted moments (equivalently
+    /// L-moments): $scale = \frac{\lambda_2}{\log 2}$ and $loc = \lambda_1 - \gamma \cdot scale$.
+    /// At least 3 finite observations taking at least 2 distinct values are required.
+    pub fn fit_pwm(data: &[f64]) -> Result<Gumbel, FitError> {
+        check_data(data, 3)?;
+        check_distinct(data, 2)?;
+        let lmom: LMoments = sample_lmoments(data);
+        let scale: f64 = lmom.l2 / log(2.0);
+        if scale > 0.0 {
+            Ok(Gumbel { loc: lmom.l1 - EULER_GAMMA * scale, scale })
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+    /// Fit the Gumbel distribution to the data by L-moments: $scale = \frac{\lambda_2}{\log 2}$ and
+    /// $loc = \lambda_1 - \gamma \cdot scale$. This is the same estimator as `fit_pwm`, under the
+    /// L-moment name; it is closed form and less sensitive to outliers than `fit_mle`, in particular to
+    /// low outliers which dominate the likelihood through its $e^{-y}$ term.
+    /// At least 3 finite observations taking at least 2 distinct values are required.
+    pub fn fit_lmoments(data: &[f64]) -> Result<Gumbel, FitError> {
+        Gumbel::fit_pwm(data)
+    }
+
+    /// Fit the Gumbel distribution to the data by least squares on a Gumbel probability plot, the
+    /// "Gumbel method" of older hydrology: the sorted observations $x_{(i)}$ are regressed on the
+    /// reduced variates $y_i = - \log(- \log p_i)$ of their plotting positions $p_i$ (see
+    /// `PlottingPosition`), so that the fitted line $x = loc + scale \cdot y$ gives the scale as its
+    /// slope and the location as its intercept. It is less efficient than `fit_pwm` or `fit_mle`,
+    /// and the choice of plotting position shifts the estimates slightly.
+    /// At least 2 finite observations taking at least 2 distinct values are required.
+    pub fn fit_plotting(data: &[f64], kind: PlottingPosition) -> Result<Gumbel, FitError> {
+        check_data(data, 2)?;
+        check_distinct(data, 2)?;
+        let mut sorted: Vec<f64> = data.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let n: usize = sorted.len();
+        let reduced: Vec<f64> = (1..=n).map(|i| - log(- log(kind.probability(i, n)))).collect();
+        let mean_x: f64 = sorted.iter().sum::<f64>() / n as f64;
+        let mean_y: f64 = reduced.iter().sum::<f64>() / n as f64;
+        let cov: f64 = sorted.iter().zip(&reduced).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+        let var_y: f64 = reduced.iter().map(|y| (y - mean_y) * (y - mean_y)).sum::<f64>();
+        let scale: f64 = cov / var_y;
+        if scale > 0.0 && scale.is_finite() {
+            Ok(Gumbel { loc: mean_x - scale * mean_y, scale })
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+    /// Fit the Gumbel distribution to the data by maximum likelihood. The optimizer (Nelder-Mead)
+    /// works on $(loc, \log scale)$ and is started from the moment estimates.
+    /// At least 2 finite observations taking at least 2 distinct values are required.
+    pub fn fit_mle(data: &[f64]) -> Result<Gumbel, FitError> {
+        let init: Gumbel = Gumbel::fit_moments(data)?;
+        Gumbel::fit_mle_from(data, init)
+    }
+
+    /// Fit the Gumbel distribution to the data by maximum likelihood, starting the optimizer from
+    /// `init` rather than from the moment estimates. A start close to the optimum (e.g. the PWM
+    /// estimate `Gumbel::fit_pwm`) avoids the optimizer getting stuck far from it, e.g. when the
+    /// moments are distorted by outliers.
+    /// At least 2 finite observations taking at least 2 distinct values are required.
+    pub fn fit_mle_from(data: &[f64], init: Gumbel) -> Result<Gumbel, FitError> {
+        check_data(data, 2)?;
+        check_distinct(data, 2)?;
+        let neg_lik = |p: &[f64]| - Gumbel { loc: p[0], scale: exp(p[1]) }.log_likelihood(data);
+        let start: [f64; 2] = [init.loc, log(init.scale)];
+        let (params, _) = nelder_mead(neg_lik, &start, &[0.1 * init.scale, 0.1], 5000, 1e-12).ok_or(FitError::NoConvergence)?;
+        let fitted: Gumbel = Gumbel { loc: params[0], scale: exp(params[1]) };
+        if fitted.loc.is_finite() && fitted.scale.is_finite() && fitted.scale > 0.0 {
+            Ok(fitted)
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+
+    /// Gumbel reduced variate $y = - \log(- \log p)$ of the probability $p$, the x-axis of the
+    /// classic Gumbel plot on which the quantiles are linear: $F^{-1}(p) = loc + scale \cdot y$.
+    pub fn reduced_variate(p: f64) -> f64 {
+        domain!(p > 0.0 && p < 1.0);
+        - log(- log(p))
+    }
+
+    /// Level corresponding to the reduced variate `y`, i.e. $loc + scale \cdot y$.
     #[inline(always)]
-    pub fn loc(&self) -> f64 {
+    pub fn from_reduced_variate(&self, y: f64) -> f64 {
+        self.loc + self.scale * y
+    }
+
+    /// Reduced variate of the return period $T$ (in blocks), i.e. $- \log(- \log(1 - 1 / T))$.
+    pub fn return_period_to_reduced_variate(return_period: f64) -> f64 {
+        domain!(return_period > 1.0);
+        Gumbel::reduced_variate(1.0 - 1.0 / return_period)
+    }
+
+    /// Return level for the given return period (in blocks), $F^{-1}(1 - 1 / T)$.
+    /// The return period must be larger than 1.
+    pub fn return_level(&self, return_period: f64) -> f64 {
+        domain!(return_period > 1.0);
+        self.quantile(1.0 - 1.0 / return_period)
+    }
+
+    /// Return level for a return period given on a base-10 log scale, i.e. `return_level(10^log10_period)`,
+    /// for hydrology tables using a $\log_{10}(T)$ axis.
+    pub fn return_level_log10(&self, log10_period: f64) -> f64 {
+        self.return_level(pow(10.0, log10_period))
+    }
+
+    /// Base-10 logarithm of the return period of the given level (inverse of `return_level_log10`),
+    /// $- \log_{10} S(level)$, from the stable `sf` so that it stays finite far in the upper tail.
+    pub fn return_period_log10(&self, level: f64) -> f64 {
+        - log10(self.sf(level))
+    }
+
+    /// Mean of the Gumbel distribution, $loc + \gamma \cdot scale$ with $\gamma$ the Euler-Mascheroni constant.
+    pub fn mean(&self) -> f64 {
+        self.loc + EULER_GAMMA * self.scale
+    }
+
+    /// Variance of the Gumbel distribution, $\frac{\pi^2}{6} scale^2$.
+    pub fn variance(&self) -> f64 {
+        PI_SQUARED_OVER_6 * self.scale * self.scale
+    }
+
+    /// Median of the Gumbel distribution, $loc - scale \log \log 2$.
+    pub fn median(&self) -> f64 {
+        self.loc - self.scale * log(log(2.0))
+    }
+
+    /// Skewness of the Gumbel distribution, the constant $\frac{12 \sqrt{6} \zeta(3)}{\pi^3} \approx 1.1395$.
+    pub fn skewness(&self) -> f64 {
+        12.0 * sqrt(6.0) * ZETA_3 / pow(std::f64::consts::PI, 3.0)
+    }
+
+    /// Differential entropy of the Gumbel distribution, $\log scale + \gamma + 1$.
+    pub fn entropy(&self) -> f64 {
+        log(self.scale) + EULER_GAMMA + 1.0
+    }
+
+    /// Summary of the distribution, all the moments existing.
+    pub fn summary(&self) -> DistSummary {
+        DistSummary {
+            mean: Some(self.mean()),
+            variance: Some(self.variance()),
+            median: self.median(),
+            mode: self.mode(),
+            skewness: Some(self.skewness()),
+            support: self.support(),
+            entropy: self.entropy(),
+        }
+    }
+
+    /// Mode of the Gumbel distribution, which is the location parameter.
+    #[inline(always)]
+    pub fn mode(&self) -> f64 {
         self.loc
     }
 
-    /// Obtain the scale parameter
+    /// Maximum value of the PDF, attained at the mode: $f(loc) = \frac{1}{e \cdot scale}$.
+    pub fn pdf_max(&self) -> f64 {
+        self.pdf(self.mode())
+    }
+
+    /// PDF over `xs` scaled so that its peak `pdf_max` has the height `peak_height`, e.g. to overlay
+    /// the fitted density on a histogram with an arbitrary vertical scale. The maximum of the
+    /// returned values is `peak_height` when `xs` contains the mode.
+    pub fn pdf_scaled(&self, xs: &[f64], peak_height: f64) -> Vec<f64> {
+        scale_to_peak(self.pdf_over(xs), self.pdf_max(), peak_height)
+    }
+
+}
+
+/// Fitting methods available for the Gumbel Distribution: all of them.
+impl Fittable for Gumbel {
+    fn fit(data: &[f64], method: FitMethod) -> Result<Self, FitError> {
+        match method {
+            FitMethod::Mle => Gumbel::fit_mle(data),
+            FitMethod::Pwm => Gumbel::fit_pwm(data),
+            FitMethod::MomentMatching => Gumbel::fit_moments(data),
+        }
+    }
+}
+
+impl fmt::Display for Gumbel {
+    /// Format as `Gumbel(loc=..., scale=...)`, honoring the precision of the formatter
+    /// (e.g. `format!("{:.2}", dist)`), the parameters being printed in full by default.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_params(f, "Gumbel", &[("loc", self.loc), ("scale", self.scale)])
+    }
+}
+
+impl LocationScale for Gumbel {
     #[inline(always)]
-    pub fn scale(&self) -> f64 {
-        self.scale
+    fn loc(&self) -> f64 {
+        self.loc
     }
 
+    #[inline(always)]
+    fn scale(&self) -> f64 {
+        self.scale
+    }
 }
 
 /// Distributional Quantities for the Gumbel Distribution.
@@ -47,25 +372,71 @@ impl DistQuant for Gumbel {
     /// CDF: $F(x) = \exp \left \{ - \exp \left \{- \frac{x - \loc}{\scale}  \right \} \right \} $
     /// for $x \in \mathbb{R}$
     fn cdf(&self, x: f64) -> f64 {
-        let y: f64 = (x - self.loc) / self.scale; 
+        let y: f64 = self.reduced(x);
         exp(- exp(-y))
     }
+
+    /// Logarithm of the CDF, exactly $\log F(x) = - e^{-y}$, without underflow in the lower tail.
+    fn log_cdf(&self, x: f64) -> f64 {
+        let y: f64 = self.reduced(x);
+        - exp(-y)
+    }
+
+    /// Logarithm of the survival function, $\log(1 - e^{-t})$ with $t = e^{-y}$, which tends to
+    /// $- y$ in the upper tail instead of underflowing.
+    fn log_sf(&self, x: f64) -> f64 {
+        log1mexp(- self.reduced(x))
+    }
+
+    /// Reduced variate $- \log(- \log F(x)) = \frac{x - loc}{scale}$, exactly linear in $x$.
+    fn gumbel_reduced(&self, x: f64) -> f64 {
+        self.reduced(x)
+    }
+
+    /// Survival function $S(x) = - \mathrm{expm1}(- e^{-y})$, accurate in the upper tail.
+    fn sf(&self, x: f64) -> f64 {
+        let y: f64 = self.reduced(x);
+        - expm1(- exp(-y))
+    }
     
     /// PDF of the Gumbel distribution.
     /// $f(x) = \frac{1}{\scale} \exp \left \{- \frac{x - \loc}{\scale} \right \} \exp \left \{- \exp \left \{ - \frac{x - \loc}{\scale} \right \} \right \}$
     fn pdf(&self, x: f64) -> f64 {
-        let y: f64 = (x - self.loc) / self.scale;
+        let y: f64 = self.reduced(x);
         let constant: f64 = 1.0 / self.scale;
         constant * exp(- y) * exp(- exp(-y))
     }
 
+    /// PDF and CDF computed together from $e^{-y}$, $f(x) = \frac{e^{-y}}{scale} F(x)$.
+    fn evaluate(&self, x: f64) -> (f64, f64) {
+        let exp_y: f64 = exp(- self.reduced(x));
+        let cdf: f64 = exp(- exp_y);
+        (exp_y / self.scale * cdf, cdf)
+    }
+
     /// Quantile (inverse CDF) function.
     /// $F^{-1}(x) = \loc - \scale \log \left ( - \log \left ( x \right ) \right )$
     fn quantile(&self, x: f64) -> f64 {
-        domain!(x >= 0.0 && x <= 1.0);
+        domain!((0.0..=1.0).contains(&x));
         self.loc - self.scale * log(-log(x))
     }
 
+    /// Inverse survival function $S^{-1}(p) = loc - scale \log(- \mathrm{log1p}(- p))$.
+    fn isf(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        self.loc - self.scale * log(- log1p(- p))
+    }
+
+    /// Density quantile function $\frac{dQ}{dp} = \frac{scale}{- p \log p}$, infinite at $p = 0$ and
+    /// $p = 1$ where the density vanishes.
+    fn quantile_density(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        if p == 0.0 || p == 1.0 {
+            return f64::INFINITY;
+        }
+        self.scale / (- p * log(p))
+    }
+
     /// Return a randomly generated value from the Gumbel distribution.
     fn random(&self, seed: RandomSeed) -> f64 {
         
@@ -82,6 +453,8 @@ impl DistQuant for Gumbel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dist::gof::cramer_von_mises;
+    use crate::dist::gpd::GeneralizedPareto;
 
     // quick macro to create the instance of the Gumbel Distribution
     macro_rules! new_gumbel(
@@ -111,4 +484,264 @@ mod tests {
         let gumb_quant: f64 = gumb.quantile(0.7);
         assert_eq!(ans, gumb_quant);
     }
+
+    #[test]
+    fn gumbel_from_validated_test() {
+        let gumb: Gumbel = Gumbel::from_validated(0.5, Scale::new(2.0).unwrap());
+        let raw: Gumbel = new_gumbel!(0.5, 2.0);
+        assert_eq!(raw.cdf(2.0), gumb.cdf(2.0));
+        assert_eq!(raw.quantile(0.7), gumb.quantile(0.7));
+    }
+
+    #[test]
+    fn gumbel_param_bounds_test() {
+        let bounds: Vec<(f64, f64)> = Gumbel::param_bounds();
+        assert_eq!(bounds.len(), 2);
+        // the lower bounds are valid parameters, and the values just outside are rejected
+        assert!(Gumbel::from_params(&[0.5, f64::EPSILON]).is_ok());
+        assert!(Gumbel::from_params(&[0.5, 0.0]).is_err());
+        assert!(bounds.iter().all(|(lo, hi)| lo < hi));
+    }
+
+    #[test]
+    fn gumbel_mean_mc_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        assert!((gumb.mean() - 1.6544313298030657).abs() < 1e-14);
+        assert!((gumb.variance() - 6.579736267392906).abs() < 1e-14);
+        // within 4 standard errors, sqrt(variance / n) for the mean
+        let n: usize = 100_000;
+        let mean_se: f64 = (gumb.variance() / n as f64).sqrt();
+        assert!((gumb.mean_mc(n, RandomSeed::Seed(1)) - gumb.mean()).abs() < 4.0 * mean_se);
+        assert!((gumb.variance_mc(n, RandomSeed::Seed(1)) - gumb.variance()).abs() < 0.15);
+    }
+
+    #[test]
+    fn gumbel_summary_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let summary: DistSummary = gumb.summary();
+        assert_eq!(summary.mean, Some(gumb.mean()));
+        assert_eq!(summary.variance, Some(gumb.variance()));
+        assert_eq!(summary.skewness, Some(gumb.skewness()));
+        assert_eq!((summary.median, summary.mode), (gumb.median(), 0.5));
+        assert_eq!(summary.support, (f64::NEG_INFINITY, f64::INFINITY));
+        assert!((gumb.cdf(summary.median) - 0.5).abs() < 1e-15);
+        assert!((summary.skewness.unwrap() - 1.1395470994046486).abs() < 1e-15);
+        let config: Integration = Integration::default();
+        assert!((summary.entropy - gumb.expect(|x| - log(gumb.pdf(x)), config)).abs() < 1e-8);
+    }
+
+    #[test]
+    fn gumbel_pdf_scaled_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let xs: Vec<f64> = (0..41).map(|i| -4.5 + 0.25 * i as f64).collect();
+        let scaled: Vec<f64> = gumb.pdf_scaled(&xs, 30.0);
+        // the mode 0.5 is one of the points
+        assert!((scaled.iter().cloned().fold(0.0, f64::max) - 30.0).abs() < 1e-12);
+        assert!(scaled.iter().zip(gumb.pdf_over(&xs)).all(|(s, f)| (s - 30.0 * f * 2.0 * exp(1.0)).abs() < 1e-12));
+    }
+
+    #[test]
+    fn gumbel_residuals_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        assert_eq!(gumb.residuals(&[0.5, 2.5]), vec![1.0, exp(-1.0)]);
+        let data: Vec<f64> = gumb.sample_n(500, RandomSeed::Seed(3));
+        let fitted: Gumbel = Gumbel::fit_mle(&data).unwrap();
+        let exponential: GeneralizedPareto = GeneralizedPareto::new(0.0, 1.0, 0.0);
+        assert!(cramer_von_mises(&exponential, &fitted.residuals(&data)) < 0.461);
+    }
+
+    #[test]
+    fn gumbel_return_level_precise_test() {
+        // reference -log(-log1p(-1e-5)) computed with 40 digits
+        let reference: f64 = 11.512920464949395;
+        let gumb: Gumbel = new_gumbel!(0.0, 1.0);
+        let precise_err: f64 = (gumb.return_level_precise(1e5) - reference).abs();
+        let naive_err: f64 = (gumb.return_level(1e5) - reference).abs();
+        assert!(precise_err < 1e-14);
+        assert!(precise_err < naive_err);
+        assert!((gumb.isf(0.3) - gumb.quantile(0.7)).abs() < 1e-14);
+    }
+
+    #[test]
+    fn gumbel_log_cdf_sf_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        for x in [-20.0, -3.0, 0.5, 4.0, 30.0, 100.0] {
+            let y: f64 = (x - 0.5) / 2.0;
+            // the identities hold exactly, with no rounding drift between the stable forms
+            assert_eq!(gumb.log_cdf(x), - exp(-y));
+            assert_eq!(gumb.sf(x), - expm1(gumb.log_cdf(x)));
+            assert_eq!(gumb.cdf(x), exp(gumb.log_cdf(x)));
+            assert!((gumb.sf(x) - (1.0 - gumb.cdf(x))).abs() <= f64::EPSILON);
+        }
+        // far in the tails where the plain forms degenerate
+        assert_eq!(gumb.cdf(-20.0), 0.0);
+        assert!(gumb.log_cdf(-20.0).is_finite());
+        assert_eq!(1.0 - gumb.cdf(100.0), 0.0);
+        assert!(gumb.sf(100.0) > 0.0);
+    }
+
+    #[test]
+    fn gumbel_display_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 1.0 / 3.0);
+        assert_eq!(format!("{}", gumb), "Gumbel(loc=0.5, scale=0.3333333333333333)");
+        assert_eq!(format!("{:.3}", gumb), "Gumbel(loc=0.500, scale=0.333)");
+    }
+
+    #[test]
+    fn gumbel_params_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        assert_eq!(gumb.as_params(), vec![0.5, 2.0]);
+        assert_eq!(Gumbel::from_params(&gumb.as_params()), Ok(gumb));
+        assert_eq!(Gumbel::from_params(&[0.5, 2.0, 1.0]), Err(ParamError::WrongLength { expected: 2, found: 3 }));
+        assert_eq!(Gumbel::from_params(&[0.5, -2.0]), Err(ParamError::NonPositiveScale));
+        assert_eq!(Gumbel::from_params(&[f64::NAN, 2.0]), Err(ParamError::NonFinite));
+    }
+
+    #[test]
+    fn gumbel_affine_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let moved: Gumbel = gumb.affine(3.0, -1.0);
+        assert_eq!(moved, new_gumbel!(0.5, 6.0));
+        for x in [-2.0, 0.5, 3.0] {
+            assert!((moved.cdf(3.0 * x - 1.0) - gumb.cdf(x)).abs() < 1e-14);
+        }
+        assert_eq!(gumb.try_affine(3.0, -1.0), Ok(moved));
+        assert_eq!(gumb.with_scale(4.0).try_with_scale(1.0), Ok(new_gumbel!(0.5, 1.0)));
+        // overflowing or vanishing scales are rejected
+        let large: Gumbel = new_gumbel!(0.0, 1e10);
+        assert_eq!(large.try_affine(1e308, 0.0), Err(ParamError::NonFinite));
+        assert_eq!(gumb.try_affine(-1.0, 0.0), Err(ParamError::NonPositiveScale));
+        assert_eq!(new_gumbel!(0.0, 1e-300).try_affine(1e-300, 0.0), Err(ParamError::NonPositiveScale));
+        assert_eq!(gumb.try_with_scale(f64::INFINITY), Err(ParamError::NonFinite));
+    }
+
+    #[test]
+    fn gumbel_from_quantiles_test() {
+        let gumb: Gumbel = Gumbel::from_quantiles(0.5, 10.0, 0.99, 50.0).unwrap();
+        assert!((gumb.quantile(0.5) - 10.0).abs() < 1e-10);
+        assert!((gumb.quantile(0.99) - 50.0).abs() < 1e-10);
+        assert_eq!(Gumbel::from_quantiles(0.5, 10.0, 0.5, 50.0).err(), Some(FitError::InvalidInput));
+        assert_eq!(Gumbel::from_quantiles(0.5, 50.0, 0.99, 10.0).err(), Some(FitError::InvalidInput));
+        assert_eq!(Gumbel::from_quantiles(0.0, 10.0, 0.99, 50.0).err(), Some(FitError::InvalidInput));
+    }
+
+    #[test]
+    fn gumbel_le_bytes_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let bytes: Vec<u8> = gumb.to_le_bytes();
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(bytes[0], Gumbel::TAG);
+        assert_eq!(Gumbel::from_le_bytes(&bytes), Ok(gumb));
+        assert_eq!(Gumbel::from_le_bytes(&bytes[..10]), Err(DecodeError::WrongLength { expected: 17, found: 10 }));
+        let mut bad_scale: Vec<u8> = bytes.clone();
+        bad_scale[9..].copy_from_slice(&(-2.0_f64).to_le_bytes());
+        assert_eq!(Gumbel::from_le_bytes(&bad_scale), Err(DecodeError::InvalidParams(ParamError::NonPositiveScale)));
+    }
+
+    #[test]
+    fn gumbel_fit_lmoments_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let mut data: Vec<f64> = gumb.sample_n(500, RandomSeed::Seed(2));
+        let fitted: Gumbel = Gumbel::fit_lmoments(&data).unwrap();
+        assert!((fitted.loc() - 0.5).abs() < 0.25);
+        assert!((fitted.scale() - 2.0).abs() < 0.25);
+        assert_eq!(Some(fitted), Gumbel::fit_pwm(&data).ok());
+
+        // a single low outlier moves the L-moment fit less than the maximum likelihood fit
+        let (lmom, mle) = (fitted, Gumbel::fit_mle(&data).unwrap());
+        data.push(-20.0);
+        let lmom_outlier: Gumbel = Gumbel::fit_lmoments(&data).unwrap();
+        let mle_outlier: Gumbel = Gumbel::fit_mle(&data).unwrap();
+        assert!((lmom_outlier.scale() - lmom.scale()).abs() < (mle_outlier.scale() - mle.scale()).abs());
+        assert!((lmom_outlier.loc() - lmom.loc()).abs() < (mle_outlier.loc() - mle.loc()).abs());
+    }
+
+    #[test]
+    fn gumbel_fit_mle_from_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let data: Vec<f64> = gumb.sample_n(500, RandomSeed::Seed(11));
+        // a start under which every observation has a zero density never reaches a finite likelihood
+        let bad: Gumbel = new_gumbel!(1000.0, 1e-3);
+        assert_eq!(Gumbel::fit_mle_from(&data, bad), Err(FitError::NoConvergence));
+        let fitted: Gumbel = Gumbel::fit_mle_from(&data, Gumbel::fit_pwm(&data).unwrap()).unwrap();
+        let mle: Gumbel = Gumbel::fit_mle(&data).unwrap();
+        assert!((fitted.loc() - mle.loc()).abs() < 1e-4);
+        assert!((fitted.scale() - mle.scale()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn gumbel_fit_plotting_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let data: Vec<f64> = gumb.sample_n(3000, RandomSeed::Seed(11));
+        let gringorten: Gumbel = Gumbel::fit_plotting(&data, PlottingPosition::Gringorten).unwrap();
+        assert!((gringorten.loc() - 0.5).abs() < 0.15);
+        assert!((gringorten.scale() - 2.0).abs() < 0.15);
+        // the plotting position shifts the estimates, slightly
+        let weibull: Gumbel = Gumbel::fit_plotting(&data, PlottingPosition::Weibull).unwrap();
+        assert_ne!(weibull, gringorten);
+        assert!((weibull.loc() - gringorten.loc()).abs() < 0.05);
+        assert!((weibull.scale() - gringorten.scale()).abs() < 0.1);
+        // points lying exactly on the Gumbel probability plot
+        let n: usize = 20;
+        let on_line: Vec<f64> = (1..=n).map(|i| gumb.quantile(PlottingPosition::Hazen.probability(i, n))).rev().collect();
+        let exact: Gumbel = Gumbel::fit_plotting(&on_line, PlottingPosition::Hazen).unwrap();
+        assert!((exact.loc() - 0.5).abs() < 1e-12 && (exact.scale() - 2.0).abs() < 1e-12);
+        assert_eq!(Gumbel::fit_plotting(&[1.0, 1.0, 1.0], PlottingPosition::Weibull), Err(FitError::Degenerate));
+    }
+
+    #[test]
+    fn gumbel_fit_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let data: Vec<f64> = gumb.sample_n(3000, RandomSeed::Seed(11));
+        for method in [FitMethod::Mle, FitMethod::Pwm, FitMethod::MomentMatching] {
+            let fitted: Gumbel = Gumbel::fit(&data, method).unwrap();
+            assert!((fitted.loc() - 0.5).abs() < 0.15);
+            assert!((fitted.scale() - 2.0).abs() < 0.15);
+        }
+        // the maximum likelihood fit has the largest likelihood
+        let mle: Gumbel = Gumbel::fit(&data, FitMethod::Mle).unwrap();
+        let pwm: Gumbel = Gumbel::fit(&data, FitMethod::Pwm).unwrap();
+        assert!(mle.log_likelihood(&data) >= pwm.log_likelihood(&data));
+        assert_eq!(Gumbel::fit(&[1.0], FitMethod::Mle).err(), Some(FitError::InsufficientData));
+    }
+
+    #[test]
+    fn gumbel_reduced_variate_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        // the quantiles are linear in the reduced variate
+        for p in [0.1, 0.5, 0.7, 0.99] {
+            let y: f64 = Gumbel::reduced_variate(p);
+            assert!((gumb.from_reduced_variate(y) - gumb.quantile(p)).abs() < 1e-12);
+        }
+        let (y1, y2) = (Gumbel::reduced_variate(0.2), Gumbel::reduced_variate(0.9));
+        let slope: f64 = (gumb.from_reduced_variate(y2) - gumb.from_reduced_variate(y1)) / (y2 - y1);
+        assert!((slope - 2.0).abs() < 1e-12);
+        // the 100-year point is the 0.99 quantile, at a reduced variate of about 4.6
+        let y_100: f64 = Gumbel::return_period_to_reduced_variate(100.0);
+        assert!((y_100 - 4.600149226776579).abs() < 1e-12);
+        assert!((gumb.from_reduced_variate(y_100) - gumb.quantile(0.99)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gumbel_return_level_log10_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        assert_eq!(gumb.return_level(100.0), gumb.quantile(0.99));
+        assert!((gumb.return_level_log10(2.0) - gumb.return_level(100.0)).abs() < 1e-12);
+        let level: f64 = gumb.return_level_log10(1.5);
+        assert!((gumb.return_period_log10(level) - 1.5).abs() < 1e-10);
+        // a return period of 1e20, where the CDF rounds to 1
+        let level: f64 = gumb.isf(1e-20);
+        assert_eq!(gumb.cdf(level), 1.0);
+        let log10_period: f64 = gumb.return_period_log10(level);
+        assert!((log10_period - 20.0).abs() < 1e-9);
+        assert!((gumb.isf(pow(10.0, - log10_period)) / level - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gumbel_pdf_max_test() {
+        let gumb: Gumbel = new_gumbel!(0.5, 2.0);
+        let grid_max: f64 = (0..20001).map(|i| gumb.pdf(-5.0 + 0.001 * i as f64)).fold(0.0, f64::max);
+        assert!((gumb.pdf_max() - grid_max).abs() < 1e-9);
+        assert!(gumb.pdf_max() >= grid_max);
+    }
 }
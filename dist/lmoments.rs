@@ -0,0 +1,85 @@
+//! Sample L-moments, used for the (fast and robust) L-moment / probability weighted moment fits
+//! and as initial values of the maximum likelihood fits.
+
+/// The first three sample L-moments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LMoments {
+    /// first L-moment (the sample mean)
+    pub l1: f64,
+    /// second L-moment (a measure of scale)
+    pub l2: f64,
+    /// third L-moment
+    pub l3: f64,
+}
+
+impl LMoments {
+    /// L-skewness $\tau_3 = \lambda_3 / \lambda_2$
+    #[inline(always)]
+    pub fn t3(&self) -> f64 {
+        self.l3 / self.l2
+    }
+}
+
+/// Unbiased estimators $b_0, \ldots, b_{order}$ of the probability weighted moments
+/// $\beta_r = E[X F(X)^r]$, from the sorted sample $x_{(1)} \leq \cdots \leq x_{(n)}$:
+/// $$ b_r = \frac{1}{n} \sum_{i=1}^{n} \frac{(i-1) \cdots (i-r)}{(n-1) \cdots (n-r)} x_{(i)}. $$
+/// In particular $b_0$ is the sample mean. More than `order` observations are needed.
+pub fn sample_pwm(data: &[f64], order: usize) -> Vec<f64> {
+    domain!(data.len() > order);
+    let mut sorted: Vec<f64> = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let n: f64 = sorted.len() as f64;
+
+    let mut pwm: Vec<f64> = vec![0.0; order + 1];
+    for (i, x) in sorted.iter().enumerate() {
+        let i: f64 = i as f64;
+        let (mut num, mut den) = (1.0, 1.0);
+        for (r, b) in pwm.iter_mut().enumerate() {
+            if r > 0 {
+                num *= i - (r - 1) as f64;
+                den *= n - r as f64;
+            }
+            *b += num / den * x;
+        }
+    }
+    pwm.iter().map(|b| b / n).collect()
+}
+
+/// Unbiased sample L-moments $\lambda_1, \lambda_2, \lambda_3$ of the data, computed from the
+/// probability weighted moments $b_r$ (see `sample_pwm`):
+/// $\lambda_1 = b_0$, $\lambda_2 = 2 b_1 - b_0$ and $\lambda_3 = 6 b_2 - 6 b_1 + b_0$.
+/// At least 3 observations are needed.
+pub fn sample_lmoments(data: &[f64]) -> LMoments {
+    domain!(data.len() >= 3);
+    let pwm: Vec<f64> = sample_pwm(data, 2);
+    let (b0, b1, b2) = (pwm[0], pwm[1], pwm[2]);
+    LMoments { l1: b0, l2: 2.0 * b1 - b0, l3: 6.0 * b2 - 6.0 * b1 + b0 }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_pwm_test() {
+        let data: [f64; 5] = [2.0, 5.0, 1.0, 7.0, 3.0];
+        let pwm: Vec<f64> = sample_pwm(&data, 3);
+        assert_eq!(pwm.len(), 4);
+        // hand computed on the sorted sample 1, 2, 3, 5, 7
+        let expected: [f64; 4] = [3.6, 2.55, 2.0, 1.65];
+        for (b, e) in pwm.iter().zip(expected) {
+            assert!((b - e).abs() < 1e-12);
+        }
+        assert_eq!(sample_pwm(&data, 0), vec![data.iter().sum::<f64>() / 5.0]);
+    }
+
+    #[test]
+    fn sample_lmoments_test() {
+        let lmom: LMoments = sample_lmoments(&[2.0, 5.0, 1.0, 7.0, 3.0]);
+        assert!((lmom.l1 - 3.6).abs() < 1e-12);
+        assert!((lmom.l2 - 1.5).abs() < 1e-12);
+        assert!((lmom.l3 - 0.3).abs() < 1e-12);
+        assert!((lmom.t3() - 0.2).abs() < 1e-12);
+    }
+}
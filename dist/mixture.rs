@@ -0,0 +1,157 @@
+//! Finite mixtures of extreme value distributions, e.g. for maxima generated by several regimes
+//! (convective and frontal storms, snowmelt and rainfall floods, ...).
+use crate::dist::distutils::*;
+use crate::dist::extreme::ExtremeValue;
+use crate::dist::gof::cdf_extended;
+use crate::dist::params::ParamError;
+
+use rand_chacha::ChaCha8Rng;
+use rand::Rng;
+
+/// Mixture of extreme value distributions: with probability $w_i$ a value is drawn from the
+/// $i$-th component, so $F(x) = \sum_i w_i F_i(x)$ and $f(x) = \sum_i w_i f_i(x)$.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mixture {
+    components: Vec<(f64, ExtremeValue)>,
+}
+
+impl Mixture {
+    /// Create the mixture of the given `(weight, distribution)` components.
+    /// There must be at least one component, the weights must be positive and sum to 1 (up to
+    /// `1e-10`), otherwise `ParamError::InvalidWeights` is returned.
+    pub fn new(components: Vec<(f64, ExtremeValue)>) -> Result<Self, ParamError> {
+        if components.iter().any(|(w, _)| w.is_nan() || *w <= 0.0) {
+            return Err(ParamError::InvalidWeights);
+        }
+        let total: f64 = components.iter().map(|(w, _)| w).sum();
+        if (total - 1.0).abs() > 1e-10 {
+            return Err(ParamError::InvalidWeights);
+        }
+        Ok(Mixture { components })
+    }
+
+    /// The `(weight, distribution)` components of the mixture.
+    pub fn components(&self) -> &[(f64, ExtremeValue)] {
+        &self.components
+    }
+
+    /// Draw a value with the given random number generator: a component is picked according to
+    /// the weights with a first uniform, then a value is drawn from it.
+    fn draw(&self, rng: &mut ChaCha8Rng) -> f64 {
+        let u: f64 = rng.gen::<f64>();
+        let mut cumulative: f64 = 0.0;
+        for (weight, dist) in self.components.iter() {
+            cumulative += weight;
+            if u < cumulative {
+                return dist.random_with(&mut *rng);
+            }
+        }
+        // u above the rounded total of the weights
+        self.components[self.components.len() - 1].1.random_with(rng)
+    }
+}
+
+impl DistQuant for Mixture {
+    /// CDF: $F(x) = \sum_i w_i F_i(x)$, each component CDF being 0 below and 1 above its support.
+    fn cdf(&self, x: f64) -> f64 {
+        self.components.iter().map(|(w, dist)| w * cdf_extended(dist, x)).sum()
+    }
+
+    /// PDF: $f(x) = \sum_i w_i f_i(x)$, each component density being 0 outside of its support.
+    fn pdf(&self, x: f64) -> f64 {
+        self.components.iter()
+            .filter(|(_, dist)| dist.is_in_support(x))
+            .map(|(w, dist)| w * dist.pdf(x))
+            .sum()
+    }
+
+    /// Quantile, computed numerically (see `DistQuant::quantile_numeric`) as the mixture has no
+    /// closed form quantile function.
+    fn quantile(&self, x: f64) -> f64 {
+        self.quantile_numeric(x)
+    }
+
+    /// Support: from the smallest lower end point to the largest upper end point of the components
+    /// (the density may vanish in between, if the component supports do not overlap).
+    fn support(&self) -> (f64, f64) {
+        self.components.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, dist)| {
+            let (lower, upper) = dist.support();
+            (lo.min(lower), hi.max(upper))
+        })
+    }
+
+    /// Return a randomly generated value from the mixture.
+    fn random(&self, seed: RandomSeed) -> f64 {
+        self.draw(&mut seed.rng())
+    }
+
+    /// Generate `n` values by picking a component then drawing from it, rather than by inverting
+    /// the (numerical) quantile function.
+    fn sample_n(&self, n: usize, seed: RandomSeed) -> Vec<f64> {
+        let mut rng: ChaCha8Rng = seed.rng();
+        (0..n).map(|_| self.draw(&mut rng)).collect()
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::gumbel::Gumbel;
+    use crate::dist::weibull::Weibull;
+    use crate::dist::gev::GEV;
+
+    fn two_regimes() -> (Gumbel, GEV, Mixture) {
+        let first: Gumbel = Gumbel::new(0.0, 1.0);
+        let second: GEV = GEV::new(10.0, 2.0, 0.2);
+        let mixture: Mixture = Mixture::new(vec![(0.3, ExtremeValue::Gumbel(first)), (0.7, ExtremeValue::GEV(second))]).unwrap();
+        (first, second, mixture)
+    }
+
+    #[test]
+    fn mixture_new_test() {
+        let gumb: ExtremeValue = ExtremeValue::Gumbel(Gumbel::new(0.0, 1.0));
+        assert_eq!(Mixture::new(vec![(0.5, gumb), (0.6, gumb)]), Err(ParamError::InvalidWeights));
+        assert_eq!(Mixture::new(vec![(1.5, gumb), (-0.5, gumb)]), Err(ParamError::InvalidWeights));
+        assert_eq!(Mixture::new(vec![(f64::NAN, gumb)]), Err(ParamError::InvalidWeights));
+        assert_eq!(Mixture::new(vec![]), Err(ParamError::InvalidWeights));
+        assert_eq!(Mixture::new(vec![(1.0, gumb)]).unwrap().components(), &[(1.0, gumb)]);
+    }
+
+    #[test]
+    fn mixture_cdf_pdf_test() {
+        let (first, second, mixture) = two_regimes();
+        for x in [0.5, 5.0, 12.0, 30.0] {
+            assert!((mixture.cdf(x) - (0.3 * first.cdf(x) + 0.7 * second.cdf(x))).abs() < 1e-15);
+            assert!((mixture.pdf(x) - (0.3 * first.pdf(x) + 0.7 * second.pdf(x))).abs() < 1e-15);
+        }
+        // below the lower end point of the GEV component
+        assert_eq!(mixture.cdf(-2.0), 0.3 * first.cdf(-2.0));
+        assert_eq!(mixture.pdf(-2.0), 0.3 * first.pdf(-2.0));
+        assert_eq!(mixture.integrate_pdf(-20.0, 200.0, 20_000).round(), 1.0);
+        for p in [0.1, 0.3, 0.5, 0.99] {
+            assert!((mixture.cdf(mixture.quantile(p)) - p).abs() < 1e-10);
+        }
+
+        // components with disjoint supports
+        let weib: Weibull = Weibull::new(0.0, 1.0, 2.0);
+        let disjoint: Mixture = Mixture::new(vec![(0.5, ExtremeValue::Weibull(weib)), (0.5, ExtremeValue::GEV(second))]).unwrap();
+        assert_eq!(disjoint.support(), (f64::NEG_INFINITY, f64::INFINITY));
+        assert_eq!(disjoint.cdf(0.5), 0.5);
+        assert_eq!(disjoint.pdf(0.5), 0.0);
+    }
+
+    #[test]
+    fn mixture_random_test() {
+        let (_, _, mixture) = two_regimes();
+        let n: usize = 20_000;
+        let sample: Vec<f64> = mixture.sample_n(n, RandomSeed::Seed(1));
+        assert_eq!(sample, mixture.sample_n(n, RandomSeed::Seed(1)));
+        // the Gumbel regime is (almost always) below 5 and the GEV regime above
+        let low: f64 = sample.iter().filter(|&&x| x < 5.0).count() as f64 / n as f64;
+        let expected: f64 = mixture.cdf(5.0);
+        assert!((low - expected).abs() < 0.01);
+        assert!((expected - 0.3).abs() < 0.01);
+        assert_eq!(mixture.random(RandomSeed::Seed(4)), mixture.sample_n(1, RandomSeed::Seed(4))[0]);
+    }
+}
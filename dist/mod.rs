@@ -1,5 +1,21 @@
 #[macro_use] pub mod distutils;
+pub mod constants;
 pub mod gumbel;
 pub mod frechet;
 pub mod weibull;
 pub mod gev;
+pub mod fit;
+pub mod params;
+pub mod lmoments;
+pub mod bootstrap;
+pub mod gpd;
+pub mod viz;
+pub mod gof;
+pub mod empirical;
+pub mod extreme;
+pub mod blocks;
+pub mod mixture;
+pub mod spliced;
+pub mod truncated;
+pub mod nonstationary;
+pub mod tail;
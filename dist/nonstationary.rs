@@ -0,0 +1,137 @@
+//! Non-stationary GEV models, whose parameters depend on a covariate $t$ (e.g. the year), for
+//! extremes whose distribution changes over time.
+use crate::dist::distutils::*;
+use crate::dist::fit::{check_data, check_distinct, nelder_mead, FitError};
+use crate::dist::gev::GEV;
+
+/// GEV distribution whose scale is linear in the covariate: $scale(t) = scale_0 + scale_1 t$, the
+/// location and shape being constant. This models extremes whose variability changes with $t$.
+/// The scale must be positive at every covariate value where the model is evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GevLinearScale {
+    /// location parameter
+    pub loc: f64,
+    /// scale at $t = 0$
+    pub scale0: f64,
+    /// change of the scale per unit of the covariate
+    pub scale1: f64,
+    /// shape parameter
+    pub shape: f64,
+}
+
+impl GevLinearScale {
+    /// Create the model with scale $scale_0 + scale_1 t$.
+    pub fn new(loc: f64, scale0: f64, scale1: f64, shape: f64) -> Self {
+        GevLinearScale{loc, scale0, scale1, shape}
+    }
+
+    /// Scale parameter at the covariate value `t`, $scale_0 + scale_1 t$.
+    #[inline(always)]
+    pub fn scale_at(&self, t: f64) -> f64 {
+        self.scale0 + self.scale1 * t
+    }
+
+    /// The (stationary) GEV distribution at the covariate value `t`, where the scale must be positive.
+    pub fn at(&self, t: f64) -> GEV {
+        GEV::new(self.loc, self.scale_at(t), self.shape)
+    }
+
+    /// CDF at `x` for the covariate value `t`.
+    pub fn cdf(&self, x: f64, t: f64) -> f64 {
+        self.at(t).cdf(x)
+    }
+
+    /// PDF at `x` for the covariate value `t`.
+    pub fn pdf(&self, x: f64, t: f64) -> f64 {
+        self.at(t).pdf(x)
+    }
+
+    /// Quantile of the probability `p` for the covariate value `t`.
+    pub fn quantile(&self, p: f64, t: f64) -> f64 {
+        self.at(t).quantile(p)
+    }
+
+    /// Log-likelihood of the observations `data` made at the covariate values `times`.
+    /// Returns $-\infty$ if the scale is not positive at one of the times or an observation lies
+    /// outside of the support, so it can safely be used as an objective function.
+    pub fn log_likelihood(&self, times: &[f64], data: &[f64]) -> f64 {
+        domain!(times.len() == data.len());
+        let mut total: f64 = 0.0;
+        for (&t, &x) in times.iter().zip(data.iter()) {
+            let scale: f64 = self.scale_at(t);
+            if scale.is_nan() || scale <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            total += GEV { loc: self.loc, scale, shape: self.shape }.log_likelihood(&[x]);
+            if total == f64::NEG_INFINITY {
+                return total;
+            }
+        }
+        total
+    }
+
+    /// Fit the model by maximum likelihood to the observations `data` made at the covariate values
+    /// `times`. The optimizer (Nelder-Mead) is started from the stationary maximum likelihood fit
+    /// ($scale_1 = 0$), and optimizes over $(loc, scale_0, scale_1, shape)$ with the scale kept
+    /// positive at all the times by the log-likelihood.
+    /// Returns `FitError::InvalidInput` if the lengths differ or a time is not finite; at least
+    /// 4 finite observations taking at least 3 distinct values are required.
+    pub fn fit(times: &[f64], data: &[f64]) -> Result<Self, FitError> {
+        if times.len() != data.len() || times.iter().any(|t| !t.is_finite()) {
+            return Err(FitError::InvalidInput);
+        }
+        check_data(data, 4)?;
+        check_distinct(data, 3)?;
+        let init: GEV = GEV::fit_mle(data)?;
+        let t_min: f64 = times.iter().cloned().fold(f64::INFINITY, f64::min);
+        let t_max: f64 = times.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let t_range: f64 = if t_max > t_min { t_max - t_min } else { 1.0 };
+
+        let neg_lik = |p: &[f64]| - GevLinearScale::new(p[0], p[1], p[2], p[3]).log_likelihood(times, data);
+        let start: [f64; 4] = [init.loc, init.scale, 0.0, init.shape];
+        let step: [f64; 4] = [0.1 * init.scale, 0.1 * init.scale, 0.1 * init.scale / t_range, 0.1];
+        let (params, _) = nelder_mead(neg_lik, &start, &step, 10_000, 1e-12).ok_or(FitError::NoConvergence)?;
+        let fitted: GevLinearScale = GevLinearScale::new(params[0], params[1], params[2], params[3]);
+        if params.iter().all(|p| p.is_finite()) && fitted.log_likelihood(times, data).is_finite() {
+            Ok(fitted)
+        } else {
+            Err(FitError::NoConvergence)
+        }
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gev_linear_scale_test() {
+        let model: GevLinearScale = GevLinearScale::new(10.0, 1.0, 0.5, 0.1);
+        assert_eq!(model.scale_at(2.0), 2.0);
+        assert_eq!(model.at(2.0), GEV::new(10.0, 2.0, 0.1));
+        assert_eq!(model.cdf(12.0, 2.0), GEV::new(10.0, 2.0, 0.1).cdf(12.0));
+        assert_eq!(model.pdf(12.0, 2.0), GEV::new(10.0, 2.0, 0.1).pdf(12.0));
+        assert_eq!(model.quantile(0.9, 0.0), GEV::new(10.0, 1.0, 0.1).quantile(0.9));
+        // the scale is negative at t = -3
+        assert_eq!(model.log_likelihood(&[-3.0], &[10.0]), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn gev_linear_scale_fit_test() {
+        // variability increasing from scale 1 to scale 3 over the record
+        let model: GevLinearScale = GevLinearScale::new(10.0, 1.0, 2.0, 0.1);
+        let times: Vec<f64> = (0..600).map(|i| i as f64 / 600.0).collect();
+        let data: Vec<f64> = times.iter().enumerate().map(|(i, &t)| model.at(t).random(RandomSeed::Seed(i as u64))).collect();
+        let fitted: GevLinearScale = GevLinearScale::fit(&times, &data).unwrap();
+        assert!(fitted.scale1 > 0.0);
+        assert!((fitted.scale1 - 2.0).abs() < 0.6);
+        assert!((fitted.scale0 - 1.0).abs() < 0.3);
+        assert!((fitted.loc - 10.0).abs() < 0.3);
+        // better than the stationary fit
+        let stationary: GEV = GEV::fit_mle(&data).unwrap();
+        assert!(fitted.log_likelihood(&times, &data) > stationary.log_likelihood(&data));
+
+        assert_eq!(GevLinearScale::fit(&times[..10], &data), Err(FitError::InvalidInput));
+    }
+}
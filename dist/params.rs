@@ -0,0 +1,190 @@
+//! Validated parameter types, so that invalid distributions can be rejected when they are built
+//! rather than when they are evaluated.
+use std::fmt;
+
+/// Errors returned when a distribution parameter is invalid.
+///
+/// They are also returned by the checked transforms of the distributions (`try_affine`,
+/// `try_with_scale`, ...), for long transform chains: an error is returned, rather than a silently
+/// broken distribution, when the new parameters overflow or the scale is not positive (e.g. a
+/// non-positive factor, or a subnormal product rounding to 0).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamError {
+    /// The parameter is NaN or infinite.
+    NonFinite,
+    /// The scale parameter is not strictly positive.
+    NonPositiveScale,
+    /// The shape parameter is not strictly positive (Fréchet and Weibull distributions).
+    NonPositiveShape,
+    /// The wrong number of parameters was given.
+    WrongLength { expected: usize, found: usize },
+    /// The mixture weights are not positive or do not sum to 1.
+    InvalidWeights,
+    /// The truncation interval is empty or has a zero probability.
+    InvalidInterval,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::NonFinite => write!(f, "the parameter must be finite"),
+            ParamError::NonPositiveScale => write!(f, "the scale parameter must be positive"),
+            ParamError::NonPositiveShape => write!(f, "the shape parameter must be positive"),
+            ParamError::WrongLength { expected, found } => write!(f, "expected {} parameters, found {}", expected, found),
+            ParamError::InvalidWeights => write!(f, "the weights must be positive and sum to 1"),
+            ParamError::InvalidInterval => write!(f, "the interval must be non-empty with a positive probability"),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+/// Errors returned when decoding a distribution from its binary form.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecodeError {
+    /// The byte slice does not have the expected length.
+    WrongLength { expected: usize, found: usize },
+    /// The tag byte does not identify the expected distribution.
+    WrongTag { expected: u8, found: u8 },
+    /// The decoded parameters are not valid for the distribution.
+    InvalidParams(ParamError),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::WrongLength { expected, found } => write!(f, "expected {} bytes, found {}", expected, found),
+            DecodeError::WrongTag { expected, found } => write!(f, "expected the tag {}, found {}", expected, found),
+            DecodeError::InvalidParams(err) => write!(f, "invalid parameters: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Errors returned when building a distribution from `key = value` pairs (see `extreme::from_kv`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseDistError {
+    /// The kind does not name one of the extreme value families.
+    UnknownKind(String),
+    /// The key is not a parameter of the family.
+    UnknownKey(String),
+    /// The key is given more than once.
+    DuplicateKey(String),
+    /// The parameters are not valid for the family.
+    InvalidParams(ParamError),
+}
+
+impl fmt::Display for ParseDistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDistError::UnknownKind(kind) => write!(f, "unknown distribution kind '{}'", kind),
+            ParseDistError::UnknownKey(key) => write!(f, "unknown parameter '{}'", key),
+            ParseDistError::DuplicateKey(key) => write!(f, "the parameter '{}' is given more than once", key),
+            ParseDistError::InvalidParams(err) => write!(f, "invalid parameters: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ParseDistError {}
+
+/// Binary form of a distribution: one tag byte identifying the distribution followed by the
+/// parameters as little-endian `f64`s.
+pub(crate) fn encode(tag: u8, params: &[f64]) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(1 + 8 * params.len());
+    bytes.push(tag);
+    for param in params {
+        bytes.extend_from_slice(&param.to_le_bytes());
+    }
+    bytes
+}
+
+/// Decode the `n_params` parameters of the distribution identified by `tag` (see `encode`).
+pub(crate) fn decode(bytes: &[u8], tag: u8, n_params: usize) -> Result<Vec<f64>, DecodeError> {
+    let expected: usize = 1 + 8 * n_params;
+    if bytes.len() != expected {
+        return Err(DecodeError::WrongLength { expected, found: bytes.len() });
+    }
+    if bytes[0] != tag {
+        return Err(DecodeError::WrongTag { expected: tag, found: bytes[0] });
+    }
+    Ok(bytes[1..]
+        .chunks_exact(8)
+        .map(|chunk| f64::from_le_bytes(chunk.try_into().expect("chunks of 8 bytes")))
+        .collect())
+}
+
+/// Check the number of parameters and that all of them are finite.
+pub(crate) fn check_params(params: &[f64], expected: usize) -> Result<(), ParamError> {
+    if params.len() != expected {
+        return Err(ParamError::WrongLength { expected, found: params.len() });
+    }
+    if params.iter().any(|x| !x.is_finite()) {
+        return Err(ParamError::NonFinite);
+    }
+    Ok(())
+}
+
+/// A scale parameter, guaranteed to be finite and strictly positive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale(f64);
+
+impl Scale {
+    /// Validate the given value as a scale parameter.
+    pub fn new(value: f64) -> Result<Self, ParamError> {
+        if !value.is_finite() {
+            Err(ParamError::NonFinite)
+        } else if value <= 0.0 {
+            Err(ParamError::NonPositiveScale)
+        } else {
+            Ok(Scale(value))
+        }
+    }
+
+    /// Obtain the underlying value
+    #[inline(always)]
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+/// A shape parameter, guaranteed to be finite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shape(f64);
+
+impl Shape {
+    /// Validate the given value as a shape parameter.
+    pub fn new(value: f64) -> Result<Self, ParamError> {
+        if value.is_finite() {
+            Ok(Shape(value))
+        } else {
+            Err(ParamError::NonFinite)
+        }
+    }
+
+    /// Obtain the underlying value
+    #[inline(always)]
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_validation_test() {
+        assert_eq!(Scale::new(-1.0), Err(ParamError::NonPositiveScale));
+        assert_eq!(Scale::new(0.0), Err(ParamError::NonPositiveScale));
+        assert_eq!(Scale::new(f64::NAN), Err(ParamError::NonFinite));
+        assert_eq!(Scale::new(2.0).unwrap().get(), 2.0);
+    }
+
+    #[test]
+    fn shape_validation_test() {
+        assert_eq!(Shape::new(f64::INFINITY), Err(ParamError::NonFinite));
+        assert_eq!(Shape::new(-0.5).unwrap().get(), -0.5);
+    }
+}
@@ -0,0 +1,96 @@
+//! Semi-parametric distributions: the empirical distribution of the data in the bulk, spliced with
+//! a Generalized Pareto tail above a high threshold (the usual peaks-over-threshold model).
+use crate::dist::distutils::*;
+use crate::dist::empirical::EmpiricalCdf;
+use crate::dist::gpd::GeneralizedPareto;
+
+/// Empirical CDF below `threshold`, GPD tail above it:
+/// $$ F(x) = (1 - \zeta) \frac{F_n(x)}{F_n(u)} \text{ for } x \leq u, \quad
+///    F(x) = 1 - \zeta \left( 1 - G(x) \right) \text{ for } x > u, $$
+/// with $u$ the threshold, $\zeta$ the tail probability `tail_prob` and $G$ the CDF of the tail GPD.
+/// Both pieces equal $1 - \zeta$ at the threshold, so the CDF is continuous there as long as the GPD
+/// is located at the threshold ($G(u) = 0$), which `new` checks. With $\zeta = 1 - F_n(u)$, the
+/// proportion of exceedances used by `new`, the bulk is exactly the empirical CDF.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplicedDistribution {
+    /// empirical distribution of the data, used below the threshold
+    pub empirical: EmpiricalCdf,
+    /// distribution of the observations above the threshold, located at the threshold
+    pub tail: GeneralizedPareto,
+    /// threshold where the two pieces are joined
+    pub threshold: f64,
+    /// probability $\zeta$ of exceeding the threshold
+    pub tail_prob: f64,
+}
+
+impl SplicedDistribution {
+    /// Splice the empirical CDF and the GPD `tail` at `threshold`, with the proportion of the
+    /// observations above the threshold as tail probability. The GPD must be located at the
+    /// threshold, and the threshold must lie between the smallest and the largest observations.
+    pub fn new(empirical: EmpiricalCdf, tail: GeneralizedPareto, threshold: f64) -> Self {
+        let tail_prob: f64 = 1.0 - empirical.cdf(threshold);
+        domain!(tail.loc == threshold && tail_prob > 0.0 && tail_prob < 1.0);
+        SplicedDistribution{empirical, tail, threshold, tail_prob}
+    }
+
+    /// CDF, the rescaled empirical CDF up to the threshold and the GPD tail above it.
+    pub fn cdf(&self, x: f64) -> f64 {
+        if x <= self.threshold {
+            (1.0 - self.tail_prob) * self.empirical.cdf(x) / self.empirical.cdf(self.threshold)
+        } else if self.tail.is_in_support(x) {
+            1.0 - self.tail_prob * self.tail.sf(x)
+        } else {
+            1.0
+        }
+    }
+
+    /// Quantile, the empirical quantile for $p \leq 1 - \zeta$ and the GPD quantile of the
+    /// conditional exceedance probability above: $G^{-1} \left( 1 - \frac{1 - p}{\zeta} \right)$.
+    pub fn quantile(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        let bulk_prob: f64 = 1.0 - self.tail_prob;
+        if p <= bulk_prob {
+            self.empirical.quantile(p / bulk_prob * self.empirical.cdf(self.threshold))
+        } else {
+            self.tail.isf((1.0 - p) / self.tail_prob)
+        }
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spliced() -> SplicedDistribution {
+        let data: Vec<f64> = (1..=100).map(|i| i as f64).collect();
+        SplicedDistribution::new(EmpiricalCdf::new(&data), GeneralizedPareto::new(90.0, 5.0, 0.1), 90.0)
+    }
+
+    #[test]
+    fn spliced_cdf_test() {
+        let dist: SplicedDistribution = spliced();
+        assert!((dist.tail_prob - 0.1).abs() < 1e-15);
+        // continuous at the threshold
+        assert!((dist.cdf(90.0) - 0.9).abs() < 1e-15);
+        assert!((dist.cdf(90.0 + 1e-9) - dist.cdf(90.0)).abs() < 1e-9);
+        // the empirical CDF below, the scaled GPD above
+        for x in [0.5, 10.0, 45.5, 89.0] {
+            assert!((dist.cdf(x) - dist.empirical.cdf(x)).abs() < 1e-15);
+        }
+        for x in [91.0, 100.0, 150.0] {
+            assert!((dist.cdf(x) - (0.9 + 0.1 * dist.tail.cdf(x))).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn spliced_quantile_test() {
+        let dist: SplicedDistribution = spliced();
+        assert_eq!(dist.quantile(0.0), 1.0);
+        assert_eq!(dist.quantile(0.455), 46.0);
+        assert!((dist.quantile(0.9) - 90.0).abs() < 1e-12);
+        for p in [0.91, 0.99, 0.9999] {
+            assert!((dist.cdf(dist.quantile(p)) - p).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Tail index estimators, computed from the upper order statistics of the data only and thus
+//! assumption-light: quick diagnostics of the shape and initial values for the maximum likelihood fits.
+use libm::log;
+
+/// Pickands' estimator of the shape (extreme value index) from the upper order statistics
+/// $x_{(1)} \leq \cdots \leq x_{(n)}$:
+/// $$ \hat\xi_k = \frac{1}{\log 2} \log \frac{x_{(n-k+1)} - x_{(n-2k+1)}}{x_{(n-2k+1)} - x_{(n-4k+1)}}. $$
+/// It is consistent for any shape (positive, zero or negative, in the sign convention of `GEV`)
+/// as $k \to \infty$ with $k / n \to 0$, but its variance is large: `k` trades the bias of a large
+/// fraction $4k / n$ of the data against the variance of a small `k`.
+/// `k` must satisfy $1 \leq k$ and $4 k \leq n$.
+pub fn pickands_shape(data: &[f64], k: usize) -> f64 {
+    domain!(k >= 1 && 4 * k <= data.len());
+    let mut sorted: Vec<f64> = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let n: usize = sorted.len();
+    let (top, mid, low) = (sorted[n - k], sorted[n - 2 * k], sorted[n - 4 * k]);
+    log((top - mid) / (mid - low)) / log(2.0)
+}
+
+/// Hill's estimator of the tail index $\alpha$ of heavy tailed (Fréchet type, positive shape)
+/// data, from the $k$ largest order statistics above $x_{(n-k)}$:
+/// $$ \hat\gamma_k = \frac{1}{k} \sum_{i=1}^{k} \log \frac{x_{(n-i+1)}}{x_{(n-k)}}, \quad \hat\alpha_k = \frac{1}{\hat\gamma_k}, $$
+/// where $\hat\gamma_k$ estimates the shape of the GEV (and $\hat\alpha_k$ the shape of the Fréchet
+/// distribution). Its standard error is about $\hat\alpha_k / \sqrt{k}$.
+/// `k` must satisfy $1 \leq k < n$ and the threshold $x_{(n-k)}$ must be positive.
+pub fn hill_estimator(data: &[f64], k: usize) -> f64 {
+    domain!(k >= 1 && k < data.len());
+    let mut sorted: Vec<f64> = data.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let n: usize = sorted.len();
+    let threshold: f64 = sorted[n - k - 1];
+    domain!(threshold > 0.0);
+    let gamma: f64 = sorted[(n - k)..].iter().map(|x| log(x / threshold)).sum::<f64>() / k as f64;
+    1.0 / gamma
+}
+
+/// Hill plot: the pairs $(k, \hat\alpha_k)$ of Hill's estimator (see `hill_estimator`) for
+/// $k = 1, \dots, n - 1$, stopping at the first non-positive threshold $x_{(n-k)}$. The tail index
+/// is read off a region of `k` where the estimates are stable (a plateau): small values of `k` are
+/// noisy and large ones are biased by the observations outside of the tail.
+/// The logarithms of the sorted data are summed cumulatively, so the plot takes $O(n \log n)$.
+pub fn hill_plot(data: &[f64]) -> Vec<(usize, f64)> {
+    let mut sorted: Vec<f64> = data.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a)); // decreasing
+    let mut plot: Vec<(usize, f64)> = Vec::new();
+    let mut sum_logs: f64 = 0.0;
+    for k in 1..sorted.len() {
+        let threshold: f64 = sorted[k];
+        if threshold <= 0.0 {
+            break;
+        }
+        sum_logs += log(sorted[k - 1]);
+        // sum over i < k of log(x_(i) / threshold)
+        let gamma: f64 = sum_logs / k as f64 - log(threshold);
+        plot.push((k, 1.0 / gamma));
+    }
+    plot
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::distutils::*;
+    use libm::pow;
+    use crate::dist::frechet::Frechet;
+    use crate::dist::weibull::Weibull;
+    use crate::dist::gumbel::Gumbel;
+
+    #[test]
+    fn pickands_shape_test() {
+        // Fréchet with tail index 2, i.e. shape 1/2
+        let data: Vec<f64> = Frechet::new(0.0, 1.0, 2.0).sample_n(40_000, RandomSeed::Seed(1));
+        assert!((pickands_shape(&data, 1000) - 0.5).abs() < 0.15);
+        // light and bounded tails
+        let data: Vec<f64> = Gumbel::new(0.0, 1.0).sample_n(40_000, RandomSeed::Seed(2));
+        assert!(pickands_shape(&data, 1000).abs() < 0.15);
+        let data: Vec<f64> = Weibull::new(0.0, 1.0, 2.0).sample_n(40_000, RandomSeed::Seed(3));
+        assert!((pickands_shape(&data, 1000) + 0.5).abs() < 0.15);
+        // the estimator only depends on the order statistics, not on the order of the data
+        let data: Vec<f64> = (1..=8).map(|i| (i * i * i) as f64).collect();
+        let reversed: Vec<f64> = data.iter().rev().cloned().collect();
+        assert_eq!(pickands_shape(&data, 2), pickands_shape(&reversed, 2));
+        assert!((pickands_shape(&data, 2) - log((343.0 - 125.0) / (125.0 - 1.0)) / log(2.0)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn hill_estimator_test() {
+        let data: Vec<f64> = Frechet::new(0.0, 1.0, 2.0).sample_n(20_000, RandomSeed::Seed(4));
+        // the plateau of the Hill plot recovers the tail index 2
+        let plot: Vec<(usize, f64)> = hill_plot(&data);
+        assert_eq!(plot.len(), data.len() - 1);
+        for &k in [500, 1000, 1500, 2000].iter() {
+            let (plot_k, estimate) = plot[k - 1];
+            assert_eq!(plot_k, k);
+            assert!((estimate - hill_estimator(&data, k)).abs() < 1e-9);
+            assert!((estimate - 2.0).abs() < 0.25);
+        }
+        // exact on a Pareto sample with x_(n-i+1) / x_(n-k) = ((k + 1) / i)^(1 / alpha)
+        let pareto: Vec<f64> = (1..=11).map(|i| pow(11.0 / i as f64, 0.5)).collect();
+        let gamma: f64 = (1..=10).map(|i| 0.5 * log(11.0 / i as f64)).sum::<f64>() / 10.0;
+        assert!((hill_estimator(&pareto, 10) - 1.0 / gamma).abs() < 1e-12);
+        // the plot stops at the first non-positive threshold
+        assert_eq!(hill_plot(&[3.0, 2.0, 1.0, 0.0, -1.0]).len(), 2);
+    }
+}
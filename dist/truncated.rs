@@ -0,0 +1,138 @@
+//! Distributions truncated to an interval, e.g. for bounded physical quantities.
+use crate::dist::distutils::*;
+use crate::dist::gof::cdf_extended;
+use crate::dist::params::ParamError;
+
+use rand_chacha::ChaCha8Rng;
+use rand::Rng;
+
+/// The distribution `inner` conditioned on lying in $[a, b]$:
+/// $F_T(x) = \frac{F(x) - F(a)}{F(b) - F(a)}$ and $f_T(x) = \frac{f(x)}{F(b) - F(a)}$ for $a \leq x \leq b$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Truncated<D: DistQuant> {
+    inner: D,
+    lower: f64,
+    upper: f64,
+    /// $F(a)$
+    cdf_lower: f64,
+    /// $F(b) - F(a)$, the probability of the interval under `inner`
+    mass: f64,
+}
+
+impl<D: DistQuant> Truncated<D> {
+    /// Truncate `inner` to `[lower, upper]` (the end points may be infinite).
+    /// Returns `ParamError::InvalidInterval` unless `lower < upper` and the interval has a positive
+    /// probability under `inner`.
+    pub fn new(inner: D, lower: f64, upper: f64) -> Result<Self, ParamError> {
+        if lower.is_nan() || upper.is_nan() || lower >= upper {
+            return Err(ParamError::InvalidInterval);
+        }
+        let cdf_lower: f64 = cdf_extended(&inner, lower);
+        let mass: f64 = cdf_extended(&inner, upper) - cdf_lower;
+        if mass.is_nan() || mass <= 0.0 {
+            return Err(ParamError::InvalidInterval);
+        }
+        Ok(Truncated { inner, lower, upper, cdf_lower, mass })
+    }
+
+    /// The distribution before truncation
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    /// The truncation interval `(lower, upper)`
+    pub fn bounds(&self) -> (f64, f64) {
+        (self.lower, self.upper)
+    }
+}
+
+impl<D: DistQuant> DistQuant for Truncated<D> {
+    /// CDF renormalized to the interval: 0 below $a$ and 1 above $b$.
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= self.lower {
+            0.0
+        } else if x >= self.upper {
+            1.0
+        } else {
+            ((cdf_extended(&self.inner, x) - self.cdf_lower) / self.mass).clamp(0.0, 1.0)
+        }
+    }
+
+    /// PDF renormalized to the interval, 0 outside of it (and outside of the support of `inner`).
+    fn pdf(&self, x: f64) -> f64 {
+        if self.lower <= x && x <= self.upper && self.inner.is_in_support(x) {
+            self.inner.pdf(x) / self.mass
+        } else {
+            0.0
+        }
+    }
+
+    /// Quantile by inverting the rescaled uniform: $F^{-1}(F(a) + p (F(b) - F(a)))$, clamped into
+    /// $[a, b]$ against rounding.
+    fn quantile(&self, x: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&x));
+        let prob: f64 = (self.cdf_lower + x * self.mass).clamp(0.0, 1.0);
+        self.inner.quantile(prob).clamp(self.lower, self.upper)
+    }
+
+    /// Support: the intersection of the interval with the support of `inner`.
+    fn support(&self) -> (f64, f64) {
+        let (lower, upper) = self.inner.support();
+        (lower.max(self.lower), upper.min(self.upper))
+    }
+
+    /// Return a randomly generated value from the truncated distribution, by inverse transform
+    /// sampling (see `quantile`), so no draw is ever rejected.
+    fn random(&self, seed: RandomSeed) -> f64 {
+        let mut rng: ChaCha8Rng = seed.rng();
+        self.quantile(rng.gen::<f64>())
+    }
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::gev::GEV;
+    use crate::dist::gumbel::Gumbel;
+
+    #[test]
+    fn truncated_new_test() {
+        let gev: GEV = GEV::new(10.0, 2.0, -0.3);
+        assert_eq!(Truncated::new(gev, 15.0, 8.0), Err(ParamError::InvalidInterval));
+        assert_eq!(Truncated::new(gev, 8.0, 8.0), Err(ParamError::InvalidInterval));
+        assert_eq!(Truncated::new(gev, f64::NAN, 8.0), Err(ParamError::InvalidInterval));
+        // above the upper end point 10 + 2 / 0.3 of the support
+        assert_eq!(Truncated::new(gev, 20.0, 30.0), Err(ParamError::InvalidInterval));
+        let trunc: Truncated<GEV> = Truncated::new(gev, 8.0, f64::INFINITY).unwrap();
+        assert_eq!(trunc.support(), (8.0, gev.support().1));
+        assert_eq!(trunc.bounds(), (8.0, f64::INFINITY));
+        assert_eq!(trunc.inner(), &gev);
+    }
+
+    #[test]
+    fn truncated_cdf_pdf_test() {
+        let gev: GEV = GEV::new(10.0, 2.0, 0.2);
+        let trunc: Truncated<GEV> = Truncated::new(gev, 8.0, 15.0).unwrap();
+        let mass: f64 = gev.cdf(15.0) - gev.cdf(8.0);
+        assert_eq!(trunc.cdf(7.0), 0.0);
+        assert_eq!(trunc.cdf(15.0), 1.0);
+        assert!((trunc.cdf(11.0) - (gev.cdf(11.0) - gev.cdf(8.0)) / mass).abs() < 1e-15);
+        assert!((trunc.pdf(11.0) - gev.pdf(11.0) / mass).abs() < 1e-15);
+        assert_eq!(trunc.pdf(16.0), 0.0);
+        assert!((trunc.integrate_pdf(8.0, 15.0, 2000) - 1.0).abs() < 1e-10);
+        for p in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            assert!((trunc.cdf(trunc.quantile(p)) - p).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn truncated_random_test() {
+        let trunc: Truncated<Gumbel> = Truncated::new(Gumbel::new(0.5, 2.0), -1.0, 2.0).unwrap();
+        let sample: Vec<f64> = trunc.sample_n(10_000, RandomSeed::Seed(1));
+        assert!(sample.iter().all(|&x| (-1.0..=2.0).contains(&x)));
+        let below: f64 = sample.iter().filter(|&&x| x < 0.5).count() as f64 / 10_000.0;
+        assert!((below - trunc.cdf(0.5)).abs() < 0.02);
+        assert!(trunc.random(RandomSeed::Seed(2)) <= 2.0);
+    }
+}
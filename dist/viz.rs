@@ -0,0 +1,72 @@
+//! Helpers to produce plotting data, e.g. to compare the data with a fitted distribution.
+
+/// Histogram of the data with `bins` equally wide bins spanning $[\min_i x_i, \max_i x_i]$.
+/// Returns the `bins + 1` bin edges and the `bins` densities, normalized so that the histogram
+/// integrates to 1 and can be overlaid with the fitted PDF (see `DistQuant::pdf_over` and `bin_centers`).
+/// If all the observations are equal, the bins span $[x - 0.5, x + 0.5]$ instead.
+pub fn histogram(data: &[f64], bins: usize) -> (Vec<f64>, Vec<f64>) {
+    domain!(!data.is_empty() && bins > 0);
+    let mut lo: f64 = data.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mut hi: f64 = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if lo == hi {
+        lo -= 0.5;
+        hi += 0.5;
+    }
+    let width: f64 = (hi - lo) / bins as f64;
+    let edges: Vec<f64> = (0..=bins).map(|i| lo + width * i as f64).collect();
+
+    let mut counts: Vec<usize> = vec![0; bins];
+    for x in data {
+        // the maximum falls in the last bin
+        let index: usize = (((x - lo) / width) as usize).min(bins - 1);
+        counts[index] += 1;
+    }
+    let norm: f64 = data.len() as f64 * width;
+    let densities: Vec<f64> = counts.iter().map(|&c| c as f64 / norm).collect();
+    (edges, densities)
+}
+
+/// Centers of the bins defined by the given edges.
+pub fn bin_centers(edges: &[f64]) -> Vec<f64> {
+    edges.windows(2).map(|pair| 0.5 * (pair[0] + pair[1])).collect()
+}
+
+/// Rescale the PDF values `pdf` by `peak_height / pdf_max`, so that the peak of the density is
+/// drawn at `peak_height` (see e.g. `GEV::pdf_scaled`). For an unbounded density ($pdf\_max = \infty$)
+/// the largest of the values is used instead, so that the returned maximum is still `peak_height`.
+pub(crate) fn scale_to_peak(pdf: Vec<f64>, pdf_max: f64, peak_height: f64) -> Vec<f64> {
+    let peak: f64 = if pdf_max.is_finite() { pdf_max } else { pdf.iter().cloned().fold(0.0, f64::max) };
+    pdf.into_iter().map(|f| f * peak_height / peak).collect()
+}
+
+/// tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::distutils::*;
+    use crate::dist::gumbel::Gumbel;
+
+    #[test]
+    fn histogram_test() {
+        let gumb: Gumbel = Gumbel::new(0.5, 2.0);
+        let data: Vec<f64> = gumb.sample_n(5000, RandomSeed::Seed(3));
+        let (edges, densities) = histogram(&data, 30);
+        assert_eq!(edges.len(), 31);
+        assert_eq!(densities.len(), 30);
+        let integral: f64 = edges.windows(2).zip(&densities).map(|(pair, d)| (pair[1] - pair[0]) * d).sum();
+        assert!((integral - 1.0).abs() < 1e-12);
+
+        // the histogram is close to the pdf where there is enough data
+        let centers: Vec<f64> = bin_centers(&edges);
+        let pdf: Vec<f64> = gumb.pdf_over(&centers);
+        let peak: usize = (0..30).max_by(|&a, &b| pdf[a].total_cmp(&pdf[b])).unwrap();
+        assert!((densities[peak] - pdf[peak]).abs() < 0.03);
+    }
+
+    #[test]
+    fn histogram_constant_data_test() {
+        let (edges, densities) = histogram(&[2.0; 10], 4);
+        assert_eq!(edges, vec![1.5, 1.75, 2.0, 2.25, 2.5]);
+        assert_eq!(densities, vec![0.0, 0.0, 4.0, 0.0]);
+    }
+}
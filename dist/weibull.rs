@@ -1,15 +1,23 @@
 //! The (Inverse) Weibull Distribution. The Weibull distribution in the extreme value context is in
 //! fact the Inverse Weibull distribution.
-use libm::{exp, log, pow};
+use std::fmt;
+
+use libm::{exp, log, log1p, nextafter, pow, tgamma};
 
 use crate::dist::distutils::*;
+use crate::dist::viz::scale_to_peak;
+use crate::dist::constants::EULER_GAMMA;
+use crate::dist::fit::{check_data, nelder_mead, FitError, FitMethod, Fittable};
+use crate::dist::frechet::Frechet;
+use crate::dist::gev::GEV;
+use crate::dist::params::{check_params, decode, encode, DecodeError, ParamError};
 
 use rand_chacha::ChaCha8Rng;
 use rand::SeedableRng;
 use rand::Rng;
 
 /// Fréchet Dist. struct
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Weibull {
     /// location parameter
     pub loc:   f64,
@@ -28,15 +36,70 @@ impl Weibull {
         Weibull{loc, scale, shape}
     }
 
-    /// Obtain the location parameter
-    #[inline(always)]
-    pub fn loc(&self) -> f64 {
-        self.loc
+    /// Parameters packed into a vector, in the order (loc, scale, shape).
+    pub fn as_params(&self) -> Vec<f64> {
+        vec![self.loc, self.scale, self.shape]
     }
 
-    /// Obtain the scale parameter
-    pub fn scale(&self) -> f64 {
-        self.scale
+    /// Bounds `(lo, hi)` of each parameter, in the order (loc, scale, shape) of `as_params`, for bounded
+    /// optimizers. The scale and the shape, both strictly positive, are bounded below by `f64::EPSILON`.
+    pub fn param_bounds() -> Vec<(f64, f64)> {
+        vec![(f64::NEG_INFINITY, f64::INFINITY), (f64::EPSILON, f64::INFINITY), (f64::EPSILON, f64::INFINITY)]
+    }
+
+    /// Create the distribution from parameters packed in the order (loc, scale, shape), e.g. by an optimizer.
+    /// Exactly 3 finite parameters are required and the scale must be positive as well as the shape.
+    pub fn from_params(params: &[f64]) -> Result<Self, ParamError> {
+        check_params(params, 3)?;
+        if params[1] <= 0.0 {
+            return Err(ParamError::NonPositiveScale);
+        }
+        if params[2] <= 0.0 {
+            return Err(ParamError::NonPositiveShape);
+        }
+        Ok(Weibull{ loc: params[0], scale: params[1], shape: params[2] })
+    }
+
+    /// Distribution of $a X + b$ for $X$ following this distribution, $a > 0$: the location becomes
+    /// $a \cdot loc + b$ and the scale $a \cdot scale$. The resulting scale must be finite and
+    /// positive (checked by `domain!`, see `try_affine` for the checked version).
+    pub fn affine(&self, a: f64, b: f64) -> Self {
+        let dist: Weibull = Weibull { loc: a * self.loc + b, scale: a * self.scale, shape: self.shape };
+        domain!(dist.loc.is_finite() && dist.scale.is_finite() && dist.scale > 0.0);
+        dist
+    }
+
+    /// Checked version of `affine`, see `ParamError`.
+    pub fn try_affine(&self, a: f64, b: f64) -> Result<Self, ParamError> {
+        Weibull::from_params(&[a * self.loc + b, a * self.scale, self.shape])
+    }
+
+    /// The same distribution with the given scale parameter, which must be finite and positive
+    /// (checked by `domain!`, see `try_with_scale` for the checked version).
+    pub fn with_scale(&self, scale: f64) -> Self {
+        domain!(scale.is_finite() && scale > 0.0);
+        Weibull { scale, ..*self }
+    }
+
+    /// Checked version of `with_scale`, see `ParamError`.
+    pub fn try_with_scale(&self, scale: f64) -> Result<Self, ParamError> {
+        Weibull::from_params(&[self.loc, scale, self.shape])
+    }
+
+    /// Tag byte identifying the Weibull distribution in its binary form.
+    pub const TAG: u8 = 2;
+
+    /// Compact binary form: the tag byte `Weibull::TAG` followed by the 3 parameters (see `as_params`)
+    /// as little-endian `f64`s.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        encode(Weibull::TAG, &self.as_params())
+    }
+
+    /// Decode the distribution from its binary form (see `to_le_bytes`), validating the length,
+    /// the tag and the parameters.
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let params: Vec<f64> = decode(bytes, Weibull::TAG, 3)?;
+        Weibull::from_params(&params).map_err(DecodeError::InvalidParams)
     }
 
     /// Obtain the shape parameter
@@ -44,6 +107,222 @@ impl Weibull {
         self.shape
     }
 
+    /// Create the (inverse) Weibull distribution going through the three given (probability, quantile)
+    /// pairs, by solving for the GEV (see `GEV::from_quantiles`) and mapping it to the Weibull parameters
+    /// ($shape = -1 / \zeta$, $scale = - \sigma / \zeta$, $loc = \mu + scale$).
+    /// Returns `FitError::InvalidInput` if the implied GEV shape is not negative.
+    pub fn from_quantiles(quantiles: &[(f64, f64); 3]) -> Result<Weibull, FitError> {
+        let gev: GEV = GEV::from_quantiles(quantiles)?;
+        if gev.shape() >= 0.0 {
+            return Err(FitError::InvalidInput);
+        }
+        let scale: f64 = - gev.scale() / gev.shape();
+        Ok(Weibull { loc: gev.loc() + scale, scale, shape: - 1.0 / gev.shape() })
+    }
+
+    /// Move a quantile that rounded onto the end point $loc$ to the first float below it,
+    /// when `inside` (the probability is below 1) and the quantile must lie in the open support.
+    fn stay_inside(&self, x: f64, inside: bool) -> f64 {
+        if inside && x >= self.loc { nextafter(self.loc, f64::NEG_INFINITY) } else { x }
+    }
+
+    /// Mode of the (inverse) Weibull distribution, $loc - scale \left( \frac{shape - 1}{shape} \right)^{1 / shape}$
+    /// for $shape > 1$, and the upper end point $loc$ of the support for $shape \leq 1$.
+    pub fn mode(&self) -> f64 {
+        if self.shape > 1.0 {
+            self.loc - self.scale * pow((self.shape - 1.0) / self.shape, 1.0 / self.shape)
+        } else {
+            self.loc
+        }
+    }
+
+    /// Maximum value of the PDF, attained at the mode.
+    /// For $shape = 1$ it is the limit $\frac{1}{scale}$ at $loc$, and for $shape < 1$ the density is
+    /// unbounded at $loc$, so `f64::INFINITY` is returned.
+    pub fn pdf_max(&self) -> f64 {
+        if self.shape > 1.0 {
+            self.pdf(self.mode())
+        } else if self.shape == 1.0 {
+            1.0 / self.scale
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// PDF over `xs` scaled so that its peak `pdf_max` has the height `peak_height`, e.g. to overlay
+    /// the fitted density on a histogram with an arbitrary vertical scale. The maximum of the
+    /// returned values is `peak_height` when `xs` contains the mode.
+    /// For $shape < 1$, where the density is unbounded, the largest value over `xs` is scaled instead.
+    pub fn pdf_scaled(&self, xs: &[f64], peak_height: f64) -> Vec<f64> {
+        scale_to_peak(self.pdf_over(xs), self.pdf_max(), peak_height)
+    }
+
+    /// $g_k = \Gamma(1 + k / shape)$, the building block of the Weibull moments.
+    #[inline(always)]
+    fn g_k(&self, k: f64) -> f64 {
+        tgamma(1.0 + k / self.shape)
+    }
+
+    /// Hazard rate of the usual (reliability) Weibull lifetime $T = loc - X$ at $t > 0$,
+    /// $h_T(t) = \frac{shape}{scale} \left( \frac{t}{scale} \right)^{shape - 1}$, computed as the reversed
+    /// hazard $\frac{f(loc - t)}{F(loc - t)}$ of this (reversed) distribution. It is increasing for
+    /// $shape > 1$ (wear-out), constant for $shape = 1$ and decreasing for $shape < 1$ (infant mortality).
+    /// The hazard of $X$ itself (`DistQuant::hazard`) increases towards the upper end point whatever the shape.
+    pub fn reliability_hazard(&self, t: f64) -> f64 {
+        domain!(t > 0.0);
+        let x: f64 = self.loc - t;
+        self.pdf(x) / self.cdf(x)
+    }
+
+    /// Reciprocal duality with the Fréchet distribution: if $X$ follows this (reversed) Weibull
+    /// distribution, then $Z = \frac{1}{loc - X}$ follows the Fréchet distribution with location 0,
+    /// scale $1 / scale$ and the same shape, since
+    /// $P(Z \leq z) = P(X \leq loc - 1/z) = \exp \{ - (z \cdot scale)^{-shape} \}$.
+    /// See `Frechet::to_weibull` for the inverse mapping.
+    pub fn to_frechet(&self) -> Frechet {
+        Frechet::new(0.0, 1.0 / self.scale, self.shape)
+    }
+
+    /// Whether the moment of the given order is finite: always, as the support is bounded above
+    /// and the lower tail decays exponentially.
+    pub fn moment_exists(&self, _order: u32) -> bool {
+        true
+    }
+
+    /// Mean of the (inverse) Weibull distribution, $loc - scale \cdot g_1$.
+    pub fn mean(&self) -> f64 {
+        self.loc - self.scale * self.g_k(1.0)
+    }
+
+    /// Variance of the (inverse) Weibull distribution, $scale^2 (g_2 - g_1^2)$.
+    pub fn variance(&self) -> f64 {
+        let g1: f64 = self.g_k(1.0);
+        self.scale * self.scale * (self.g_k(2.0) - g1 * g1)
+    }
+
+    /// Median of the (inverse) Weibull distribution, $loc - scale (\log 2)^{1 / shape}$.
+    pub fn median(&self) -> f64 {
+        self.loc - self.scale * pow(log(2.0), 1.0 / self.shape)
+    }
+
+    /// Differential entropy of the (inverse) Weibull distribution, that of the usual Weibull
+    /// distribution: $\gamma \left( 1 - \frac{1}{shape} \right) + \log \frac{scale}{shape} + 1$.
+    pub fn entropy(&self) -> f64 {
+        EULER_GAMMA * (1.0 - 1.0 / self.shape) + log(self.scale / self.shape) + 1.0
+    }
+
+    /// Summary of the distribution, all the moments existing.
+    pub fn summary(&self) -> DistSummary {
+        DistSummary {
+            mean: Some(self.mean()),
+            variance: Some(self.variance()),
+            median: self.median(),
+            mode: self.mode(),
+            skewness: Some(self.skewness()),
+            support: self.support(),
+            entropy: self.entropy(),
+        }
+    }
+
+    /// Skewness of the (inverse) Weibull distribution,
+    /// $$ - \frac{g_3 - 3 g_1 g_2 + 2 g_1^3}{(g_2 - g_1^2)^{3/2}}, \quad g_k = \Gamma(1 + k / shape), $$
+    /// i.e. minus the skewness of the usual Weibull since the distribution is reflected around $loc$.
+    /// All the moments exist for any $shape > 0$.
+    pub fn skewness(&self) -> f64 {
+        let (g1, g2, g3) = (self.g_k(1.0), self.g_k(2.0), self.g_k(3.0));
+        - (g3 - 3.0 * g1 * g2 + 2.0 * pow(g1, 3.0)) / pow(g2 - g1 * g1, 1.5)
+    }
+
+    /// Excess kurtosis of the (inverse) Weibull distribution,
+    /// $$ \frac{g_4 - 4 g_1 g_3 + 6 g_1^2 g_2 - 3 g_1^4}{(g_2 - g_1^2)^2} - 3, \quad g_k = \Gamma(1 + k / shape). $$
+    /// All the moments exist for any $shape > 0$.
+    pub fn kurtosis(&self) -> f64 {
+        let (g1, g2, g3, g4) = (self.g_k(1.0), self.g_k(2.0), self.g_k(3.0), self.g_k(4.0));
+        let num: f64 = g4 - 4.0 * g1 * g3 + 6.0 * g1 * g1 * g2 - 3.0 * pow(g1, 4.0);
+        num / pow(g2 - g1 * g1, 2.0) - 3.0
+    }
+
+    /// Log-likelihood of the data under the (inverse) Weibull distribution,
+    /// $$ \ell = \sum_i \left( \log \frac{shape}{scale} + (shape - 1) \log w_i - w_i^{shape} \right), \quad w_i = \frac{loc - x_i}{scale}. $$
+    /// Returns $-\infty$ if an observation is not below $loc$ or if a parameter is not positive.
+    pub fn log_likelihood(&self, data: &[f64]) -> f64 {
+        if self.scale <= 0.0 || self.shape <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let log_const: f64 = log(self.shape / self.scale);
+        let mut total: f64 = 0.0;
+        for &x in data {
+            let w: f64 = (self.loc - x) / self.scale;
+            if w <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            total += log_const + (self.shape - 1.0) * log(w) - pow(w, self.shape);
+        }
+        total
+    }
+
+    /// Fit the (inverse) Weibull distribution to the data by maximum likelihood over the three
+    /// parameters. The optimizer works on $(\log(loc - \max_i x_i), \log scale, \log shape)$, so
+    /// that all the data always lie strictly below $loc$, and is started from the L-moment (PWM)
+    /// GEV fit mapped to the Weibull parameters ($shape = -1 / \zeta$, $scale = - \sigma / \zeta$, $loc = \mu + scale$).
+    ///
+    /// For $shape < 1$ the likelihood is unbounded as $loc$ approaches the sample maximum, so no
+    /// maximum likelihood estimate exists; if the fitted $loc$ collapses onto the sample maximum,
+    /// `FitError::NoConvergence` is returned instead of a degenerate distribution.
+    /// At least 3 finite observations are required.
+    pub fn fit_mle(data: &[f64]) -> Result<Weibull, FitError> {
+        check_data(data, 3)?;
+        let max: f64 = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let init: GEV = GEV::fit_pwm(data)?;
+
+        // an L-moment fit with a non-negative shape is not of Weibull type, use a default shape instead
+        let shape_init: f64 = if init.shape() < -0.05 { - 1.0 / init.shape() } else { 3.0 };
+        let scale_init: f64 = init.scale() * shape_init;
+        let offset_init: f64 = (init.loc() + scale_init - max).max(0.05 * scale_init);
+
+        let neg_lik = |p: &[f64]| - Weibull { loc: max + exp(p[0]), scale: exp(p[1]), shape: exp(p[2]) }.log_likelihood(data);
+        let start: [f64; 3] = [log(offset_init), log(scale_init), log(shape_init)];
+        let (params, _) = nelder_mead(neg_lik, &start, &[0.2, 0.1, 0.1], 5000, 1e-12).ok_or(FitError::NoConvergence)?;
+
+        let fitted: Weibull = Weibull { loc: max + exp(params[0]), scale: exp(params[1]), shape: exp(params[2]) };
+        let collapsed: bool = fitted.loc - max <= 1e-8 * fitted.scale;
+        if collapsed || !fitted.loc.is_finite() || !fitted.scale.is_finite() || !fitted.shape.is_finite() {
+            Err(FitError::NoConvergence)
+        } else {
+            Ok(fitted)
+        }
+    }
+
+}
+
+/// Fitting methods available for the Weibull Distribution: maximum likelihood.
+impl Fittable for Weibull {
+    fn fit(data: &[f64], method: FitMethod) -> Result<Self, FitError> {
+        match method {
+            FitMethod::Mle => Weibull::fit_mle(data),
+            FitMethod::Pwm | FitMethod::MomentMatching => Err(FitError::UnsupportedMethod),
+        }
+    }
+}
+
+impl fmt::Display for Weibull {
+    /// Format as `Weibull(loc=..., scale=..., shape=...)`, honoring the precision of the formatter
+    /// (e.g. `format!("{:.2}", dist)`), the parameters being printed in full by default.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_params(f, "Weibull", &[("loc", self.loc), ("scale", self.scale), ("shape", self.shape)])
+    }
+}
+
+impl LocationScale for Weibull {
+    #[inline(always)]
+    fn loc(&self) -> f64 {
+        self.loc
+    }
+
+    #[inline(always)]
+    fn scale(&self) -> f64 {
+        self.scale
+    }
 }
 
 impl DistQuant for Weibull {
@@ -51,24 +330,68 @@ impl DistQuant for Weibull {
     /// for $x < loc$, $loc \in \mathbb{R}$, $scale > 0$ and $shape > 0$.
     fn cdf(&self, x: f64) -> f64 {
         domain!(x < self.loc && self.scale > 0.0 && self.shape > 0.0);
-        let y: f64 = (x - self.loc) / self.scale;
+        let y: f64 = self.reduced(x);
         exp(- pow(-y, self.shape))
     }
+
+    /// Logarithm of the CDF, exactly $\log F(x) = - \left( - \frac{x - loc}{scale} \right)^{shape}$.
+    fn log_cdf(&self, x: f64) -> f64 {
+        domain!(x < self.loc && self.scale > 0.0 && self.shape > 0.0);
+        - pow(- self.reduced(x), self.shape)
+    }
+
+    /// Logarithm of the survival function, $\log(1 - e^{-t})$ with
+    /// $\log t = shape \log \left( - \frac{x - loc}{scale} \right)$, accurate near the end point $loc$.
+    fn log_sf(&self, x: f64) -> f64 {
+        domain!(x < self.loc && self.scale > 0.0 && self.shape > 0.0);
+        log1mexp(self.shape * log(- self.reduced(x)))
+    }
     
     /// PDF of the Weibull distribution.
     /// $$f(x) = \frac{shape}{scale} \left ( - \frac{x - loc}{scale} \right)^{shape -1} \cdot F(x) $$
     fn pdf(&self, x: f64) -> f64 {
         domain!(x < self.loc && self.scale > 0.0 && self.shape > 0.0);
-        let y: f64 = (x - self.loc) / self.scale;
+        let y: f64 = self.reduced(x);
         let pow_const: f64 = self.shape / self.scale;
         pow_const * pow(-y, self.shape- 1.0 ) * exp(- pow(-y, self.shape))
     }
 
     /// Quantile (inverse CDF) function.
     /// $F^{-1}(x) = - scale \cdot \left(\log x  \right)^{\frac{1}{shape}} + loc$
+    /// Near $x = 1$, $\log x$ keeps all its digits since $x - 1$ is exact, but the offset from
+    /// $loc$ can be smaller than the spacing of the floats around $loc$ (for a small shape), and
+    /// the difference would round onto the end point $loc$, where the CDF is not defined.
+    /// The quantile of a probability below 1 is kept inside the support, at worst on the first
+    /// float below $loc$ (see `stay_inside`).
     fn quantile(&self, x: f64) -> f64 {
-        domain!(x >= 0.0 && x <= 1.0);
-        self.loc - self.scale * pow(-log(x), 1.0 / self.shape)
+        domain!((0.0..=1.0).contains(&x));
+        self.stay_inside(self.loc - self.scale * pow(-log(x), 1.0 / self.shape), x < 1.0)
+    }
+
+    /// Inverse survival function $S^{-1}(p) = loc - scale \left(- \mathrm{log1p}(- p) \right)^{\frac{1}{shape}}$,
+    /// kept inside the support like `quantile`.
+    fn isf(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        self.stay_inside(self.loc - self.scale * pow(- log1p(- p), 1.0 / self.shape), p > 0.0)
+    }
+
+    /// Density quantile function $\frac{dQ}{dp} = \frac{scale}{shape \cdot p} (- \log p)^{\frac{1}{shape} - 1}$,
+    /// infinite at $p = 0$. At $p = 1$ (the end point $loc$) it is infinite for $shape > 1$, $scale$
+    /// for $shape = 1$ and 0 for $shape < 1$ (where the density is unbounded).
+    fn quantile_density(&self, p: f64) -> f64 {
+        domain!((0.0..=1.0).contains(&p));
+        if p == 0.0 {
+            return f64::INFINITY;
+        }
+        if p == 1.0 {
+            return if self.shape > 1.0 { f64::INFINITY } else if self.shape == 1.0 { self.scale } else { 0.0 };
+        }
+        self.scale / (self.shape * p) * pow(- log(p), 1.0 / self.shape - 1.0)
+    }
+
+    /// Support: $(-\infty, loc)$
+    fn support(&self) -> (f64, f64) {
+        (f64::NEG_INFINITY, self.loc)
     }
 
     /// Return a randomly generated value from the Weibull distribution.
@@ -117,4 +440,155 @@ mod tests {
         let quant_weibull: f64 = weib.quantile(0.7);
         assert_eq!(ans, quant_weibull);
     }
+
+    #[test]
+    fn weibull_quantile_round_trip_test() {
+        let probs: Vec<f64> = (1..=10).flat_map(|k| [pow(10.0, - k as f64), 1.0 - pow(10.0, - k as f64)]).chain([0.5]).collect();
+        for &(loc, shape) in [(0.0, 0.3), (0.0, 3.0), (1.0, 1.0), (1.0, 3.0), (-5.0, 20.0)].iter() {
+            let weib: Weibull = new_weibull!(loc, 2.0, shape);
+            for &p in probs.iter() {
+                assert!((weib.cdf(weib.quantile(p)) - p).abs() < 1e-9);
+                assert!((weib.sf(weib.isf(p)) - p).abs() < 1e-9);
+            }
+        }
+        // the quantile of a probability close to 1 rounds onto loc = 1 for a small shape: it is kept
+        // inside the support, although the closest float cannot reproduce the probability
+        let weib: Weibull = new_weibull!(1.0, 2.0, 0.3);
+        assert_eq!(weib.quantile(1.0 - 1e-10), nextafter(1.0, 0.0));
+        assert!(weib.cdf(weib.quantile(1.0 - 1e-10)) < 1.0);
+        assert_eq!(weib.quantile(1.0), 1.0);
+    }
+
+    #[test]
+    fn weibull_param_bounds_test() {
+        let bounds: Vec<(f64, f64)> = Weibull::param_bounds();
+        assert_eq!(bounds.len(), 3);
+        // the lower bounds are valid parameters, and the values just outside are rejected
+        assert!(Weibull::from_params(&[0.5, f64::EPSILON, f64::EPSILON]).is_ok());
+        assert!(Weibull::from_params(&[0.5, 2.0, 0.0]).is_err());
+        assert!(bounds.iter().all(|(lo, hi)| lo < hi));
+    }
+
+    #[test]
+    fn weibull_params_test() {
+        let weib: Weibull = new_weibull!(2.0, 2.0, 2.0);
+        assert_eq!(weib.as_params(), vec![2.0, 2.0, 2.0]);
+        assert_eq!(Weibull::from_params(&weib.as_params()), Ok(weib));
+        assert_eq!(Weibull::from_params(&[]), Err(ParamError::WrongLength { expected: 3, found: 0 }));
+        assert_eq!(Weibull::from_params(&[2.0, 0.0, 2.0]), Err(ParamError::NonPositiveScale));
+    }
+
+    #[test]
+    fn weibull_le_bytes_test() {
+        let weib: Weibull = new_weibull!(2.0, 2.0, 2.0);
+        assert_eq!(Weibull::from_le_bytes(&weib.to_le_bytes()), Ok(weib));
+        assert_eq!(Weibull::from_le_bytes(&weib.to_le_bytes()[..1]), Err(DecodeError::WrongLength { expected: 25, found: 1 }));
+    }
+
+    #[test]
+    fn weibull_from_quantiles_test() {
+        let orig: Weibull = new_weibull!(2.0, 2.0, 2.0);
+        let points: [(f64, f64); 3] = [(0.1, orig.quantile(0.1)), (0.5, orig.quantile(0.5)), (0.99, orig.quantile(0.99))];
+        let weib: Weibull = Weibull::from_quantiles(&points).unwrap();
+        for (p, q) in points {
+            assert!((weib.quantile(p) - q).abs() < 1e-8);
+        }
+        assert!((weib.shape() - 2.0).abs() < 1e-6);
+        assert!((weib.loc() - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn weibull_pdf_max_test() {
+        let weib: Weibull = new_weibull!(2.0, 2.0, 2.0);
+        let grid_max: f64 = (1..20001).map(|i| weib.pdf(2.0 - 0.0005 * i as f64)).fold(0.0, f64::max);
+        assert!((weib.pdf_max() - grid_max).abs() < 1e-6);
+        assert!(weib.pdf_max() >= grid_max);
+        assert_eq!(new_weibull!(2.0, 2.0, 1.0).pdf_max(), 0.5);
+        assert_eq!(new_weibull!(2.0, 2.0, 0.5).pdf_max(), f64::INFINITY);
+    }
+
+    #[test]
+    fn weibull_hazard_test() {
+        let times: [f64; 4] = [0.1, 0.5, 1.0, 2.0];
+        let hazards = |weib: Weibull| -> Vec<f64> { times.iter().map(|&t| weib.reliability_hazard(t)).collect() };
+        let increasing: Vec<f64> = hazards(new_weibull!(1.0, 0.5, 2.0));
+        assert!(increasing.windows(2).all(|w| w[0] < w[1]));
+        let decreasing: Vec<f64> = hazards(new_weibull!(1.0, 0.5, 0.5));
+        assert!(decreasing.windows(2).all(|w| w[0] > w[1]));
+        assert!(hazards(new_weibull!(1.0, 0.5, 1.0)).iter().all(|h| (h - 2.0).abs() < 1e-12));
+        // closed form
+        assert!((increasing[2] - 2.0 / 0.5 * (1.0 / 0.5)).abs() < 1e-12);
+        // the hazard of the reversed distribution increases towards loc
+        let weib: Weibull = new_weibull!(1.0, 0.5, 0.5);
+        assert!(weib.hazard_over(&[-1.0, 0.0, 0.9]).windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn weibull_to_frechet_test() {
+        let weib: Weibull = new_weibull!(1.0, 0.5, 2.5);
+        let frech: Frechet = weib.to_frechet();
+        for x in [-2.0, -0.5, 0.0, 0.5, 0.9, 0.99] {
+            let z: f64 = 1.0 / (1.0 - x);
+            assert!((weib.cdf(x) - frech.cdf(z)).abs() < 1e-14);
+        }
+        // back to the Weibull distribution, located at 0
+        assert_eq!(frech.to_weibull(), new_weibull!(0.0, 0.5, 2.5));
+    }
+
+    #[test]
+    fn weibull_summary_test() {
+        let weib: Weibull = new_weibull!(1.0, 2.0, 3.0);
+        let summary: DistSummary = weib.summary();
+        assert_eq!(summary.skewness, Some(weib.skewness()));
+        assert_eq!(summary.support, (f64::NEG_INFINITY, 1.0));
+        // the same as the equivalent GEV distribution
+        let gev: GEV = GEV::new(1.0 - 2.0, 2.0 / 3.0, - 1.0 / 3.0);
+        assert!((summary.mean.unwrap() - gev.mean().unwrap()).abs() < 1e-12);
+        assert!((summary.variance.unwrap() - gev.variance().unwrap()).abs() < 1e-12);
+        assert!((summary.skewness.unwrap() - gev.skewness().unwrap()).abs() < 1e-12);
+        assert!((summary.entropy - gev.entropy()).abs() < 1e-12);
+        assert!((summary.median - gev.median()).abs() < 1e-12);
+        assert!((summary.mode - gev.mode()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn weibull_moment_exists_test() {
+        for shape in [0.1, 1.0, 5.0] {
+            let weib: Weibull = new_weibull!(1.0, 0.5, shape);
+            assert!((0..10).all(|order| weib.moment_exists(order)));
+        }
+    }
+
+    #[test]
+    fn weibull_skewness_test() {
+        let weib: Weibull = new_weibull!(2.0, 2.0, 2.0);
+        let ans: f64 = -0.631110657818942;
+        assert!((weib.skewness() - ans).abs() < 1e-10);
+        // shape = 1 is the reflected exponential distribution
+        assert!((new_weibull!(0.0, 1.0, 1.0).skewness() + 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn weibull_kurtosis_test() {
+        let weib: Weibull = new_weibull!(2.0, 2.0, 2.0);
+        let ans: f64 = 0.245089300687638;
+        assert!((weib.kurtosis() - ans).abs() < 1e-10);
+        assert!((new_weibull!(0.0, 1.0, 1.0).kurtosis() - 6.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn weibull_fit_mle_test() {
+        let weib: Weibull = new_weibull!(5.0, 2.0, 3.0);
+        let data: Vec<f64> = (0..3000).map(|i| weib.random(RandomSeed::Seed(i))).collect();
+        let fitted: Weibull = Weibull::fit_mle(&data).unwrap();
+        assert!(data.iter().all(|&x| x < fitted.loc()));
+        assert!((fitted.loc() - 5.0).abs() < 0.2);
+        assert!((fitted.scale() - 2.0).abs() < 0.2);
+        assert!((fitted.shape() - 3.0).abs() < 0.3);
+    }
+
+    #[test]
+    fn weibull_fit_mle_insufficient_data_test() {
+        assert_eq!(Weibull::fit_mle(&[1.0]).err(), Some(FitError::InsufficientData));
+    }
 }
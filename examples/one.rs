@@ -1,5 +1,3 @@
-use gevlib;
-
 use gevlib::dist::gumbel::Gumbel;
 use gevlib::dist::distutils::*;
 
@@ -1,3 +1,4 @@
 //! lib.rs file
 
 pub mod dist;
+pub mod prelude;
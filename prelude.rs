@@ -0,0 +1,18 @@
+//! The prelude, re-exporting the commonly used items so that a single import is enough:
+//!
+//! ```
+//! use gevlib::prelude::*;
+//!
+//! let gumbel: Gumbel = Gumbel::new(0.5, 2.0);
+//! let cdf: f64 = gumbel.cdf(2.0);
+//! assert!(cdf > 0.0 && cdf < 1.0);
+//! let draw: f64 = GEV::new(0.0, 1.0, 0.1).random(RandomSeed::Seed(1));
+//! assert!(draw.is_finite());
+//! ```
+
+pub use crate::dist::distutils::{DistQuant, EvalError, LocationScale, RandomSeed};
+pub use crate::dist::gumbel::Gumbel;
+pub use crate::dist::frechet::Frechet;
+pub use crate::dist::weibull::Weibull;
+pub use crate::dist::gev::GEV;
+pub use crate::dist::gpd::GeneralizedPareto;